@@ -0,0 +1,113 @@
+use std::{fmt, process::Command, str::FromStr};
+
+/// CPU cores a process may run on, given as for `taskset -c`, e.g. `0-3,6`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoreSet {
+    cores: Vec<usize>,
+}
+
+impl CoreSet {
+    /// Cores found in both sets.
+    pub fn overlap(&self, other: &CoreSet) -> Vec<usize> {
+        self.cores
+            .iter()
+            .filter(|core| other.cores.contains(core))
+            .copied()
+            .collect()
+    }
+
+    /// Wrap `command` in `taskset` so that it only runs on these cores.
+    /// `taskset` execs, the pid stays the one of the process.
+    pub fn wrap(&self, command: &Command) -> Command {
+        let mut wrapped = Command::new("taskset");
+        wrapped
+            .arg("-c")
+            .arg(self.to_string())
+            .arg(command.get_program())
+            .args(command.get_args());
+
+        if let Some(dir) = command.get_current_dir() {
+            wrapped.current_dir(dir);
+        }
+        for (key, value) in command.get_envs() {
+            match value {
+                Some(value) => wrapped.env(key, value),
+                None => wrapped.env_remove(key),
+            };
+        }
+
+        wrapped
+    }
+}
+
+impl FromStr for CoreSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let core = |core: &str| {
+            core.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("invalid core {:?} in {:?}", core, s))
+        };
+
+        let mut cores = Vec::new();
+        for part in s.split(',') {
+            match part.split_once('-') {
+                Some((start, end)) => {
+                    let (start, end) = (core(start)?, core(end)?);
+                    if start > end {
+                        return Err(format!("invalid core range {:?}", part));
+                    }
+                    cores.extend(start..=end);
+                }
+                None => cores.push(core(part)?),
+            }
+        }
+        cores.sort_unstable();
+        cores.dedup();
+
+        Ok(Self { cores })
+    }
+}
+
+impl fmt::Display for CoreSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cores = self
+            .cores
+            .iter()
+            .map(|core| core.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{}", cores.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod core_set {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let actual = "0-3,6,2".parse::<CoreSet>().unwrap();
+
+            assert_eq!(actual.to_string(), "0,1,2,3,6");
+        }
+
+        #[test]
+        fn err() {
+            assert!("3-1".parse::<CoreSet>().is_err());
+            assert!("a".parse::<CoreSet>().is_err());
+            assert!("".parse::<CoreSet>().is_err());
+        }
+
+        #[test]
+        fn overlap() {
+            let server = "0-3".parse::<CoreSet>().unwrap();
+            let bench = "3-5".parse::<CoreSet>().unwrap();
+
+            assert_eq!(server.overlap(&bench), [3]);
+        }
+    }
+}
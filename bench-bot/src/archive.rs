@@ -0,0 +1,125 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Name of the entry in the output directory pointing at the last run.
+const LATEST: &str = "latest";
+
+/// Create `{root}/{timestamp}/` for the artifacts of this run.
+pub fn create(root: &Path) -> io::Result<PathBuf> {
+    let dir = root.join(timestamp(SystemTime::now()));
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Point `{root}/latest` at `run_dir`: a relative symlink on Unix, a copy
+/// elsewhere.
+pub fn update_latest(root: &Path, run_dir: &Path) -> io::Result<()> {
+    let latest = root.join(LATEST);
+
+    match fs::symlink_metadata(&latest) {
+        Ok(meta) if meta.is_dir() => fs::remove_dir_all(&latest)?,
+        Ok(_) => fs::remove_file(&latest)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+
+    #[cfg(unix)]
+    {
+        let name = run_dir
+            .file_name()
+            .ok_or_else(|| io::Error::other("run directory has no name"))?;
+        std::os::unix::fs::symlink(name, &latest)
+    }
+    #[cfg(not(unix))]
+    {
+        copy_dir(run_dir, &latest)
+    }
+}
+
+#[cfg(not(unix))]
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &to.join(entry.file_name()))?;
+        } else {
+            fs::copy(entry.path(), to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+/// `time` in UTC as `2022-08-01T12-30-05Z`, sortable and without the colons
+/// Windows forbids in file names.
+fn timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let (days, secs) = (secs / 86_400, secs % 86_400);
+
+    // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}-{:02}-{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3_600,
+        secs % 3_600 / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    mod timestamp {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            assert_eq!(timestamp(UNIX_EPOCH), "1970-01-01T00-00-00Z");
+            // a leap day
+            let given = UNIX_EPOCH + Duration::from_secs(1_709_210_096);
+            assert_eq!(timestamp(given), "2024-02-29T12-34-56Z");
+        }
+    }
+
+    mod update_latest {
+        use super::*;
+
+        #[test]
+        fn replaces() {
+            let root =
+                std::env::temp_dir().join(format!("bench-bot-archive-{}", std::process::id()));
+            let first = root.join("2024-01-01T00-00-00Z");
+            let second = root.join("2024-01-02T00-00-00Z");
+            fs::create_dir_all(&first).unwrap();
+            fs::create_dir_all(&second).unwrap();
+            fs::write(second.join("hello-world.md"), "second").unwrap();
+
+            update_latest(&root, &first).unwrap();
+            update_latest(&root, &second).unwrap();
+
+            let actual = fs::read_to_string(root.join(LATEST).join("hello-world.md"));
+            fs::remove_dir_all(&root).unwrap();
+
+            assert_eq!(actual.unwrap(), "second");
+        }
+    }
+}
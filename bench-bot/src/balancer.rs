@@ -0,0 +1,123 @@
+use std::{
+    io,
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+/// Round-robin TCP balancer in front of several instances of a server, each
+/// accepted connection going to the next instance. It works below HTTP, so
+/// keep-alive connections stick to their instance. Stops on drop.
+pub struct Balancer {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    accept: Option<JoinHandle<()>>,
+}
+
+impl Balancer {
+    pub fn start(host: &str, port: u16, backends: Vec<SocketAddr>) -> io::Result<Self> {
+        let listener = TcpListener::bind((host, port))?;
+        let addr = listener.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let accept = {
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || {
+                for (i, client) in listener.incoming().enumerate() {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let Ok(client) = client else { continue };
+                    let backend = backends[i % backends.len()];
+
+                    thread::spawn(move || {
+                        if let Err(e) = proxy(client, backend) {
+                            log::debug!("Balancing a connection to {} failed: {}", backend, e);
+                        }
+                    });
+                }
+            })
+        };
+
+        Ok(Self {
+            addr,
+            stop,
+            accept: Some(accept),
+        })
+    }
+}
+
+impl Drop for Balancer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // wake the accept loop up so it sees the flag.
+        let _ = TcpStream::connect(self.addr);
+        if let Some(accept) = self.accept.take() {
+            let _ = accept.join();
+        }
+    }
+}
+
+/// Copy bytes both ways until either side closes.
+fn proxy(client: TcpStream, backend: SocketAddr) -> io::Result<()> {
+    let server = TcpStream::connect(backend)?;
+    client.set_nodelay(true)?;
+    server.set_nodelay(true)?;
+
+    let (mut client_read, mut server_write) = (client.try_clone()?, server.try_clone()?);
+    let upstream = thread::spawn(move || {
+        let _ = io::copy(&mut client_read, &mut server_write);
+        let _ = server_write.shutdown(Shutdown::Write);
+    });
+
+    let (mut server_read, mut client_write) = (server, client);
+    let _ = io::copy(&mut server_read, &mut client_write);
+    let _ = client_write.shutdown(Shutdown::Write);
+    let _ = upstream.join();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+
+    mod balancer {
+        use super::*;
+
+        /// A backend answering every connection with its `name`.
+        fn backend(name: &'static [u8]) -> SocketAddr {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let _ = stream.unwrap().write_all(name);
+                }
+            });
+
+            addr
+        }
+
+        #[test]
+        fn round_robin() {
+            let backends = vec![backend(b"a"), backend(b"b")];
+            let given = Balancer::start("127.0.0.1", 0, backends).unwrap();
+
+            let actual = (0..3)
+                .map(|_| {
+                    let mut answer = String::new();
+                    let mut stream = TcpStream::connect(given.addr).unwrap();
+                    stream.read_to_string(&mut answer).unwrap();
+                    answer
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(actual, ["a", "b", "a"]);
+        }
+    }
+}
@@ -0,0 +1,593 @@
+use crate::{
+    grpc::{self, Protocol},
+    interrupt, loadgen,
+    matrix::Matrix,
+    process::{self, PeakMemory},
+    ramp::{Ramp, RampPoint},
+    report::{self, Metrics, MetricsError},
+    tool::{self, CustomParser, Tool},
+    Args,
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus, Output, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread,
+    time::Duration,
+};
+use sysinfo::{Pid, PidExt, System, SystemExt};
+
+/// Interval of the memory samples of every step of a ramp or a matrix.
+const STEP_SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Longest `--warmup-requests`, in seconds, however many are left.
+const WARMUP_MAX_SECS: u64 = 60;
+/// Longest `Bench::warm_up`, the first second measuring the rate of tools
+/// that can't count requests.
+pub const WARMUP_BUDGET: Duration = Duration::from_secs(1 + WARMUP_MAX_SECS);
+
+/// Interval at which a running tool is checked for `stop`.
+const STOP_POLL: Duration = Duration::from_millis(50);
+
+/// Set by `stop`, cleared by `resume`.
+static STOPPED: AtomicBool = AtomicBool::new(false);
+
+/// End the running load generator early and skip the runs left of its
+/// series, e.g. once `--mem-abort` killed the server, until `resume`.
+pub fn stop() {
+    STOPPED.store(true, Ordering::SeqCst);
+}
+
+pub fn resume() {
+    STOPPED.store(false, Ordering::SeqCst);
+}
+
+fn stopped() -> bool {
+    STOPPED.load(Ordering::SeqCst)
+}
+
+/// Request bodies of `--body-dir`, sent with `POST`.
+#[derive(Clone)]
+pub struct Bodies {
+    files: Vec<PathBuf>,
+    /// Content of the first file, for tools taking a single body inline.
+    first: String,
+}
+
+impl Bodies {
+    /// Every file of `dir`, sorted by name.
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let mut files = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        files.retain(|file| file.is_file());
+        files.sort();
+
+        let first = files
+            .first()
+            .ok_or_else(|| io::Error::other(format!("{:?} has no files", dir)))?;
+        let first = fs::read_to_string(first)?;
+
+        Ok(Self { files, first })
+    }
+
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+}
+
+/// Runs the load generator against a server and parses what it reports.
+pub struct Bench<'a> {
+    args: &'a Args,
+    threads: String,
+    parser: Option<CustomParser>,
+    bodies: Option<Bodies>,
+}
+
+impl<'a> Bench<'a> {
+    pub fn new(
+        args: &'a Args,
+        threads: usize,
+        parser: Option<CustomParser>,
+        bodies: Option<Bodies>,
+    ) -> Self {
+        Self {
+            args,
+            threads: threads.to_string(),
+            parser,
+            bodies,
+        }
+    }
+
+    /// The same load generator, run with other `args`.
+    pub fn with_args<'b>(&self, args: &'b Args) -> Bench<'b> {
+        Bench {
+            args,
+            threads: self.threads.clone(),
+            parser: self.parser.clone(),
+            bodies: self.bodies.clone(),
+        }
+    }
+
+    /// Program followed by its arguments, `--tool-arg`s last. The built-in
+    /// tool isn't a program, its command only tells its settings.
+    pub fn command(&self, urls: &[String], connections: usize, duration: usize) -> Vec<String> {
+        let mut command = self.generated(urls, connections, duration);
+        command.extend(self.args.tool_arg.iter().cloned());
+        command
+    }
+
+    /// `--tool-arg`s setting a flag bench-bot already passes.
+    pub fn tool_arg_conflicts(&self, urls: &[String]) -> Vec<String> {
+        let generated = self.generated(urls, self.args.connections, self.args.duration);
+        tool::conflicts(&generated, &self.args.tool_arg)
+    }
+
+    /// The arguments bench-bot derives from its own options.
+    fn generated(&self, urls: &[String], connections: usize, duration: usize) -> Vec<String> {
+        let args = self.args;
+        let url = urls[0].as_str();
+        let conn_count = connections.to_string();
+        let duration = format!("{}s", duration);
+
+        if let (Tool::Custom, Some(template)) = (args.tool, &args.tool_command) {
+            let pipeline = args.pipeline.to_string();
+            let timeout_ms = args
+                .request_timeout
+                .map(|timeout| timeout.to_string())
+                .unwrap_or_default();
+            let path = |path: &Option<PathBuf>| {
+                path.as_ref()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default()
+            };
+            let (client_cert, client_key) = (path(&args.client_cert), path(&args.client_key));
+            let files = self.bodies.as_ref().map_or(&[][..], Bodies::files);
+            let body_file = files
+                .first()
+                .map(|file| file.display().to_string())
+                .unwrap_or_default();
+            let body_files = files
+                .iter()
+                .map(|file| file.display().to_string())
+                .collect::<Vec<_>>();
+            // every url and body file becomes its own argument.
+            let template = template
+                .replace("{urls}", &urls.join(" "))
+                .replace("{body_files}", &body_files.join(" "));
+            return tool::custom_command(
+                &template,
+                &[
+                    ("url", url),
+                    ("connections", &conn_count),
+                    ("duration", &duration),
+                    ("threads", &self.threads),
+                    ("pipeline", &pipeline),
+                    ("body_file", &body_file),
+                    ("timeout_ms", &timeout_ms),
+                    ("client_cert", &client_cert),
+                    ("client_key", &client_key),
+                ],
+            );
+        }
+
+        if let (Protocol::Grpc, Some(method)) = (args.protocol, &args.grpc_call) {
+            let call = grpc::Call {
+                method,
+                data: &args.grpc_data,
+                proto: args.grpc_proto.as_deref(),
+                timeout_ms: args.request_timeout,
+                client_cert: args.client_cert.as_deref().zip(args.client_key.as_deref()),
+            };
+            return call.command(url, connections, &duration);
+        }
+
+        if args.tool == Tool::Builtin {
+            let mut command = ["builtin", "-c", &conn_count, "-d", &duration]
+                .map(String::from)
+                .to_vec();
+            if let Some(timeout) = args.request_timeout {
+                command.extend(["--timeout".to_string(), format!("{}ms", timeout)]);
+            }
+            command.push(url.to_string());
+            return command;
+        }
+
+        let mut rewrk_args = [
+            "rewrk",
+            "-t",
+            &self.threads,
+            "-c",
+            &conn_count,
+            "-d",
+            &duration,
+            "-h",
+            url,
+        ]
+        .map(String::from)
+        .to_vec();
+
+        if let Some(encoding) = &args.accept_encoding {
+            rewrk_args.push("-H".to_owned());
+            rewrk_args.push(format!("Accept-Encoding: {}", encoding));
+        }
+        if args.no_keepalive {
+            rewrk_args.push("-H".to_owned());
+            rewrk_args.push("Connection: close".to_owned());
+        }
+        if args.http2 {
+            rewrk_args.push("--http2".to_owned());
+        }
+        if let Some(bodies) = &self.bodies {
+            rewrk_args.extend(["-m", "POST", "-b", &bodies.first].map(String::from));
+        }
+
+        rewrk_args
+    }
+
+    /// The command of a regular run, as it would be typed in a shell.
+    pub fn display(&self, urls: &[String]) -> String {
+        self.command(urls, self.args.connections, self.args.duration)
+            .iter()
+            .map(|arg| {
+                if arg.contains(' ') {
+                    format!("\"{}\"", arg)
+                } else {
+                    arg.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn run(&self, urls: &[String], connections: usize, duration: usize) -> Output {
+        if self.args.tool == Tool::Builtin {
+            return self.run_builtin(urls, connections, duration);
+        }
+
+        let command = self.command(urls, connections, duration);
+
+        let mut tool = Command::new(&command[0]);
+        tool.args(&command[1..]);
+        if let Some(cores) = &self.args.bench_cores {
+            tool = cores.wrap(&tool);
+        }
+
+        let (tool, _tracked) =
+            interrupt::spawn(tool.stdout(Stdio::piped()).stderr(Stdio::piped())).unwrap();
+
+        // the tool is killed from another thread while this one reads its
+        // output, which would block it if left unread.
+        let pid = Pid::from_u32(tool.id());
+        let (done, finished) = mpsc::channel::<()>();
+        let watcher = thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = finished.recv_timeout(STOP_POLL) {
+                if stopped() {
+                    let mut sys = System::new();
+                    sys.refresh_process(pid);
+                    let spawned = process::Spawned::of(&sys, pid);
+                    process::reap(&spawned.into_iter().collect::<Vec<_>>());
+                    break;
+                }
+            }
+        });
+        let output = tool.wait_with_output().unwrap();
+        drop(done);
+        let _ = watcher.join();
+
+        output
+    }
+
+    /// Run the built-in load generator, its statistics as stdout and a
+    /// failure as stderr, as if it were a program.
+    fn run_builtin(&self, urls: &[String], connections: usize, duration: usize) -> Output {
+        let request = self.request(urls).with_rate(self.args.rate);
+
+        let (stdout, stderr) = match loadgen::bench(
+            &request,
+            connections,
+            Duration::from_secs(duration as u64),
+            &STOPPED,
+        ) {
+            Ok(stats) => (stats.render(), String::new()),
+            Err(e) => (
+                String::new(),
+                format!("builtin load generator failed: {}", e),
+            ),
+        };
+
+        Output {
+            status: ExitStatus::default(),
+            stdout: stdout.into_bytes(),
+            stderr: stderr.into_bytes(),
+        }
+    }
+
+    /// The request of the built-in load generator, to every one of `urls`
+    /// in turn.
+    fn request(&self, urls: &[String]) -> loadgen::Request {
+        let args = self.args;
+        let headers = args
+            .accept_encoding
+            .as_deref()
+            .map(|encoding| ("Accept-Encoding", encoding))
+            .into_iter()
+            .collect::<Vec<_>>();
+        let body = self.bodies.as_ref().map(|bodies| bodies.first.as_str());
+
+        loadgen::Request::new(urls, &headers, !args.no_keepalive, body)
+            .expecting(&args.expect_status)
+            .with_timeout(args.request_timeout.map(Duration::from_millis))
+    }
+
+    /// Send `requests` requests before the measured runs, see
+    /// `--warmup-requests`. Over HTTP/1.1 bench-bot sends exactly that many
+    /// itself, otherwise the tool runs for as long as its rate in a first
+    /// second says they take. How many were sent.
+    pub fn warm_up(&self, urls: &[String], requests: u64) -> u64 {
+        let args = self.args;
+        // the builtin client can't present a `--client-cert`.
+        if !args.http2 && args.protocol == Protocol::Http && args.client_cert.is_none() {
+            let max = Duration::from_secs(WARMUP_MAX_SECS);
+            return match loadgen::send(&self.request(urls), args.connections, requests, max) {
+                Ok(stats) => stats.sent(),
+                Err(e) => {
+                    log::warn!("Warmup requests failed: {}", e);
+                    0
+                }
+            };
+        }
+
+        // a first second for the rate, then the rest at that rate.
+        let mut sent = 0;
+        let mut duration = 1;
+        for _ in 0..2 {
+            let output = self.run(urls, args.connections, duration);
+            let metrics = self.parse(&output).ok();
+            let Some((total, req_per_sec)) = metrics.and_then(|m| m.total().zip(m.req_per_sec()))
+            else {
+                break;
+            };
+            sent += total;
+            if sent >= requests || req_per_sec <= 0.0 {
+                break;
+            }
+            let left = (requests - sent) as f64 / req_per_sec;
+            duration = (left.ceil() as usize).clamp(1, WARMUP_MAX_SECS as usize);
+        }
+
+        sent
+    }
+
+    pub fn parse(&self, output: &Output) -> Result<Metrics, MetricsError> {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        if self.args.protocol == Protocol::Grpc {
+            return grpc::parse(&stdout);
+        }
+
+        match (&self.parser, self.args.tool) {
+            (Some(parser), _) => parser.parse(&stdout),
+            (None, Tool::Builtin) => loadgen::parse(&stdout),
+            (None, _) => stdout.parse::<Metrics>(),
+        }
+    }
+
+    /// Why `output` is not a valid run: the tool exited with an error or
+    /// its stats don't parse. Stderr alone is no failure, some tools print
+    /// warnings or progress there.
+    pub fn failure(&self, output: &Output) -> Option<String> {
+        let limit = self.args.stderr_limit;
+        let stderr = tail("stderr", &String::from_utf8_lossy(&output.stderr), limit);
+
+        if !output.status.success() {
+            Some(format!(
+                "the load generator exited with {}: \n{}",
+                output.status, stderr
+            ))
+        } else if self.parse(output).is_err() {
+            Some(format!(
+                "could not parse the output of the load generator: \n{}\n{}",
+                tail("stdout", &String::from_utf8_lossy(&output.stdout), limit),
+                stderr
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Requests/sec of a successful run.
+    fn req_per_sec(&self, output: &Output) -> Option<f64> {
+        if !output.status.success() {
+            return None;
+        }
+        self.parse(output).ok()?.req_per_sec()
+    }
+
+    /// Run `--runs` times, or with `--until-stable` until two successive
+    /// runs agree, returning the run with the median requests/sec, or the
+    /// better of the two that agreed, and the requests/sec of every run.
+    /// A failed run stops the series and is returned as is, the one
+    /// `--discard-first-run` throws away included.
+    pub fn repeat(&self, urls: &[String], member: &Path) -> (Output, Vec<f64>) {
+        let args = self.args;
+        let runs = if args.until_stable {
+            args.max_runs
+        } else {
+            args.runs
+        }
+        .max(1);
+        let mut samples = Vec::with_capacity(runs);
+        let mut stable = false;
+
+        if args.discard_first_run {
+            log::info!("Discarded run of {:?}", member);
+            let output = self.run(urls, args.connections, args.duration);
+            if stopped() || self.req_per_sec(&output).is_none() {
+                return (output, Vec::new());
+            }
+        }
+
+        for run in 1..=runs {
+            if runs > 1 {
+                log::info!("Run {}/{} of {:?}", run, runs, member);
+            }
+
+            let output = self.run(urls, args.connections, args.duration);
+            let Some(req_per_sec) = self.req_per_sec(&output).filter(|_| !stopped()) else {
+                return (output, Vec::new());
+            };
+            samples.push((req_per_sec, output));
+
+            if args.until_stable {
+                let req_per_sec = samples.iter().map(|(rps, _)| *rps).collect::<Vec<_>>();
+                if report::converged(&req_per_sec, args.tolerance) {
+                    log::info!("{:?} is stable after {} runs", member, run);
+                    stable = true;
+                    break;
+                }
+                if run == runs {
+                    log::warn!("{:?} did not stabilize within {} runs.", member, runs);
+                }
+            }
+        }
+
+        // in the order of the runs, before sorting them for the median.
+        let req_per_sec = samples.iter().map(|(rps, _)| *rps).collect();
+        if stable {
+            // the better of the two runs that agreed, not the median of a
+            // series that started before the server was warm.
+            let last = samples.len() - 1;
+            let best = if samples[last - 1].0 > samples[last].0 {
+                last - 1
+            } else {
+                last
+            };
+            let (_, output) = samples.swap_remove(best);
+            return (output, req_per_sec);
+        }
+
+        samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let (_, median) = samples.swap_remove(samples.len() / 2);
+
+        (median, req_per_sec)
+    }
+
+    /// Benchmark every combination of `connections` and `durations`, see
+    /// `--matrix`, sampling the peak memory of `servers` at each one.
+    pub fn matrix(
+        &self,
+        urls: &[String],
+        member: &Path,
+        servers: &[Pid],
+        connections: &[usize],
+        durations: &[usize],
+    ) -> Matrix {
+        Matrix::sweep(connections, durations, |connections, duration| {
+            if stopped() {
+                return (None, None);
+            }
+            log::info!(
+                "Matrix of {:?} at {} connections for {}s",
+                member,
+                connections,
+                duration
+            );
+            let memory = PeakMemory::start(servers, STEP_SAMPLE_INTERVAL);
+            let output = self.run(urls, connections, duration);
+            let memory_mb = memory.stop().map(|kb| kb as f64 / 1024.0);
+            (self.req_per_sec(&output), memory_mb)
+        })
+    }
+
+    /// Ramp the connection count up, see `--ramp`, sampling the peak memory
+    /// of `servers` at every step. Returns the run with the best
+    /// requests/sec along with the whole ramp.
+    pub fn ramp(&self, urls: &[String], member: &Path, servers: &[Pid]) -> (Output, Ramp) {
+        let args = self.args;
+        let mut ramp = Ramp::new(args.ramp_tolerance);
+        let mut best: Option<(f64, Output)> = None;
+        let mut connections = args.ramp_start.max(1);
+
+        loop {
+            log::info!("Ramping {:?} at {} connections", member, connections);
+            let memory = PeakMemory::start(servers, STEP_SAMPLE_INTERVAL);
+            let output = self.run(urls, connections, args.ramp_step);
+            let memory_mb = memory.stop().map(|kb| kb as f64 / 1024.0);
+
+            let Some(req_per_sec) = self.req_per_sec(&output).filter(|_| !stopped()) else {
+                // a failed step ends the ramp, it is reported if nothing better ran.
+                best.get_or_insert((0.0, output));
+                break;
+            };
+
+            let plateaued = ramp.push(RampPoint {
+                connections,
+                req_per_sec,
+                memory_mb,
+            });
+            if best.as_ref().is_none_or(|(rps, _)| req_per_sec > *rps) {
+                best = Some((req_per_sec, output));
+            }
+
+            if plateaued || connections * 2 > args.connections {
+                break;
+            }
+            connections *= 2;
+        }
+
+        (best.map(|(_, output)| output).unwrap(), ramp)
+    }
+}
+
+/// The last `limit` bytes of `output`, the `stream` of a child, with how
+/// much was cut in front of them. A `limit` of 0 keeps it whole.
+pub fn tail(stream: &str, output: &str, limit: usize) -> String {
+    if limit == 0 || output.len() <= limit {
+        return output.to_string();
+    }
+
+    let mut start = output.len() - limit;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    format!(
+        "[... {} bytes of {} truncated ...]\n{}",
+        start,
+        stream,
+        &output[start..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod tail {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = "warning: unused\nerror: linking failed";
+
+            assert_eq!(
+                tail("stderr", given, 21),
+                "[... 16 bytes of stderr truncated ...]\nerror: linking failed"
+            );
+            assert_eq!(tail("stderr", given, 0), given);
+            assert_eq!(tail("stderr", given, 100), given);
+        }
+
+        #[test]
+        fn char_boundary() {
+            assert_eq!(
+                tail("stderr", "é error", 7),
+                "[... 2 bytes of stderr truncated ...]\n error"
+            );
+        }
+    }
+}
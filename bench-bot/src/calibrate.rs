@@ -0,0 +1,145 @@
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+/// Answer of the null server to every request.
+const RESPONSE: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok";
+
+/// Share of the generator's ceiling from which a server's requests/sec are
+/// likely limited by the generator rather than the server.
+pub const GENERATOR_BOUND: f64 = 0.8;
+
+/// HTTP/1.1 server on loopback doing no work at all, to measure how many
+/// requests/sec the load generator manages on this machine. Stops on drop.
+pub struct NullServer {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    accept: Option<JoinHandle<()>>,
+}
+
+impl NullServer {
+    pub fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0))?;
+        let addr = listener.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let accept = {
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || {
+                for client in listener.incoming() {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let Ok(client) = client else { continue };
+                    thread::spawn(move || {
+                        if let Err(e) = serve(client) {
+                            log::debug!("Null server connection failed: {}", e);
+                        }
+                    });
+                }
+            })
+        };
+
+        Ok(Self {
+            addr,
+            stop,
+            accept: Some(accept),
+        })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+}
+
+impl Drop for NullServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // wake the accept loop up so it sees the flag.
+        let _ = TcpStream::connect(self.addr);
+        if let Some(accept) = self.accept.take() {
+            let _ = accept.join();
+        }
+    }
+}
+
+/// Answer every request of `client` until it hangs up, skipping bodies.
+fn serve(client: TcpStream) -> io::Result<()> {
+    let mut writer = client.try_clone()?;
+    let mut reader = BufReader::new(client);
+    let mut line = String::new();
+
+    loop {
+        let mut length = 0;
+        let mut close = false;
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            if line.trim().is_empty() {
+                break;
+            }
+            if let Some((key, value)) = line.split_once(':') {
+                let (key, value) = (key.trim(), value.trim());
+                if key.eq_ignore_ascii_case("Content-Length") {
+                    length = value.parse().unwrap_or(0);
+                } else if key.eq_ignore_ascii_case("Connection") {
+                    close = value.eq_ignore_ascii_case("close");
+                }
+            }
+        }
+        io::copy(&mut (&mut reader).take(length), &mut io::sink())?;
+
+        writer.write_all(RESPONSE)?;
+        if close {
+            return Ok(());
+        }
+    }
+}
+
+/// Whether `req_per_sec` of a server come close enough to `ceiling`, the
+/// requests/sec against the null server, to be the generator's limit.
+pub fn generator_bound(req_per_sec: f64, ceiling: f64) -> bool {
+    ceiling > 0.0 && req_per_sec >= ceiling * GENERATOR_BOUND
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod null_server {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let server = NullServer::start().unwrap();
+            let mut stream = TcpStream::connect(server.addr).unwrap();
+
+            stream
+                .write_all(b"POST / HTTP/1.1\r\nContent-Length: 4\r\n\r\nbodyGET / HTTP/1.1\r\nConnection: close\r\n\r\n")
+                .unwrap();
+            let mut response = Vec::new();
+            stream.read_to_end(&mut response).unwrap();
+
+            assert_eq!(response, [RESPONSE, RESPONSE].concat());
+        }
+    }
+
+    mod generator_bound {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            assert!(generator_bound(90_000.0, 100_000.0));
+            assert!(!generator_bound(50_000.0, 100_000.0));
+            assert!(!generator_bound(50_000.0, 0.0));
+        }
+    }
+}
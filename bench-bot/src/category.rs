@@ -0,0 +1,174 @@
+use crate::{port, Args};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// `--config` file, e.g.
+///
+/// ```toml
+/// [category.hello-world]
+/// connections = 1000
+///
+/// [category.db]
+/// connections = 64
+/// duration = 30
+/// mem_warmup_ms = 2000
+/// route = "/users/1"
+/// expect_status = [200, 404]
+///
+/// [tags]
+/// "hello-world/axum" = ["tokio", "minimal"]
+/// ```
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Overrides per benchmark category, the directory of its members.
+    #[serde(default)]
+    pub category: HashMap<String, Overrides>,
+    /// Tags per member, badged next to its name in the comparison table.
+    #[serde(default)]
+    pub tags: HashMap<PathBuf, Vec<String>>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let toml =
+            fs::read_to_string(path).map_err(|e| format!("could not read {:?}: {}", path, e))?;
+        toml::from_str(&toml).map_err(|e| format!("could not parse {:?}: {}", path, e))
+    }
+}
+
+/// Settings of a category taking precedence over the command line, unset
+/// ones keep its values.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Overrides {
+    pub connections: Option<usize>,
+    pub duration: Option<usize>,
+    pub mem_warmup_ms: Option<u64>,
+    /// Path requested on every `-u`, e.g. `/json`.
+    pub route: Option<String>,
+    /// Status codes of a successful response of the route, e.g. `[201]`.
+    pub expect_status: Option<Vec<u16>>,
+}
+
+impl Overrides {
+    /// `args` with the overrides applied.
+    pub fn apply(&self, args: &Args) -> Args {
+        let mut args = args.clone();
+        if let Some(connections) = self.connections {
+            args.connections = connections;
+        }
+        if let Some(duration) = self.duration {
+            args.duration = duration;
+        }
+        if let Some(warmup) = self.mem_warmup_ms {
+            args.mem_warmup_ms = Some(warmup);
+        }
+        if let Some(expect_status) = &self.expect_status {
+            args.expect_status = expect_status.clone();
+        }
+        if let Some(route) = &self.route {
+            args.url = args
+                .url
+                .iter()
+                .map(|url| port::with_path(url, route))
+                .collect();
+        }
+
+        args
+    }
+}
+
+/// The effective settings of `category` for the methodology.
+pub fn describe(category: &str, args: &Args) -> String {
+    let mut settings = vec![
+        format!("{} connections", args.connections),
+        format!("{}s", args.duration),
+    ];
+    if let Some(warmup) = args.mem_warmup_ms {
+        settings.push(format!("{} ms memory warmup", warmup));
+    }
+    settings.push(format!("`{}`", args.url.join("`, `")));
+    if !args.expect_status.is_empty() {
+        let statuses = args
+            .expect_status
+            .iter()
+            .map(u16::to_string)
+            .collect::<Vec<_>>();
+        settings.push(format!("expecting status {}", statuses.join(" or ")));
+    }
+
+    format!("Category `{}`: {}.", category, settings.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQUIRED: [&str; 5] = ["bench-bot", "-w", "../benchmark", "-o", "../result"];
+
+    mod config {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = "[category.db]\nconnections = 64\nroute = \"/users/1\"\n";
+
+            let actual = toml::from_str::<Config>(given).unwrap();
+
+            let expect = Overrides {
+                connections: Some(64),
+                route: Some("/users/1".to_string()),
+                ..Overrides::default()
+            };
+            assert_eq!(actual.category["db"], expect);
+        }
+
+        #[test]
+        fn tags() {
+            let given = "[tags]\n\"hello-world/axum\" = [\"tokio\", \"minimal\"]\n";
+
+            let actual = toml::from_str::<Config>(given).unwrap();
+
+            assert_eq!(
+                actual.tags[Path::new("hello-world/axum")],
+                ["tokio", "minimal"]
+            );
+        }
+
+        #[test]
+        fn unknown_setting() {
+            assert!(toml::from_str::<Config>("[category.db]\nconection = 64\n").is_err());
+        }
+    }
+
+    mod overrides {
+        use super::*;
+
+        #[test]
+        fn apply() {
+            let args =
+                crate::tests::try_parse(REQUIRED.iter().chain(&["-c", "500", "-d", "10"])).unwrap();
+            let given = Overrides {
+                duration: Some(30),
+                mem_warmup_ms: Some(200),
+                route: Some("/json".to_string()),
+                ..Overrides::default()
+            };
+
+            let actual = given.apply(&args);
+
+            assert_eq!(actual.connections, 500);
+            assert_eq!(actual.duration, 30);
+            assert_eq!(
+                describe("json", &actual),
+                "Category `json`: 500 connections, 30s, 200 ms memory warmup, \
+                 `http://127.0.0.1:3000/json`."
+            );
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+    thread,
+    time::Duration,
+};
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// A cgroup v2 with a memory limit, used to run a single server under
+/// memory pressure. The group is killed and removed on drop.
+pub struct Cgroup {
+    path: PathBuf,
+}
+
+impl Cgroup {
+    /// Check that a cgroup v2 hierarchy with the memory controller exists.
+    pub fn supported() -> io::Result<()> {
+        if cfg!(not(target_os = "linux")) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "memory limits need cgroup v2, which is only available on Linux",
+            ));
+        }
+
+        let controllers = fs::read_to_string(Path::new(CGROUP_ROOT).join("cgroup.controllers"))?;
+        if controllers.split_whitespace().any(|c| c == "memory") {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the cgroup v2 memory controller is not enabled",
+            ))
+        }
+    }
+
+    pub fn create(name: &str, limit_mb: u64) -> io::Result<Self> {
+        let path = Path::new(CGROUP_ROOT).join(format!("bench-bot-{}", name.replace('/', "-")));
+
+        fs::create_dir_all(&path)?;
+        fs::write(
+            path.join("memory.max"),
+            (limit_mb * 1024 * 1024).to_string(),
+        )?;
+        // without swap limit the kernel swaps instead of killing.
+        let _ = fs::write(path.join("memory.swap.max"), "0");
+
+        Ok(Self { path })
+    }
+
+    /// Wrap `command` so that it joins this cgroup before `exec`, which
+    /// makes every process it spawns (e.g. `cargo run` children) inherit
+    /// the limit.
+    pub fn wrap(&self, command: &Command) -> Command {
+        let procs = self.path.join("cgroup.procs");
+
+        let mut wrapped = Command::new("sh");
+        wrapped
+            .arg("-c")
+            .arg(format!("echo $$ > '{}' && exec \"$@\"", procs.display()))
+            .arg("sh")
+            .arg(command.get_program())
+            .args(command.get_args());
+
+        if let Some(dir) = command.get_current_dir() {
+            wrapped.current_dir(dir);
+        }
+        for (key, value) in command.get_envs() {
+            match value {
+                Some(value) => wrapped.env(key, value),
+                None => wrapped.env_remove(key),
+            };
+        }
+
+        wrapped
+    }
+
+    /// Whether the kernel OOM killer fired inside this cgroup.
+    pub fn oom_killed(&self) -> bool {
+        fs::read_to_string(self.path.join("memory.events"))
+            .map(|events| oom_kills(&events) > 0)
+            .unwrap_or(false)
+    }
+}
+
+impl Drop for Cgroup {
+    fn drop(&mut self) {
+        // `cgroup.kill` also takes down children the server left behind.
+        let _ = fs::write(self.path.join("cgroup.kill"), "1");
+
+        for _ in 0..50 {
+            if fs::remove_dir(&self.path).is_ok() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        log::warn!("Could not remove cgroup {:?}.", self.path);
+    }
+}
+
+// parse for
+// low 0
+// high 0
+// max 12
+// oom 1
+// oom_kill 1
+fn oom_kills(events: &str) -> u64 {
+    events
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .find(|(key, _)| *key == "oom_kill")
+        .and_then(|(_, count)| count.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod oom_kills {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = "low 0\nhigh 0\nmax 12\noom 1\noom_kill 1\noom_group_kill 0\n";
+
+            assert_eq!(oom_kills(given), 1);
+        }
+
+        #[test]
+        fn missing() {
+            assert_eq!(oom_kills("low 0\nhigh 0\n"), 0);
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use serde::Serialize;
+use std::{
+    fs, thread,
+    time::{Duration, Instant},
+};
+use sysinfo::{CpuExt, PidExt, ProcessExt, System, SystemExt};
+
+/// Interval of the usage polls of `wait_idle`, usage needs some time
+/// between two refreshes to be meaningful.
+const IDLE_POLL: Duration = Duration::from_millis(250);
+
+/// Time the CPU usage of every process is measured over by `background`.
+const BACKGROUND_SAMPLE: Duration = Duration::from_secs(1);
+
+/// Spread of the utilization of every core over a benchmark, in percent.
+/// A single threaded framework shows one hot core and idle others.
+#[derive(PartialEq, Debug, Clone, Copy, Serialize)]
+pub struct CoreUsage {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+}
+
+impl CoreUsage {
+    /// `samples` holds the usage of every core at each sample, cores are
+    /// averaged over the samples first.
+    pub fn from_samples(samples: &[Vec<f32>]) -> Option<Self> {
+        let cores = samples.first()?.len();
+        if cores == 0 {
+            return None;
+        }
+
+        let per_core = (0..cores)
+            .map(|core| {
+                let total = samples
+                    .iter()
+                    .filter_map(|sample| sample.get(core))
+                    .sum::<f32>();
+                total / samples.len() as f32
+            })
+            .collect::<Vec<_>>();
+
+        Some(Self {
+            min: per_core.iter().copied().fold(f32::INFINITY, f32::min),
+            max: per_core.iter().copied().fold(0.0, f32::max),
+            mean: per_core.iter().sum::<f32>() / cores as f32,
+        })
+    }
+}
+
+/// Usage in percent from which a core counts as loaded.
+const BUSY: f32 = 50.0;
+
+/// Lowest frequency, in MHz, among the loaded cores of one sample given as
+/// `(usage, frequency)` per core. Idle cores clocking down are expected and
+/// ignored, a loaded core below its base clock is throttled.
+pub fn busy_frequency(cpus: &[(f32, u64)]) -> Option<u64> {
+    cpus.iter()
+        .filter(|(usage, _)| *usage >= BUSY)
+        .map(|(_, frequency)| *frequency)
+        .min()
+}
+
+/// Lowest `busy_frequency` over every sample of a benchmark.
+#[derive(Debug, Default)]
+pub struct MinFrequency(Option<u64>);
+
+impl MinFrequency {
+    pub fn sample(&mut self, cpus: &[(f32, u64)]) {
+        if let Some(frequency) = busy_frequency(cpus) {
+            self.0 = Some(self.0.map_or(frequency, |min| min.min(frequency)));
+        }
+    }
+
+    pub fn get(&self) -> Option<u64> {
+        self.0
+    }
+}
+
+/// Frequency of every core right now, in MHz, next to the usage of `sys`.
+/// sysinfo reads frequencies once per `System` and never refreshes them,
+/// so they are read from cpufreq on Linux and from a new `System` elsewhere.
+pub fn usage_and_frequency(sys: &System) -> Vec<(f32, u64)> {
+    let mut fresh = None;
+
+    sys.cpus()
+        .iter()
+        .enumerate()
+        .map(|(core, cpu)| {
+            let frequency = scaling_cur_freq(core).unwrap_or_else(|| {
+                let fresh = fresh.get_or_insert_with(|| {
+                    let mut fresh = System::new();
+                    fresh.refresh_cpu();
+                    fresh
+                });
+                fresh.cpus().get(core).map_or(0, CpuExt::frequency)
+            });
+            (cpu.cpu_usage(), frequency)
+        })
+        .collect()
+}
+
+// in kHz, missing without cpufreq, e.g. in most VMs.
+fn scaling_cur_freq(core: usize) -> Option<u64> {
+    let path = format!(
+        "/sys/devices/system/cpu/cpu{}/cpufreq/scaling_cur_freq",
+        core
+    );
+    let khz = fs::read_to_string(path).ok()?.trim().parse::<u64>().ok()?;
+    Some(khz / 1000)
+}
+
+/// Tells when the machine has settled: aggregate usage below `threshold`
+/// percent for `window` in a row.
+pub struct Idle {
+    threshold: f32,
+    window: Duration,
+    since: Option<Duration>,
+}
+
+impl Idle {
+    pub fn new(threshold: f32, window: Duration) -> Self {
+        Self {
+            threshold,
+            window,
+            since: None,
+        }
+    }
+
+    /// Add the usage sampled `at` into the wait, true once idle for long
+    /// enough. A busy sample starts the window over.
+    pub fn sample(&mut self, at: Duration, usage: f32) -> bool {
+        if usage >= self.threshold {
+            self.since = None;
+            return false;
+        }
+
+        let since = *self.since.get_or_insert(at);
+        at - since >= self.window
+    }
+}
+
+/// Poll the aggregate usage until `idle` is reached, for at most `max`.
+/// How long it took, `None` if the machine didn't settle.
+pub fn wait_idle(mut idle: Idle, max: Duration) -> Option<Duration> {
+    let mut sys = System::new();
+    // usage is computed against the previous refresh.
+    sys.refresh_cpu();
+    let start = Instant::now();
+
+    while start.elapsed() < max {
+        thread::sleep(IDLE_POLL);
+        sys.refresh_cpu();
+        if idle.sample(start.elapsed(), sys.global_cpu_info().cpu_usage()) {
+            return Some(start.elapsed());
+        }
+    }
+
+    None
+}
+
+/// A process other than bench-bot using the CPU.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Busy {
+    pub name: String,
+    pub pid: u32,
+    /// Percent of one core.
+    pub usage: f32,
+}
+
+/// Processes using at least `threshold` percent of a core over a second,
+/// busiest first, leaving out bench-bot and kernel threads, which have no
+/// command line.
+pub fn background(threshold: f32) -> Vec<Busy> {
+    let mut sys = System::new();
+    // usage is computed against the previous refresh.
+    sys.refresh_processes();
+    thread::sleep(BACKGROUND_SAMPLE);
+    sys.refresh_processes();
+
+    let own = std::process::id();
+    let processes = sys
+        .processes()
+        .iter()
+        .filter(|(pid, process)| pid.as_u32() != own && !process.cmd().is_empty())
+        .map(|(pid, process)| Busy {
+            name: process.name().to_string(),
+            pid: pid.as_u32(),
+            usage: process.cpu_usage(),
+        })
+        .collect();
+
+    busiest(processes, threshold)
+}
+
+fn busiest(mut processes: Vec<Busy>, threshold: f32) -> Vec<Busy> {
+    processes.retain(|process| process.usage >= threshold);
+    processes.sort_by(|a, b| b.usage.total_cmp(&a.usage));
+    processes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod core_usage {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = [vec![100.0, 0.0, 10.0], vec![80.0, 0.0, 30.0]];
+
+            let expect = CoreUsage {
+                min: 0.0,
+                max: 90.0,
+                mean: 110.0 / 3.0,
+            };
+
+            assert_eq!(CoreUsage::from_samples(&given), Some(expect));
+        }
+
+        #[test]
+        fn empty() {
+            assert_eq!(CoreUsage::from_samples(&[]), None);
+        }
+    }
+
+    mod busy_frequency {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = [(95.0, 3200), (2.0, 800), (60.0, 2900)];
+
+            assert_eq!(busy_frequency(&given), Some(2900));
+        }
+
+        #[test]
+        fn idle() {
+            assert_eq!(busy_frequency(&[(2.0, 800)]), None);
+        }
+    }
+
+    mod min_frequency {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let mut given = MinFrequency::default();
+
+            given.sample(&[(95.0, 3200), (2.0, 800)]);
+            assert_eq!(given.get(), Some(3200));
+            given.sample(&[(95.0, 2100), (2.0, 800)]);
+            given.sample(&[(95.0, 3000), (2.0, 800)]);
+            assert_eq!(given.get(), Some(2100));
+        }
+
+        #[test]
+        fn idle() {
+            let mut given = MinFrequency::default();
+
+            given.sample(&[(2.0, 800)]);
+            assert_eq!(given.get(), None);
+        }
+    }
+
+    mod busiest {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let busy = |name: &str, usage| Busy {
+                name: name.to_string(),
+                pid: 1,
+                usage,
+            };
+            let given = vec![busy("sshd", 0.5), busy("cc1", 40.0), busy("rustc", 98.0)];
+
+            assert_eq!(
+                busiest(given, 10.0),
+                [busy("rustc", 98.0), busy("cc1", 40.0)]
+            );
+        }
+    }
+
+    mod idle {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let mut idle = Idle::new(5.0, Duration::from_secs(2));
+            let at = Duration::from_secs;
+
+            assert!(!idle.sample(at(0), 2.0));
+            assert!(!idle.sample(at(1), 40.0));
+            assert!(!idle.sample(at(2), 1.0));
+            assert!(!idle.sample(at(3), 4.0));
+            assert!(idle.sample(at(4), 3.0));
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use serde::Serialize;
+use std::{collections::HashMap, fs};
+
+/// Bytes a process read from and wrote to storage, as counted in
+/// `/proc/<pid>/io`. Reads served by the page cache are not counted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct DiskIo {
+    pub read: u64,
+    pub written: u64,
+}
+
+impl DiskIo {
+    /// `None` where `/proc/<pid>/io` can't be read, e.g. off Linux.
+    pub fn of(pid: u32) -> Option<Self> {
+        parse(&fs::read_to_string(format!("/proc/{}/io", pid)).ok()?)
+    }
+
+    /// Both in MB, e.g. `0.0MB/12.5MB`.
+    pub fn render(&self) -> String {
+        let mb = |bytes: u64| bytes as f64 / 1024.0 / 1024.0;
+        format!("{:.1}MB/{:.1}MB", mb(self.read), mb(self.written))
+    }
+}
+
+// parse the storage counters of `/proc/<pid>/io`, e.g.
+//      rchar: 3980
+//      wchar: 0
+//      read_bytes: 4096
+//      write_bytes: 8192
+fn parse(io: &str) -> Option<DiskIo> {
+    let counter = |name: &str| {
+        io.lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(key, _)| key.trim() == name)
+            .and_then(|(_, value)| value.trim().parse().ok())
+    };
+
+    Some(DiskIo {
+        read: counter("read_bytes")?,
+        written: counter("write_bytes")?,
+    })
+}
+
+/// Disk I/O of processes over the benchmark, from their first and last
+/// readings.
+#[derive(Debug, Default)]
+pub struct DiskUsage {
+    first: HashMap<u32, DiskIo>,
+    last: HashMap<u32, DiskIo>,
+}
+
+impl DiskUsage {
+    pub fn sample(&mut self, pid: u32) {
+        if let Some(io) = DiskIo::of(pid) {
+            self.first.entry(pid).or_insert(io);
+            self.last.insert(pid, io);
+        }
+    }
+
+    /// Over every process, `None` when none could be read.
+    pub fn total(&self) -> Option<DiskIo> {
+        if self.first.is_empty() {
+            return None;
+        }
+
+        Some(
+            self.first
+                .iter()
+                .fold(DiskIo::default(), |total, (pid, first)| {
+                    let last = self.last.get(pid).copied().unwrap_or(*first);
+                    DiskIo {
+                        read: total.read + last.read.saturating_sub(first.read),
+                        written: total.written + last.written.saturating_sub(first.written),
+                    }
+                }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = "rchar: 3980\nwchar: 12\nsyscr: 8\nsyscw: 1\nread_bytes: 4096\n\
+                         write_bytes: 8192\ncancelled_write_bytes: 0\n";
+
+            assert_eq!(
+                parse(given),
+                Some(DiskIo {
+                    read: 4096,
+                    written: 8192
+                })
+            );
+        }
+
+        #[test]
+        fn err() {
+            assert_eq!(parse("rchar: 3980\n"), None);
+        }
+    }
+
+    mod disk_usage {
+        use super::*;
+
+        #[test]
+        fn total() {
+            let mut given = DiskUsage::default();
+            given.first.insert(
+                1,
+                DiskIo {
+                    read: 100,
+                    written: 1000,
+                },
+            );
+            given.last.insert(
+                1,
+                DiskIo {
+                    read: 150,
+                    written: 3000,
+                },
+            );
+            given.first.insert(
+                2,
+                DiskIo {
+                    read: 0,
+                    written: 0,
+                },
+            );
+            given.last.insert(
+                2,
+                DiskIo {
+                    read: 10,
+                    written: 0,
+                },
+            );
+
+            assert_eq!(
+                given.total(),
+                Some(DiskIo {
+                    read: 60,
+                    written: 2000
+                })
+            );
+            assert_eq!(DiskUsage::default().total(), None);
+        }
+    }
+}
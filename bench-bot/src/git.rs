@@ -0,0 +1,127 @@
+use serde::Serialize;
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// The commit a workspace was benchmarked at.
+#[derive(PartialEq, Debug, Clone, Serialize)]
+pub struct Revision {
+    pub commit: String,
+    /// Whether the working tree had uncommitted changes.
+    pub dirty: bool,
+}
+
+impl Revision {
+    /// `None` if `dir` is not inside a git repository or git is missing.
+    pub fn of(dir: &Path) -> Option<Self> {
+        let commit = git(dir, &["rev-parse", "HEAD"])?;
+        let status = git(dir, &["status", "--porcelain", "--", "."])?;
+
+        Some(Self {
+            commit,
+            dirty: !status.is_empty(),
+        })
+    }
+
+    /// Short hash, marked with `-dirty` for uncommitted changes.
+    pub fn short(&self) -> String {
+        let short = &self.commit[..self.commit.len().min(7)];
+
+        if self.dirty {
+            format!("{}-dirty", short)
+        } else {
+            short.to_string()
+        }
+    }
+}
+
+/// A detached checkout of another revision of the repository, removed on
+/// drop.
+pub struct Worktree {
+    /// Any directory of the repository the worktree was added from.
+    repo_dir: PathBuf,
+    pub path: PathBuf,
+}
+
+impl Worktree {
+    /// Check `rev` out into `path`, from the repository `repo_dir` is in.
+    pub fn add(repo_dir: &Path, rev: &str, path: &Path) -> Result<Self, String> {
+        let output = Command::new("git")
+            .args(["worktree", "add", "--detach"])
+            .arg(path)
+            .arg(rev)
+            .current_dir(repo_dir)
+            .output()
+            .map_err(|e| format!("could not run git: {}", e))?;
+
+        if output.status.success() {
+            Ok(Self {
+                repo_dir: repo_dir.to_path_buf(),
+                path: path.to_path_buf(),
+            })
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        }
+    }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        let removed = Command::new("git")
+            .args(["worktree", "remove", "--force"])
+            .arg(&self.path)
+            .current_dir(&self.repo_dir)
+            .output()
+            .is_ok_and(|output| output.status.success());
+
+        if !removed {
+            log::warn!("Could not remove git worktree {:?}.", self.path);
+        }
+    }
+}
+
+/// Path of `dir` relative to the root of its repository, e.g. `benchmark/`.
+pub fn prefix(dir: &Path) -> Option<String> {
+    git(dir, &["rev-parse", "--show-prefix"])
+}
+
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()?;
+
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod revision {
+        use super::*;
+
+        #[test]
+        fn short() {
+            let mut given = Revision {
+                commit: "073a6771c1a3b4c1d2e3f4a5b6c7d8e9f0a1b2c3".to_string(),
+                dirty: false,
+            };
+            assert_eq!(given.short(), "073a677");
+
+            given.dirty = true;
+            assert_eq!(given.short(), "073a677-dirty");
+        }
+
+        #[test]
+        fn not_a_repo() {
+            assert_eq!(Revision::of(Path::new("/")), None);
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use crate::{
+    port,
+    report::{Metrics, MetricsError},
+};
+use serde::Deserialize;
+use std::path::Path;
+
+/// What the servers are benchmarked over.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Protocol {
+    Http,
+    /// Unary gRPC calls through `ghz`.
+    Grpc,
+}
+
+/// The `ghz` call of `--protocol grpc`.
+pub struct Call<'a> {
+    /// Fully qualified method, e.g. `helloworld.Greeter/SayHello`.
+    pub method: &'a str,
+    /// Request message as JSON.
+    pub data: &'a str,
+    /// Service definition, server reflection is used without one.
+    pub proto: Option<&'a Path>,
+    /// Timeout of every call in ms, ghz's 20s without one.
+    pub timeout_ms: Option<u64>,
+    /// Certificate and key to call over mutual TLS, in plaintext without.
+    pub client_cert: Option<(&'a Path, &'a Path)>,
+}
+
+impl Call<'_> {
+    /// Program followed by its arguments, with a JSON summary on stdout.
+    pub fn command(&self, url: &str, connections: usize, duration: &str) -> Vec<String> {
+        let target = port::host_port(url)
+            .map_or(url.to_string(), |(host, port)| format!("{}:{}", host, port));
+        let connections = connections.to_string();
+
+        let mut command = vec!["ghz".to_string()];
+        match self.client_cert {
+            // the servers' certificates are rarely signed by a CA ghz knows.
+            Some((cert, key)) => command.extend([
+                "--cert".to_string(),
+                cert.display().to_string(),
+                "--key".to_string(),
+                key.display().to_string(),
+                "--skipTLS".to_string(),
+            ]),
+            None => command.push("--insecure".to_string()),
+        }
+        command
+            .extend(["--format", "json", "--call", self.method, "-d", self.data].map(String::from));
+        if let Some(proto) = self.proto {
+            command.extend(["--proto".to_string(), proto.display().to_string()]);
+        }
+        if let Some(timeout_ms) = self.timeout_ms {
+            command.extend(["--timeout".to_string(), format!("{}ms", timeout_ms)]);
+        }
+        // as many concurrent workers as connections, one call in flight each.
+        command.extend(
+            [
+                "-c",
+                &connections,
+                "--connections",
+                &connections,
+                "-z",
+                duration,
+            ]
+            .map(String::from),
+        );
+        command.push(target);
+
+        command
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Summary {
+    count: u64,
+    rps: f64,
+    /// Latencies in nanoseconds.
+    average: f64,
+    fastest: f64,
+    slowest: f64,
+    #[serde(default)]
+    latency_distribution: Option<Vec<Percentile>>,
+    #[serde(default)]
+    status_code_distribution: Option<std::collections::HashMap<String, u64>>,
+}
+
+#[derive(Deserialize)]
+struct Percentile {
+    percentage: u32,
+    latency: f64,
+}
+
+// parse the JSON summary of `ghz --format json`, e.g.
+// {"count":200,"total":2000000000,"average":5000000,"fastest":1000000,
+//  "slowest":9000000,"rps":100.0,"latencyDistribution":[{"percentage":99,
+//  "latency":8000000}],"statusCodeDistribution":{"OK":198,"Unavailable":2}}
+pub fn parse(output: &str) -> Result<Metrics, MetricsError> {
+    let summary = serde_json::from_str::<Summary>(output).map_err(|_| MetricsError::ParseError)?;
+
+    let ms = |nanos: f64| format!("{:.2}ms", nanos / 1_000_000.0);
+    let codes = summary.status_code_distribution.unwrap_or_default();
+    let ok = codes.get("OK").copied().unwrap_or(summary.count);
+    let errors = codes
+        .iter()
+        .filter(|(code, _)| *code != "OK")
+        .map(|(_, count)| count)
+        .sum::<u64>();
+    let p99 = summary
+        .latency_distribution
+        .unwrap_or_default()
+        .iter()
+        .find(|p| p.percentage == 99)
+        .map(|p| ms(p.latency));
+
+    Metrics::from_captures(|name| match name {
+        "rps" => Some(format!("{:.2}", summary.rps)),
+        "total" => Some(ok.to_string()),
+        "avg" => Some(ms(summary.average)),
+        "min" => Some(ms(summary.fastest)),
+        "max" => Some(ms(summary.slowest)),
+        "p99" => p99.clone(),
+        "errors" => Some(errors.to_string()),
+        "timeouts" => codes.get("DeadlineExceeded").map(u64::to_string),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = r#"{"date":"2022-08-01T12:00:00Z","count":200,"total":2000000000,
+                "average":5000000,"fastest":1000000,"slowest":9000000,"rps":100.0,
+                "latencyDistribution":[{"percentage":50,"latency":4000000},
+                {"percentage":99,"latency":8000000}],
+                "statusCodeDistribution":{"OK":198,"Unavailable":2}}"#;
+
+            let actual = parse(given);
+
+            let expect = Metrics::from_captures(|name| {
+                Some(
+                    match name {
+                        "rps" => "100.00",
+                        "total" => "198",
+                        "avg" => "5.00ms",
+                        "min" => "1.00ms",
+                        "max" => "9.00ms",
+                        "p99" => "8.00ms",
+                        "errors" => "2",
+                        _ => return None,
+                    }
+                    .to_string(),
+                )
+            });
+
+            assert_eq!(actual, expect);
+        }
+
+        #[test]
+        fn not_json() {
+            assert_eq!(parse("connection refused"), Err(MetricsError::ParseError));
+        }
+    }
+
+    mod call {
+        use super::*;
+
+        #[test]
+        fn command() {
+            let given = Call {
+                method: "helloworld.Greeter/SayHello",
+                data: r#"{"name":"bench"}"#,
+                proto: None,
+                timeout_ms: None,
+                client_cert: None,
+            };
+
+            let actual = given.command("http://127.0.0.1:50051/", 50, "30s");
+
+            assert_eq!(
+                actual,
+                [
+                    "ghz",
+                    "--insecure",
+                    "--format",
+                    "json",
+                    "--call",
+                    "helloworld.Greeter/SayHello",
+                    "-d",
+                    r#"{"name":"bench"}"#,
+                    "-c",
+                    "50",
+                    "--connections",
+                    "50",
+                    "-z",
+                    "30s",
+                    "127.0.0.1:50051",
+                ]
+            );
+        }
+
+        #[test]
+        fn timeout() {
+            let given = Call {
+                method: "helloworld.Greeter/SayHello",
+                data: r#"{"name":"bench"}"#,
+                proto: None,
+                timeout_ms: Some(500),
+                client_cert: None,
+            };
+
+            let actual = given.command("http://127.0.0.1:50051/", 50, "30s");
+
+            assert_eq!(
+                actual,
+                [
+                    "ghz",
+                    "--insecure",
+                    "--format",
+                    "json",
+                    "--call",
+                    "helloworld.Greeter/SayHello",
+                    "-d",
+                    r#"{"name":"bench"}"#,
+                    "--timeout",
+                    "500ms",
+                    "-c",
+                    "50",
+                    "--connections",
+                    "50",
+                    "-z",
+                    "30s",
+                    "127.0.0.1:50051",
+                ]
+            );
+        }
+
+        #[test]
+        fn client_cert() {
+            let given = Call {
+                method: "helloworld.Greeter/SayHello",
+                data: "{}",
+                proto: None,
+                timeout_ms: None,
+                client_cert: Some((Path::new("client.pem"), Path::new("client.key"))),
+            };
+
+            let actual = given.command("https://127.0.0.1:50051/", 50, "30s");
+
+            assert_eq!(
+                actual[..7],
+                [
+                    "ghz",
+                    "--cert",
+                    "client.pem",
+                    "--key",
+                    "client.key",
+                    "--skipTLS",
+                    "--format"
+                ]
+            );
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use serde::Serialize;
+use std::fs;
+use sysinfo::{CpuExt, System, SystemExt};
+
+/// The machine a run happened on, needed to compare reports across
+/// machines.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HardwareInfo {
+    pub cpu: String,
+    pub physical_cores: Option<usize>,
+    pub threads: usize,
+    /// As reported by sysinfo at startup, scaling changes it during runs.
+    pub frequency_mhz: u64,
+    /// Nominal clock, only published by some cpufreq drivers.
+    pub base_frequency_mhz: Option<u64>,
+    pub total_memory_mb: u64,
+    pub os: Option<String>,
+    pub kernel: Option<String>,
+}
+
+impl HardwareInfo {
+    pub fn of(sys: &System) -> Self {
+        let os = match (sys.name(), sys.os_version()) {
+            (Some(name), Some(version)) => Some(format!("{} {}", name, version)),
+            (name, _) => name,
+        };
+
+        Self {
+            cpu: sys.global_cpu_info().brand().to_owned(),
+            physical_cores: sys.physical_core_count(),
+            threads: sys.cpus().len(),
+            frequency_mhz: sys.cpus().first().map_or(0, CpuExt::frequency),
+            base_frequency_mhz: base_frequency_mhz(),
+            // sysinfo reports KB.
+            total_memory_mb: sys.total_memory() / 1024,
+            os,
+            kernel: sys.kernel_version(),
+        }
+    }
+
+    /// Markdown bullet list of every known field.
+    pub fn render(&self) -> String {
+        let unknown = || "unknown".to_string();
+        let cores = self
+            .physical_cores
+            .map_or_else(unknown, |cores| cores.to_string());
+        let base = self
+            .base_frequency_mhz
+            .map_or_else(unknown, |frequency| format!("{} MHz", frequency));
+
+        [
+            format!("- Cpu: {} at {} MHz", self.cpu, self.frequency_mhz),
+            format!("- Base clock: {}", base),
+            format!("- Cores: {} physical, {} threads", cores, self.threads),
+            format!("- Memory: {} MB", self.total_memory_mb),
+            format!("- OS: {}", self.os.clone().unwrap_or_else(unknown)),
+            format!("- Kernel: {}", self.kernel.clone().unwrap_or_else(unknown)),
+        ]
+        .join("\n")
+    }
+}
+
+fn base_frequency_mhz() -> Option<u64> {
+    let khz = fs::read_to_string("/sys/devices/system/cpu/cpu0/cpufreq/base_frequency").ok()?;
+    khz.trim().parse::<u64>().ok().map(|khz| khz / 1000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod hardware_info {
+        use super::*;
+
+        #[test]
+        fn render() {
+            let given = HardwareInfo {
+                cpu: "AMD Ryzen 7 5800X 8-Core Processor".to_string(),
+                physical_cores: Some(8),
+                threads: 16,
+                frequency_mhz: 3800,
+                base_frequency_mhz: None,
+                total_memory_mb: 32017,
+                os: Some("Ubuntu 22.04".to_string()),
+                kernel: None,
+            };
+
+            let expect = r#"
+- Cpu: AMD Ryzen 7 5800X 8-Core Processor at 3800 MHz
+- Base clock: unknown
+- Cores: 8 physical, 16 threads
+- Memory: 32017 MB
+- OS: Ubuntu 22.04
+- Kernel: unknown
+"#
+            .trim();
+
+            assert_eq!(given.render(), expect);
+        }
+    }
+}
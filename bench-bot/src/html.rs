@@ -0,0 +1,189 @@
+/// Width of a chart, bars scale to what is left of it after the labels.
+const CHART_WIDTH: usize = 720;
+const LABEL_WIDTH: usize = 200;
+const BAR_HEIGHT: usize = 22;
+
+// sorts the table by the clicked column, numerically when every cell of it
+// starts with a number, e.g. `1,006,342.33` or `13.7MB`.
+const SORT_SCRIPT: &str = r#"
+document.querySelectorAll("table.sortable th").forEach((th, column) => {
+  th.addEventListener("click", () => {
+    const body = th.closest("table").tBodies[0];
+    const rows = Array.from(body.rows);
+    const number = (row) => parseFloat(row.cells[column].textContent.replace(/,/g, ""));
+    const numeric = rows.every((row) => !isNaN(number(row)));
+    const descending = th.dataset.order !== "desc";
+    rows.sort((a, b) => {
+      const order = numeric
+        ? number(a) - number(b)
+        : a.cells[column].textContent.localeCompare(b.cells[column].textContent);
+      return descending ? -order : order;
+    });
+    th.closest("tr").querySelectorAll("th").forEach((other) => delete other.dataset.order);
+    th.dataset.order = descending ? "desc" : "asc";
+    rows.forEach((row) => body.appendChild(row));
+  });
+});
+"#;
+
+const STYLE: &str = r#"
+body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: right; }
+th:first-child, td:first-child { text-align: left; }
+th { background: #f3f3f3; cursor: pointer; user-select: none; }
+th[data-order="asc"]::after { content: " \25b2"; }
+th[data-order="desc"]::after { content: " \25bc"; }
+pre { background: #f6f6f6; padding: 1em; overflow-x: auto; }
+"#;
+
+/// Escape `text` for an HTML element or attribute.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A table the page's script sorts by clicking a header.
+pub fn sortable_table<H: AsRef<str>, S: AsRef<str>>(header: &[H], rows: &[Vec<S>]) -> String {
+    fn cells<S: AsRef<str>>(tag: &str, cells: &[S]) -> String {
+        cells
+            .iter()
+            .map(|cell| format!("<{0}>{1}</{0}>", tag, escape(cell.as_ref())))
+            .collect()
+    }
+
+    let mut table = format!(
+        "<table class=\"sortable\">\n<thead><tr>{}</tr></thead>\n<tbody>\n",
+        cells("th", header)
+    );
+    for row in rows {
+        table.push_str(&format!("<tr>{}</tr>\n", cells("td", row)));
+    }
+    table.push_str("</tbody>\n</table>");
+
+    table
+}
+
+/// Horizontal bar chart of `bars` as inline SVG, scaled to the largest.
+pub fn bar_chart(title: &str, bars: &[(String, f64)], unit: &str) -> String {
+    let max = bars.iter().map(|(_, value)| *value).fold(0.0, f64::max);
+    let scale = |value: f64| {
+        let room = (CHART_WIDTH - LABEL_WIDTH - 120) as f64;
+        if max > 0.0 {
+            value / max * room
+        } else {
+            0.0
+        }
+    };
+    let height = BAR_HEIGHT * (bars.len() + 1);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" role=\"img\">\n\
+         <text x=\"0\" y=\"15\" font-weight=\"bold\">{}</text>\n",
+        CHART_WIDTH,
+        height,
+        escape(title)
+    );
+    for (i, (label, value)) in bars.iter().enumerate() {
+        let y = BAR_HEIGHT * (i + 1);
+        let width = scale(*value);
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\
+             <rect x=\"{}\" y=\"{}\" width=\"{:.1}\" height=\"{}\" fill=\"#4a7fb5\"/>\
+             <text x=\"{:.1}\" y=\"{}\">{:.2}{}</text>\n",
+            LABEL_WIDTH - 8,
+            y + 15,
+            escape(label),
+            LABEL_WIDTH,
+            y + 3,
+            width,
+            BAR_HEIGHT - 6,
+            LABEL_WIDTH as f64 + width + 6.0,
+            y + 15,
+            value,
+            escape(unit)
+        ));
+    }
+    svg.push_str("</svg>");
+
+    svg
+}
+
+/// A self-contained page: `sections` of already rendered HTML, each under
+/// its heading, with the style and the sort script embedded.
+pub fn page(title: &str, intro: &str, sections: &[(&str, String)]) -> String {
+    let mut body = format!("<h1>{}</h1>\n<p>{}</p>\n", escape(title), escape(intro));
+    for (heading, html) in sections.iter().filter(|(_, html)| !html.is_empty()) {
+        body.push_str(&format!("<h2>{}</h2>\n{}\n", escape(heading), html));
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}<script>{}</script>\n\
+         </body>\n</html>\n",
+        escape(title),
+        STYLE,
+        body,
+        SORT_SCRIPT
+    )
+}
+
+/// Markdown shown as written, the report sections not being worth a
+/// Markdown renderer.
+pub fn preformatted(markdown: &str) -> String {
+    if markdown.trim().is_empty() {
+        String::new()
+    } else {
+        format!("<pre>{}</pre>", escape(markdown.trim()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod sortable_table {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let actual = sortable_table(
+                &["Framework Name", "Req/Sec"],
+                &[vec!["<axum>", "692,354.35"]],
+            );
+
+            assert_eq!(
+                actual,
+                "<table class=\"sortable\">\n\
+                 <thead><tr><th>Framework Name</th><th>Req/Sec</th></tr></thead>\n\
+                 <tbody>\n<tr><td>&lt;axum&gt;</td><td>692,354.35</td></tr>\n</tbody>\n</table>"
+            );
+        }
+    }
+
+    mod bar_chart {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = [
+                ("actix-web".to_string(), 200.0),
+                ("axum".to_string(), 100.0),
+            ];
+
+            let actual = bar_chart("Req/Sec", &given, "");
+
+            assert!(actual.starts_with("<svg "));
+            assert!(actual.contains("width=\"400.0\""), "{}", actual);
+            assert!(actual.contains("width=\"200.0\""), "{}", actual);
+            assert!(actual.contains(">100.00</text>"));
+        }
+
+        #[test]
+        fn empty() {
+            assert!(!bar_chart("Req/Sec", &[], "").contains("<rect"));
+        }
+    }
+}
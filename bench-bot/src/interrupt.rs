@@ -0,0 +1,152 @@
+use crate::process;
+use std::{
+    io,
+    process::{Child, Command},
+    sync::{
+        atomic::{AtomicI32, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+
+/// The signal received, zero until then. Set by the signal handler, which
+/// can do nothing else safely.
+static SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+/// Children running right now, builds, servers and load generators alike.
+static CHILDREN: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+const POLL: Duration = Duration::from_millis(50);
+
+/// Added to the signal number for the exit code, as shells report a
+/// process ended by a signal: 130 for SIGINT, 143 for SIGTERM.
+const EXIT_SIGNALED: i32 = 128;
+
+#[cfg(unix)]
+const SIGINT: i32 = 2;
+#[cfg(unix)]
+const SIGTERM: i32 = 15;
+
+#[cfg(unix)]
+extern "C" {
+    fn signal(signum: i32, handler: extern "C" fn(i32)) -> usize;
+    fn kill(pid: i32, signum: i32) -> i32;
+}
+
+#[cfg(unix)]
+extern "C" fn on_signal(signum: i32) {
+    SIGNAL.store(signum, Ordering::SeqCst);
+}
+
+/// On Ctrl-C or SIGTERM, kill every tracked child along with whatever it
+/// spawned and exit, instead of leaving a build or a server orphaned.
+pub fn install() {
+    #[cfg(unix)]
+    {
+        // SAFETY: the handler only stores to an atomic, which is
+        // async-signal-safe.
+        unsafe {
+            signal(SIGINT, on_signal);
+            signal(SIGTERM, on_signal);
+        }
+    }
+
+    thread::spawn(|| {
+        let signum = loop {
+            match SIGNAL.load(Ordering::SeqCst) {
+                0 => thread::sleep(POLL),
+                signum => break signum,
+            }
+        };
+
+        // held until exit, so that no child is spawned after the kill.
+        let children = CHILDREN.lock().unwrap_or_else(|e| e.into_inner());
+        log::warn!(
+            "Interrupted, stopping {} running process(es).",
+            children.len()
+        );
+        kill_trees(&children);
+        std::process::exit(EXIT_SIGNALED + signum);
+    });
+}
+
+/// Send SIGINT to `pid`, as Ctrl-C would, for children that only report
+/// when interrupted.
+pub fn interrupt(pid: u32) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        // SAFETY: kill has no memory effects, a stale pid fails with ESRCH.
+        if unsafe { kill(pid as i32, SIGINT) } == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = pid;
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+}
+
+/// Kill `pids` and their descendants, deepest first so that no wrapper
+/// respawns or outlives its children.
+fn kill_trees(pids: &[u32]) {
+    let mut sys = System::new();
+    sys.refresh_processes();
+
+    for pid in pids {
+        let pid = Pid::from_u32(*pid);
+        for child in process::descendants(&sys, pid)
+            .into_iter()
+            .rev()
+            .chain([pid])
+        {
+            if let Some(process) = sys.process(child) {
+                process.kill();
+            }
+        }
+    }
+}
+
+/// A child killed on interruption for as long as it is held.
+pub struct Tracked(u32);
+
+/// Spawn `command` and track it under the same lock, so that an
+/// interruption never lands between the two and misses the child.
+pub fn spawn(command: &mut Command) -> io::Result<(Child, Tracked)> {
+    let mut children = CHILDREN.lock().unwrap_or_else(|e| e.into_inner());
+    let child = command.spawn()?;
+    children.push(child.id());
+    let tracked = Tracked(child.id());
+    Ok((child, tracked))
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        if let Ok(mut children) = CHILDREN.lock() {
+            children.retain(|pid| *pid != self.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod spawn {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let (mut child, tracked) = spawn(&mut Command::new("true")).unwrap();
+            assert!(CHILDREN.lock().unwrap().contains(&child.id()));
+
+            child.wait().unwrap();
+            drop(tracked);
+            assert!(!CHILDREN.lock().unwrap().contains(&child.id()));
+        }
+    }
+}
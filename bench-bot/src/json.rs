@@ -0,0 +1,146 @@
+use crate::{git::Revision, hardware::HardwareInfo, report::Report};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Context of a run, written next to the reports of the JSON output.
+#[derive(Debug, Clone, Serialize)]
+pub struct Metadata {
+    pub cpu: String,
+    pub hardware: HardwareInfo,
+    pub command: String,
+    pub git: Option<Revision>,
+    /// `--label` of the run.
+    pub label: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Output<'a> {
+    metadata: &'a Metadata,
+    reports: &'a [Report],
+}
+
+/// Indented for reading with `pretty`, on a single line otherwise.
+pub fn generate(metadata: &Metadata, reports: &[Report], pretty: bool) -> String {
+    to_string(&Output { metadata, reports }, pretty)
+}
+
+/// What `--summary-json` prints to stdout once the run is over, a verdict
+/// for scripts that don't want to read the full output.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Summary {
+    /// The framework with the most requests/sec of every category.
+    winners: BTreeMap<String, Winner>,
+    /// Frameworks there were to benchmark.
+    frameworks: usize,
+    /// Frameworks without a report, failed builds included.
+    failures: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Winner {
+    framework: String,
+    req_per_sec: f64,
+}
+
+impl Summary {
+    pub fn of(reports: &[Report], frameworks: usize) -> Self {
+        summary(
+            &serde_json::to_value(reports).expect("serialize reports"),
+            frameworks,
+        )
+    }
+}
+
+/// From the reports as serialized into the JSON output.
+fn summary(reports: &Value, frameworks: usize) -> Summary {
+    let reports = reports.as_array().map_or(&[][..], Vec::as_slice);
+
+    let mut winners = BTreeMap::<String, Winner>::new();
+    for report in reports {
+        let (Some(bench_type), Some(framework), Some(req_per_sec)) = (
+            report["bench_type"].as_str(),
+            report["framework_name"].as_str(),
+            report["metrics"]["request"]["req_per_sec"]
+                .as_str()
+                .and_then(|rps| rps.parse::<f64>().ok()),
+        ) else {
+            continue;
+        };
+        if winners
+            .get(bench_type)
+            .is_none_or(|winner| req_per_sec > winner.req_per_sec)
+        {
+            let framework = framework.to_string();
+            winners.insert(
+                bench_type.to_string(),
+                Winner {
+                    framework,
+                    req_per_sec,
+                },
+            );
+        }
+    }
+
+    Summary {
+        winners,
+        frameworks,
+        failures: frameworks.saturating_sub(reports.len()),
+    }
+}
+
+/// Every JSON artifact is written through here, so they agree on `pretty`.
+pub fn to_string(value: &impl Serialize, pretty: bool) -> String {
+    if pretty {
+        serde_json::to_string_pretty(value).expect("serialize reports")
+    } else {
+        serde_json::to_string(value).expect("serialize reports")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod summary {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let report = |bench_type: &str, framework: &str, req_per_sec: &str| {
+                serde_json::json!({
+                    "bench_type": bench_type,
+                    "framework_name": framework,
+                    "metrics": { "request": { "req_per_sec": req_per_sec } },
+                })
+            };
+            let given = Value::Array(vec![
+                report("hello-world", "axum", "692354.35"),
+                report("hello-world", "actix-web", "1006342.33"),
+                report("json", "warp", "-"),
+            ]);
+
+            let actual = to_string(&summary(&given, 4), false);
+
+            assert_eq!(
+                actual,
+                r#"{"winners":{"hello-world":{"framework":"actix-web","req_per_sec":1006342.33}},"frameworks":4,"failures":1}"#
+            );
+        }
+    }
+
+    mod to_string {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = serde_json::json!({ "framework_name": "axum" });
+
+            assert_eq!(to_string(&given, false), r#"{"framework_name":"axum"}"#);
+            assert_eq!(
+                to_string(&given, true),
+                "{\n  \"framework_name\": \"axum\"\n}"
+            );
+        }
+    }
+}
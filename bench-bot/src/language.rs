@@ -0,0 +1,105 @@
+use serde::Serialize;
+use std::{fmt, fs, path::Path};
+
+/// Language a member is written in, told apart by its directory name:
+/// Go members are prefixed with `go_`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Language {
+    Rust,
+    Go,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::Rust, Language::Go];
+
+    pub fn of(member: &Path) -> Self {
+        let go = member
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with("go_"));
+        if go {
+            Language::Go
+        } else {
+            Language::Rust
+        }
+    }
+
+    /// Why `dir` is no buildable project of this language, if it isn't: a
+    /// `Cargo.toml` for Rust, a `go.mod` or `.go` files for Go.
+    pub fn missing_project(self, dir: &Path) -> Option<String> {
+        if !dir.is_dir() {
+            return Some(format!("{:?} is not a directory", dir));
+        }
+
+        let buildable = match self {
+            Language::Rust => dir.join("Cargo.toml").is_file(),
+            Language::Go => {
+                dir.join("go.mod").is_file()
+                    || fs::read_dir(dir)
+                        .into_iter()
+                        .flatten()
+                        .flatten()
+                        .any(|entry| entry.path().extension().is_some_and(|ext| ext == "go"))
+            }
+        };
+        match (buildable, self) {
+            (true, _) => None,
+            (false, Language::Rust) => Some(format!("{:?} has no Cargo.toml", dir)),
+            (false, Language::Go) => Some(format!("{:?} has no go.mod or .go files", dir)),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Language::Rust => "Rust",
+            Language::Go => "Go",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    mod language {
+        use super::*;
+
+        #[test]
+        fn of() {
+            assert_eq!(
+                Language::of(&PathBuf::from("hello-world/go_fasthttp")),
+                Language::Go
+            );
+            assert_eq!(
+                Language::of(&PathBuf::from("hello-world/axum")),
+                Language::Rust
+            );
+            assert_eq!(
+                Language::of(&PathBuf::from("go_nested/axum")),
+                Language::Rust
+            );
+        }
+
+        #[test]
+        fn missing_project() {
+            let root =
+                std::env::temp_dir().join(format!("bench-bot-language-{}", std::process::id()));
+            let (axum, go) = (root.join("axum"), root.join("go_nethttp"));
+            fs::create_dir_all(&axum).unwrap();
+            fs::create_dir_all(&go).unwrap();
+
+            assert!(Language::Rust.missing_project(&axum).is_some());
+            assert!(Language::Go.missing_project(&go).is_some());
+            assert!(Language::Rust.missing_project(&root.join("warp")).is_some());
+
+            fs::write(axum.join("Cargo.toml"), "").unwrap();
+            fs::write(go.join("main.go"), "").unwrap();
+            assert_eq!(Language::Rust.missing_project(&axum), None);
+            assert_eq!(Language::Go.missing_project(&go), None);
+
+            fs::remove_dir_all(&root).unwrap();
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{probe, stats};
+use serde::Serialize;
+use std::{
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// Latency of the foreground requests `--probe-interval-ms` sends while
+/// the load generator runs.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(into = "ProbeSummary")]
+pub struct ProbeLatency {
+    /// Latency of every successful probe, sorted.
+    latencies: Vec<Duration>,
+    /// Probes failed on the connection or answered with an unexpected
+    /// status.
+    failed: usize,
+}
+
+impl ProbeLatency {
+    fn new(mut latencies: Vec<Duration>, failed: usize) -> Self {
+        latencies.sort();
+        Self { latencies, failed }
+    }
+
+    fn percentile(&self, p: f64) -> Option<Duration> {
+        stats::percentile(&self.latencies, p)
+    }
+
+    /// In ms, `None` without a successful probe.
+    pub fn p99(&self) -> Option<String> {
+        self.percentile(0.99).map(ms)
+    }
+
+    /// e.g. `p50 1.20ms, p90 2.31ms, p99 8.02ms, max 9.10ms over 120 probes (1 failed)`.
+    pub fn render(&self) -> String {
+        let total = self.latencies.len() + self.failed;
+        let failed = if self.failed > 0 {
+            format!(" ({} failed)", self.failed)
+        } else {
+            String::new()
+        };
+
+        match (
+            self.percentile(0.5),
+            self.percentile(0.9),
+            self.percentile(0.99),
+            self.latencies.last(),
+        ) {
+            (Some(p50), Some(p90), Some(p99), Some(max)) => format!(
+                "p50 {}, p90 {}, p99 {}, max {} over {} probes{}",
+                ms(p50),
+                ms(p90),
+                ms(p99),
+                ms(*max),
+                total,
+                failed
+            ),
+            _ => format!("no successful probe out of {}", total),
+        }
+    }
+}
+
+/// `ProbeLatency` in the JSON output, its percentiles in ms rather than
+/// every probe.
+#[derive(Serialize)]
+struct ProbeSummary {
+    p50_ms: Option<f64>,
+    p90_ms: Option<f64>,
+    p99_ms: Option<f64>,
+    max_ms: Option<f64>,
+    probes: usize,
+    failed: usize,
+}
+
+impl From<ProbeLatency> for ProbeSummary {
+    fn from(latency: ProbeLatency) -> Self {
+        let ms = |latency: Option<Duration>| latency.map(|latency| latency.as_secs_f64() * 1000.0);
+
+        Self {
+            p50_ms: ms(latency.percentile(0.5)),
+            p90_ms: ms(latency.percentile(0.9)),
+            p99_ms: ms(latency.percentile(0.99)),
+            max_ms: ms(latency.latencies.last().copied()),
+            probes: latency.latencies.len() + latency.failed,
+            failed: latency.failed,
+        }
+    }
+}
+
+fn ms(latency: Duration) -> String {
+    format!("{:.2}ms", latency.as_secs_f64() * 1000.0)
+}
+
+/// Probes sent every interval on their own thread, each on a new
+/// connection so that they queue behind the load like a new client's.
+pub struct Prober {
+    stop: Sender<()>,
+    thread: JoinHandle<ProbeLatency>,
+}
+
+impl Prober {
+    pub fn start(url: &str, interval: Duration, expect_status: &[u16]) -> Self {
+        let (stop, stopped) = mpsc::channel();
+        let url = url.to_string();
+        let expect_status = expect_status.to_vec();
+        let thread = thread::spawn(move || probe_until(&url, interval, &expect_status, &stopped));
+
+        Self { stop, thread }
+    }
+
+    pub fn stop(self) -> ProbeLatency {
+        let _ = self.stop.send(());
+        self.thread.join().unwrap_or_default()
+    }
+}
+
+fn probe_until(
+    url: &str,
+    interval: Duration,
+    expect_status: &[u16],
+    stopped: &Receiver<()>,
+) -> ProbeLatency {
+    let mut latencies = Vec::new();
+    let mut failed = 0;
+    let mut next = Instant::now();
+
+    loop {
+        next += interval;
+        let start = Instant::now();
+        match probe::get(url, &[]) {
+            Ok(response) if probe::is_expected(response.status, expect_status) => {
+                latencies.push(start.elapsed())
+            }
+            Ok(_) | Err(_) => failed += 1,
+        }
+
+        // a slow probe delays the next one rather than piling up behind it.
+        let wait = next.saturating_duration_since(Instant::now());
+        match stopped.recv_timeout(wait) {
+            Err(RecvTimeoutError::Timeout) => next = next.max(Instant::now()),
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    ProbeLatency::new(latencies, failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+    };
+
+    mod probe_latency {
+        use super::*;
+
+        #[test]
+        fn render() {
+            let given = ProbeLatency::new((1..=100).rev().map(Duration::from_millis).collect(), 2);
+
+            assert_eq!(given.p99(), Some("99.00ms".to_string()));
+            assert_eq!(
+                given.render(),
+                "p50 50.00ms, p90 90.00ms, p99 99.00ms, max 100.00ms over 102 probes (2 failed)"
+            );
+        }
+
+        #[test]
+        fn serialize() {
+            let given = ProbeLatency::new((1..=100).map(Duration::from_millis).collect(), 2);
+
+            let actual = serde_json::to_value(given).unwrap();
+
+            assert_eq!(actual["p99_ms"], 99.0);
+            assert_eq!(actual["probes"], 102);
+            assert!(actual.get("latencies").is_none());
+        }
+
+        #[test]
+        fn none_succeeded() {
+            let given = ProbeLatency::new(Vec::new(), 3);
+
+            assert_eq!(given.p99(), None);
+            assert_eq!(given.render(), "no successful probe out of 3");
+        }
+    }
+
+    mod prober {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+            let url = format!("http://{}/", listener.local_addr().unwrap());
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let mut stream = stream.unwrap();
+                    let mut request = [0; 1024];
+                    let _ = stream.read(&mut request);
+                    let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok");
+                }
+            });
+
+            let prober = Prober::start(&url, Duration::from_millis(20), &[]);
+            thread::sleep(Duration::from_millis(110));
+            let actual = prober.stop();
+
+            assert_eq!(actual.failed, 0);
+            assert!((3..=7).contains(&actual.latencies.len()), "{:?}", actual);
+        }
+    }
+}
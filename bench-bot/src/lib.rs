@@ -0,0 +1,3 @@
+//! Building blocks of bench-bot usable outside of the binary.
+
+pub mod markdown;
@@ -0,0 +1,833 @@
+use crate::{
+    probe,
+    report::{Metrics, MetricsError},
+    stats,
+};
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Request timeout without `--request-timeout`.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What one `--tool builtin` run measured.
+#[derive(Debug)]
+pub struct LoadStats {
+    duration: Duration,
+    /// Latency of every successful request, sorted.
+    latencies: Vec<Duration>,
+    /// Time to the first byte of every successful response, sorted.
+    ttfbs: Vec<Duration>,
+    /// Response bytes read, headers included.
+    bytes: u64,
+    /// Requests failed on the connection or answered with an unexpected
+    /// status.
+    errors: u64,
+    /// Errors that were timeouts.
+    timeouts: u64,
+    /// Connections opened, reopened ones included.
+    opened: u64,
+}
+
+impl LoadStats {
+    fn new(duration: Duration, workers: Vec<Worker>) -> Self {
+        let mut latencies = workers
+            .iter()
+            .flat_map(|w| w.latencies.iter().copied())
+            .collect::<Vec<_>>();
+        latencies.sort();
+        let mut ttfbs = workers
+            .iter()
+            .flat_map(|w| w.ttfbs.iter().copied())
+            .collect::<Vec<_>>();
+        ttfbs.sort();
+
+        Self {
+            duration,
+            latencies,
+            ttfbs,
+            bytes: workers.iter().map(|w| w.bytes).sum(),
+            errors: workers.iter().map(|w| w.errors).sum(),
+            timeouts: workers.iter().map(|w| w.timeouts).sum(),
+            opened: workers.iter().map(|w| w.opened).sum(),
+        }
+    }
+
+    /// Requests sent, failed ones included.
+    pub fn sent(&self) -> u64 {
+        self.latencies.len() as u64 + self.errors
+    }
+
+    /// `p` in `0.0..=1.0`, nearest-rank.
+    fn percentile(&self, p: f64) -> Option<Duration> {
+        stats::percentile(&self.latencies, p)
+    }
+
+    /// The statistics as `name: value` lines, named after the captures of
+    /// `Metrics::from_captures`, which `parse` reads back.
+    pub fn render(&self) -> String {
+        let secs = self.duration.as_secs_f64().max(f64::EPSILON);
+        let ms = |latency: Duration| format!("{:.2}ms", latency.as_secs_f64() * 1000.0);
+        let total = self.latencies.len();
+
+        let mut lines = vec![
+            format!("total: {}", total),
+            format!("rps: {:.2}", total as f64 / secs),
+            format!("errors: {}", self.errors),
+            format!("timeouts: {}", self.timeouts),
+            format!("opened: {}", self.opened),
+            format!("transfer: {}", bytes(self.bytes as f64)),
+            format!("transfer_rate: {}/Sec", bytes(self.bytes as f64 / secs)),
+        ];
+
+        if total > 0 {
+            let latencies = self
+                .latencies
+                .iter()
+                .map(Duration::as_secs_f64)
+                .collect::<Vec<_>>();
+            let avg = latencies.iter().sum::<f64>() / total as f64;
+            let variance = latencies.iter().map(|l| (l - avg).powi(2)).sum::<f64>() / total as f64;
+
+            lines.extend([
+                format!("avg: {}", ms(Duration::from_secs_f64(avg))),
+                format!("stdev: {}", ms(Duration::from_secs_f64(variance.sqrt()))),
+                format!("min: {}", ms(self.latencies[0])),
+                format!("max: {}", ms(self.latencies[total - 1])),
+            ]);
+            lines.extend(self.percentile(0.5).map(|p50| format!("p50: {}", ms(p50))));
+            lines.extend(self.percentile(0.99).map(|p99| format!("p99: {}", ms(p99))));
+            lines.extend(
+                self.percentile(0.999)
+                    .map(|p999| format!("p999: {}", ms(p999))),
+            );
+            lines.extend(
+                self.percentile(0.9999)
+                    .map(|p9999| format!("p9999: {}", ms(p9999))),
+            );
+        }
+        if !self.ttfbs.is_empty() {
+            let avg = self.ttfbs.iter().sum::<Duration>() / self.ttfbs.len() as u32;
+
+            lines.push(format!("ttfb_avg: {}", ms(avg)));
+            lines.extend(
+                stats::percentile(&self.ttfbs, 0.5).map(|p50| format!("ttfb_p50: {}", ms(p50))),
+            );
+            lines.extend(
+                stats::percentile(&self.ttfbs, 0.99).map(|p99| format!("ttfb_p99: {}", ms(p99))),
+            );
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Read back the output of `LoadStats::render`.
+pub fn parse(output: &str) -> Result<Metrics, MetricsError> {
+    let values = output
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect::<Vec<_>>();
+
+    Metrics::from_captures(|name| {
+        values
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.to_string())
+    })
+}
+
+/// A request sent over and over on every connection, to each of its URLs
+/// in turn.
+pub struct Request {
+    targets: Vec<Target>,
+    keep_alive: bool,
+    /// Status codes of a successful response, any below 400 when empty.
+    expect_status: Vec<u16>,
+    /// Requests/sec over every connection, as fast as possible when `None`.
+    rate: Option<f64>,
+    /// Longest wait on a read or a write of the connection.
+    timeout: Duration,
+}
+
+/// One URL of a request, where it connects and what it sends.
+struct Target {
+    authority: String,
+    raw: Vec<u8>,
+}
+
+impl Target {
+    fn new(url: &str, headers: &[(&str, &str)], keep_alive: bool, body: Option<&str>) -> Self {
+        let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+        let path = if path.is_empty() { "/" } else { path };
+
+        let method = if body.is_some() { "POST" } else { "GET" };
+        let mut raw = format!("{} {} HTTP/1.1\r\nHost: {}\r\n", method, path, authority);
+        if !keep_alive {
+            raw.push_str("Connection: close\r\n");
+        }
+        for (key, value) in headers {
+            raw.push_str(&format!("{}: {}\r\n", key, value));
+        }
+        if let Some(body) = body {
+            raw.push_str(&format!("Content-Length: {}\r\n", body.len()));
+        }
+        raw.push_str("\r\n");
+        raw.push_str(body.unwrap_or_default());
+
+        Self {
+            authority: authority.to_string(),
+            raw: raw.into_bytes(),
+        }
+    }
+}
+
+impl Request {
+    /// `GET`, or `POST` with `body`, of every one of `urls`
+    /// (`http://host:port/path`) with the extra `headers`.
+    pub fn new(
+        urls: &[String],
+        headers: &[(&str, &str)],
+        keep_alive: bool,
+        body: Option<&str>,
+    ) -> Self {
+        Self {
+            targets: urls
+                .iter()
+                .map(|url| Target::new(url, headers, keep_alive, body))
+                .collect(),
+            keep_alive,
+            expect_status: Vec::new(),
+            rate: None,
+            timeout: TIMEOUT,
+        }
+    }
+
+    pub fn expecting(mut self, statuses: &[u16]) -> Self {
+        self.expect_status = statuses.to_vec();
+        self
+    }
+
+    pub fn with_rate(mut self, rate: Option<f64>) -> Self {
+        self.rate = rate.filter(|rate| *rate > 0.0);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout
+            .filter(|timeout| !timeout.is_zero())
+            .unwrap_or(TIMEOUT);
+        self
+    }
+
+    /// Whether every URL accepts a connection.
+    fn reachable(&self) -> io::Result<()> {
+        if self.targets.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "no url"));
+        }
+        for target in &self.targets {
+            drop(open(&target.authority, self.timeout)?);
+        }
+
+        Ok(())
+    }
+}
+
+/// Send `request` over `connections` connections, each with its own
+/// thread, for `duration`. A connection that fails is reopened. With a
+/// rate, every connection sends its share of it at a steady pace. Ends
+/// early once `stop` is set.
+///
+/// Blocking sockets on plain threads rather than hyper on an async
+/// runtime keep bench-bot free of both dependencies, and a thread per
+/// connection holds up at the connection counts benchmarked.
+pub fn bench(
+    request: &Request,
+    connections: usize,
+    duration: Duration,
+    stop: &AtomicBool,
+) -> io::Result<LoadStats> {
+    // fail fast, before spawning every connection, if nothing listens.
+    request.reachable()?;
+
+    let start = Instant::now();
+    let deadline = start + duration;
+    let interval = request
+        .rate
+        .map(|rate| Duration::from_secs_f64(connections.max(1) as f64 / rate));
+
+    let workers = thread::scope(|scope| {
+        let workers = (0..connections)
+            .map(|_| scope.spawn(|| Worker::run(request, deadline, interval, None, Some(stop))))
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("load generator worker panicked"))
+            .collect::<Vec<_>>()
+    });
+
+    Ok(LoadStats::new(start.elapsed(), workers))
+}
+
+/// Send `request` exactly `count` times over at most `connections`
+/// connections, or fewer if `max` runs out first.
+pub fn send(
+    request: &Request,
+    connections: usize,
+    count: u64,
+    max: Duration,
+) -> io::Result<LoadStats> {
+    request.reachable()?;
+
+    let start = Instant::now();
+    let deadline = start + max;
+    let remaining = AtomicU64::new(count);
+    let connections = connections.clamp(1, usize::try_from(count).unwrap_or(usize::MAX).max(1));
+
+    let workers = thread::scope(|scope| {
+        let workers = (0..connections)
+            .map(|_| scope.spawn(|| Worker::run(request, deadline, None, Some(&remaining), None)))
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("load generator worker panicked"))
+            .collect::<Vec<_>>()
+    });
+
+    Ok(LoadStats::new(start.elapsed(), workers))
+}
+
+#[derive(Default)]
+struct Worker {
+    latencies: Vec<Duration>,
+    ttfbs: Vec<Duration>,
+    bytes: u64,
+    errors: u64,
+    timeouts: u64,
+    opened: u64,
+}
+
+impl Worker {
+    /// Send requests until `deadline` or `stop`, or until `remaining`,
+    /// shared by every worker, is down to 0.
+    fn run(
+        request: &Request,
+        deadline: Instant,
+        interval: Option<Duration>,
+        remaining: Option<&AtomicU64>,
+        stop: Option<&AtomicBool>,
+    ) -> Self {
+        let mut worker = Self::default();
+        // the open connection and where to.
+        let mut stream: Option<(&str, BufReader<TcpStream>)> = None;
+        let mut next = Instant::now();
+        let mut targets = request.targets.iter().cycle();
+
+        while Instant::now() < deadline && !stop.is_some_and(|stop| stop.load(Ordering::Relaxed)) {
+            let taken = remaining.is_none_or(|remaining| {
+                remaining
+                    .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| {
+                        left.checked_sub(1)
+                    })
+                    .is_ok()
+            });
+            if !taken {
+                break;
+            }
+            if let Some(interval) = interval {
+                // a late request is sent right away, not made up for twice.
+                let now = Instant::now();
+                if next > now {
+                    thread::sleep(next - now);
+                }
+                next = next.max(now) + interval;
+            }
+            let target = targets.next().expect("a request has urls");
+            let start = Instant::now();
+            let mut reopen = || {
+                worker.opened += 1;
+                connect(&target.authority, request.timeout)
+                    .and_then(|stream| exchange(stream, &target.raw, start))
+            };
+            let reused = stream
+                .take()
+                .filter(|(authority, _)| *authority == target.authority);
+            let response = match reused {
+                Some((_, connection)) => match exchange(connection, &target.raw, start) {
+                    // the server closed the idle connection meanwhile.
+                    Err(e) if is_closed(&e) => reopen(),
+                    response => response,
+                },
+                None => reopen(),
+            };
+
+            match response {
+                Ok((connection, response, ttfb)) => {
+                    worker.bytes += response.bytes;
+                    if !probe::is_expected(response.status, &request.expect_status) {
+                        worker.errors += 1;
+                    } else {
+                        worker.latencies.push(start.elapsed());
+                        worker.ttfbs.push(ttfb);
+                    }
+                    if request.keep_alive && !response.close {
+                        stream = Some((&target.authority, connection));
+                    }
+                }
+                // a request cut off by the deadline is not an error.
+                Err(_) if Instant::now() >= deadline => {}
+                Err(e) => {
+                    worker.errors += 1;
+                    // a read timing out is `WouldBlock` on Unix, `TimedOut` on Windows.
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) {
+                        worker.timeouts += 1;
+                    }
+                }
+            }
+        }
+
+        worker
+    }
+}
+
+/// Send `raw` over `stream` and read the response, along with the time from
+/// `start` to its first byte.
+fn exchange(
+    mut stream: BufReader<TcpStream>,
+    raw: &[u8],
+    start: Instant,
+) -> io::Result<(BufReader<TcpStream>, Response, Duration)> {
+    stream.get_mut().write_all(raw)?;
+    // the first byte of the status line, the rest being read below.
+    if stream.fill_buf()?.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "connection closed before the response",
+        ));
+    }
+    let ttfb = start.elapsed();
+    let response = read_response(&mut stream)?;
+
+    Ok((stream, response, ttfb))
+}
+
+/// Whether `e` is the peer having closed the connection.
+fn is_closed(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::UnexpectedEof
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::BrokenPipe
+    )
+}
+
+fn connect(authority: &str, timeout: Duration) -> io::Result<BufReader<TcpStream>> {
+    let stream = open(authority, timeout)?;
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    Ok(BufReader::new(stream))
+}
+
+/// Connect to the first address of `authority` that accepts within
+/// `timeout`, rather than waiting on the system's own connect timeout.
+fn open(authority: &str, timeout: Duration) -> io::Result<TcpStream> {
+    let mut last = None;
+    for addr in authority.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last = Some(e),
+        }
+    }
+
+    Err(last.unwrap_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} resolves to no address", authority),
+        )
+    }))
+}
+
+#[derive(PartialEq, Debug)]
+struct Response {
+    status: u16,
+    bytes: u64,
+    /// Whether the server closes the connection after this response.
+    close: bool,
+}
+
+/// Read a whole response, its body sized by `Content-Length`, chunked or
+/// running until the connection closes.
+fn read_response(stream: &mut impl BufRead) -> io::Result<Response> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed http response");
+
+    let mut line = String::new();
+    let mut bytes = stream.read_line(&mut line)? as u64;
+    // "HTTP/1.1 200 OK"
+    let status = line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(invalid)?;
+
+    let mut length = None;
+    let mut chunked = false;
+    let mut close = line.starts_with("HTTP/1.0");
+    loop {
+        line.clear();
+        bytes += stream.read_line(&mut line)? as u64;
+        let Some((key, value)) = line.split_once(':') else {
+            if line.trim().is_empty() {
+                break;
+            }
+            return Err(invalid());
+        };
+
+        let (key, value) = (key.trim(), value.trim());
+        if key.eq_ignore_ascii_case("Content-Length") {
+            length = Some(value.parse::<u64>().map_err(|_| invalid())?);
+        } else if key.eq_ignore_ascii_case("Transfer-Encoding") {
+            chunked = value.eq_ignore_ascii_case("chunked");
+        } else if key.eq_ignore_ascii_case("Connection") {
+            close = value.eq_ignore_ascii_case("close");
+        }
+    }
+
+    bytes += match (chunked, length) {
+        (true, _) => skip_chunks(stream)?,
+        (false, Some(length)) => io::copy(&mut stream.take(length), &mut io::sink())?,
+        (false, None) => {
+            close = true;
+            io::copy(stream, &mut io::sink())?
+        }
+    };
+
+    Ok(Response {
+        status,
+        bytes,
+        close,
+    })
+}
+
+fn skip_chunks(stream: &mut impl BufRead) -> io::Result<u64> {
+    let mut bytes = 0;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        bytes += stream.read_line(&mut line)? as u64;
+        let size = line.trim().split(';').next().unwrap_or_default();
+        let size = u64::from_str_radix(size, 16)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "malformed chunk size"))?;
+
+        // the chunk and its trailing "\r\n", or the empty trailer.
+        bytes += io::copy(&mut stream.take(size + 2), &mut io::sink())?;
+        if size == 0 {
+            return Ok(bytes);
+        }
+    }
+}
+
+/// `bytes` in MB or GB, as rewrk prints them.
+fn bytes(bytes: f64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+
+    if bytes >= 1024.0 * MB {
+        format!("{:.2}GB", bytes / (1024.0 * MB))
+    } else {
+        format!("{:.2}MB", bytes / MB)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    mod read_response {
+        use super::*;
+
+        #[test]
+        fn content_length() {
+            let given = b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhelloHTTP/1.1 404";
+            let mut stream = Cursor::new(&given[..]);
+
+            let actual = read_response(&mut stream).unwrap();
+
+            assert_eq!(
+                actual,
+                Response {
+                    status: 200,
+                    bytes: 43,
+                    close: false
+                }
+            );
+            // the next response is left in the stream.
+            assert_eq!(stream.position(), 43);
+        }
+
+        #[test]
+        fn chunked() {
+            let given = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n5\r\nhello\r\n0\r\n\r\n";
+
+            let actual = read_response(&mut Cursor::new(&given[..])).unwrap();
+
+            assert_eq!(
+                actual,
+                Response {
+                    status: 200,
+                    bytes: given.len() as u64,
+                    close: true
+                }
+            );
+        }
+
+        #[test]
+        fn malformed() {
+            assert!(read_response(&mut Cursor::new(&b"hello\r\n\r\n"[..])).is_err());
+        }
+    }
+
+    mod bench {
+        use super::*;
+        use std::net::TcpListener;
+
+        // answers `ok` to every request, on every connection.
+        fn serve() -> String {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}/", listener.local_addr().unwrap());
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    thread::spawn(move || {
+                        let mut reader = BufReader::new(stream);
+                        let mut line = String::new();
+                        while reader.read_line(&mut line).is_ok_and(|read| read > 0) {
+                            if line == "\r\n" {
+                                let reply = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok";
+                                if reader.get_mut().write_all(reply).is_err() {
+                                    return;
+                                }
+                            }
+                            line.clear();
+                        }
+                    });
+                }
+            });
+
+            url
+        }
+
+        #[test]
+        fn keep_alive() {
+            let url = serve();
+
+            let request = Request::new(&[url], &[], true, None);
+            let actual = bench(
+                &request,
+                2,
+                Duration::from_millis(200),
+                &AtomicBool::new(false),
+            )
+            .unwrap();
+
+            assert!(!actual.latencies.is_empty());
+            assert_eq!(actual.errors, 0);
+            // one connection per worker, kept alive.
+            assert_eq!(actual.opened, 2);
+        }
+
+        #[test]
+        fn stop() {
+            let url = serve();
+            let stop = AtomicBool::new(false);
+
+            let request = Request::new(&[url], &[], true, None);
+            let start = Instant::now();
+            thread::scope(|scope| {
+                scope.spawn(|| {
+                    thread::sleep(Duration::from_millis(100));
+                    stop.store(true, Ordering::Relaxed);
+                });
+                bench(&request, 2, Duration::from_secs(10), &stop).unwrap();
+            });
+
+            assert!(start.elapsed() < Duration::from_secs(5));
+        }
+
+        #[test]
+        fn rate() {
+            let url = serve();
+
+            let request = Request::new(&[url], &[], true, None).with_rate(Some(50.0));
+            let actual = bench(
+                &request,
+                2,
+                Duration::from_millis(400),
+                &AtomicBool::new(false),
+            )
+            .unwrap();
+
+            // 20 at 50 req/s, the first of every connection sent right away.
+            assert!(
+                (16..=24).contains(&actual.latencies.len()),
+                "{}",
+                actual.latencies.len()
+            );
+        }
+
+        #[test]
+        fn send() {
+            let url = serve();
+
+            let request = Request::new(&[url], &[], true, None);
+            let actual = super::send(&request, 4, 25, Duration::from_secs(5)).unwrap();
+
+            assert_eq!(actual.sent(), 25);
+            assert_eq!(actual.errors, 0);
+        }
+
+        #[test]
+        fn reconnect() {
+            // answers a single request per connection, then closes it without
+            // saying so.
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}/", listener.local_addr().unwrap());
+            thread::spawn(move || {
+                for mut stream in listener.incoming().flatten() {
+                    let mut head = [0; 1024];
+                    let _ = stream.read(&mut head);
+                    let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok");
+                }
+            });
+
+            let request = Request::new(&[url], &[], true, None);
+            let actual = super::send(&request, 1, 5, Duration::from_secs(5)).unwrap();
+
+            assert_eq!(actual.latencies.len(), 5);
+            assert_eq!(actual.errors, 0);
+        }
+
+        #[test]
+        fn urls() {
+            // answers every request with its path.
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let (paths, received) = std::sync::mpsc::channel();
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let mut reader = BufReader::new(stream);
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).is_ok_and(|read| read > 0) {
+                        if let Some(path) = line.strip_prefix("GET ") {
+                            let _ = paths.send(path.split(' ').next().unwrap().to_string());
+                        } else if line == "\r\n" {
+                            let reply = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok";
+                            if reader.get_mut().write_all(reply).is_err() {
+                                break;
+                            }
+                        }
+                        line.clear();
+                    }
+                }
+            });
+
+            let urls = [format!("{}/a", url), format!("{}/b", url)];
+            let request = Request::new(&urls, &[], true, None);
+            super::send(&request, 1, 4, Duration::from_secs(5)).unwrap();
+
+            assert_eq!(
+                received.try_iter().collect::<Vec<_>>(),
+                ["/a", "/b", "/a", "/b"]
+            );
+        }
+
+        #[test]
+        fn timeout() {
+            // accepts connections but never answers.
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}/", listener.local_addr().unwrap());
+            thread::spawn(move || {
+                let _streams = listener.incoming().flatten().collect::<Vec<_>>();
+            });
+
+            let request =
+                Request::new(&[url], &[], true, None).with_timeout(Some(Duration::from_millis(50)));
+            let actual = bench(
+                &request,
+                1,
+                Duration::from_millis(300),
+                &AtomicBool::new(false),
+            )
+            .unwrap();
+
+            assert!(actual.latencies.is_empty());
+            assert!(actual.timeouts > 0);
+            assert_eq!(actual.timeouts, actual.errors);
+        }
+    }
+
+    mod load_stats {
+        use super::*;
+
+        #[test]
+        fn render() {
+            let given = LoadStats::new(
+                Duration::from_secs(2),
+                vec![Worker {
+                    latencies: (1..=4).map(Duration::from_millis).collect(),
+                    ttfbs: (1..=4)
+                        .map(|ttfb| Duration::from_micros(ttfb * 100))
+                        .collect(),
+                    bytes: 2 * 1024 * 1024,
+                    errors: 1,
+                    timeouts: 1,
+                    opened: 2,
+                }],
+            );
+
+            let actual = parse(&given.render());
+
+            let expect = Metrics::from_captures(|name| {
+                Some(
+                    match name {
+                        "total" => "4",
+                        "rps" => "2.00",
+                        "errors" => "1",
+                        "timeouts" => "1",
+                        "opened" => "2",
+                        "transfer" => "2.00MB",
+                        "transfer_rate" => "1.00MB/Sec",
+                        "avg" => "2.50ms",
+                        "stdev" => "1.12ms",
+                        "min" => "1.00ms",
+                        "max" => "4.00ms",
+                        "p50" => "2.00ms",
+                        "p99" => "4.00ms",
+                        "p999" => "4.00ms",
+                        "p9999" => "4.00ms",
+                        "ttfb_avg" => "0.25ms",
+                        "ttfb_p50" => "0.20ms",
+                        "ttfb_p99" => "0.40ms",
+                        _ => return None,
+                    }
+                    .to_string(),
+                )
+            });
+
+            assert_eq!(actual, expect);
+        }
+    }
+}
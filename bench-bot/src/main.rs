@@ -1,50 +1,653 @@
-use self::markdown::Markdown;
+use self::affinity::CoreSet;
+use self::balancer::Balancer;
+use self::bench::{Bench, Bodies};
+use self::category::Config;
+use self::cgroup::Cgroup;
+use self::cpu::CoreUsage;
+use self::disk::DiskUsage;
+use self::git::Revision;
+use self::grpc::Protocol;
+use self::hardware::HardwareInfo;
+use self::json::Metadata;
+use self::language::Language;
+use self::latency::Prober;
+use self::memory::MemStat;
+use self::meta::Meta;
+use self::methodology::Methodology;
+use self::process::Spawned;
+use self::profile::Profile;
+use self::report::{Metrics, Report};
+use self::timing::Timings;
+use self::tool::{CustomParser, Tool};
+use self::websocket::WsStats;
+use bench_bot::markdown::Markdown;
 use clap::Parser;
 use log::LevelFilter;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
+    env,
     ffi::OsStr,
     fs,
-    io::Write,
+    io::{self, Read, Write},
+    iter,
+    net::ToSocketAddrs,
     path::{Component, Path, PathBuf},
-    process::Command,
+    process::{Child, Command, Stdio},
     sync::mpsc,
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
-use sysinfo::{CpuExt, PidExt, ProcessExt, System, SystemExt};
-use self::report::{Metrics, Report};
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
 
-mod markdown;
+mod affinity;
+mod archive;
+mod balancer;
+mod bench;
+mod calibrate;
+mod category;
+mod cgroup;
+mod cpu;
+mod disk;
+mod git;
+mod grpc;
+mod hardware;
+mod html;
+mod interrupt;
+mod json;
+mod language;
+mod latency;
+mod loadgen;
+mod matrix;
+mod memory;
+mod meta;
+mod methodology;
+mod perf;
+mod port;
+mod privilege;
+mod probe;
+mod process;
+mod profile;
+mod ramp;
+mod regression;
 mod report;
+mod sqlite;
+mod stats;
+mod stream;
+mod template;
+mod timing;
+mod tool;
+mod websocket;
+
+/// Time given to a freshly spawned server before load is sent.
+const STARTUP_WAIT: Duration = Duration::from_secs(1);
+/// Interval between two memory samples of the running server.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+/// Relative difference between the wrapper's and the server's memory above
+/// which a warning is logged.
+const MEMORY_GAP: f64 = 0.25;
+/// Attempts at finding a spawned server in the process table before memory
+/// sampling starts.
+const PID_RETRIES: usize = 50;
+/// Wait between two of the `PID_RETRIES` lookups.
+const PID_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+/// How long a server gets to return memory to the OS after
+/// `--gc-endpoint` answered.
+const GC_SETTLE: Duration = Duration::from_millis(500);
+/// Duration of `--calibrate` against the null server, in seconds.
+const CALIBRATION_SECS: usize = 3;
+
+/// Build settings applied to every Rust member with `--fair-build`,
+/// overriding whatever `[profile.release]` the member declares.
+const FAIR_RUST_ENV: [(&str, &str); 4] = [
+    ("CARGO_PROFILE_RELEASE_OPT_LEVEL", "3"),
+    ("CARGO_PROFILE_RELEASE_LTO", "fat"),
+    ("CARGO_PROFILE_RELEASE_CODEGEN_UNITS", "1"),
+    ("RUSTFLAGS", "-C target-cpu=native"),
+];
+/// Build settings applied to every Go member with `--fair-build`.
+const FAIR_GO_ENV: [(&str, &str); 2] = [("CGO_ENABLED", "0"), ("GOFLAGS", "-trimpath")];
 
-#[derive(Debug, Parser)]
+/// Errors of a build that failed on contention rather than on the code,
+/// cargo giving up on a lock another cargo holds. Its "Blocking waiting for
+/// file lock" is only a notice, the build going on after it.
+const TRANSIENT_BUILD_ERRORS: [&str; 3] = [
+    "failed to acquire package cache lock",
+    "failed to acquire cache lock",
+    "failed to lock file: ",
+];
+/// Output of a build that failed on the code, never retried.
+const COMPILE_ERRORS: [&str; 2] = ["error[E", "could not compile"];
+/// Wait before the build retried by `--retry-build-once-on-lock`.
+const BUILD_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Every option can also be set through the `BENCH_*` environment variable
+/// shown in `--help`. A flag given on the command line takes precedence over
+/// the environment, which takes precedence over the default.
+#[derive(Debug, Clone, Parser)]
 #[clap(version)]
 struct Args {
     /// Path to workspace directory.
-    #[clap(short)]
+    #[clap(short, env = "BENCH_WORKSPACE_DIR")]
     workspace_dir: PathBuf,
 
     /// Path to output file.
-    #[clap(short)]
+    #[clap(short, env = "BENCH_OUTPUT_DIR")]
     output_dir: PathBuf,
 
+    /// Write this run to its own `{output_dir}/{timestamp}/` and point
+    /// `{output_dir}/latest` at it, a symlink or a copy on Windows.
+    #[clap(long, env = "BENCH_OUTPUT_DIR_PER_RUN")]
+    output_dir_per_run: bool,
+
     /// Connection count of each benchmark.
-    #[clap(short, default_value = "500")]
+    #[clap(short, default_value = "500", env = "BENCH_CONNECTIONS")]
     connections: usize,
 
     /// Duration of each benchmark in seconds.
-    #[clap(short, default_value = "30")]
+    #[clap(short, default_value = "30", env = "BENCH_DURATION")]
     duration: usize,
 
-    /// Url for each benchmark.
-    #[clap(short, default_value = "http://127.0.0.1:3000")]
-    url: String,
+    /// Url for each benchmark. If its port is busy, the next free port is
+    /// used and passed to the server through `BENCH_PORT`. Repeat it to
+    /// spread load across several endpoints at once, which needs a custom
+    /// tool with the `{urls}` placeholder.
+    #[clap(short, default_value = "http://127.0.0.1:3000", env = "BENCH_URL")]
+    url: Vec<String>,
+
+    /// Threads of the load generator, one less than the CPU count by default.
+    #[clap(long, env = "BENCH_BENCH_THREADS")]
+    bench_threads: Option<usize>,
 
     /// Cooling down for each benchmark.
-    #[clap(long, default_value = "5")]
+    #[clap(long, default_value = "5", env = "BENCH_CD")]
     cd: u64,
+
+    /// Cool down until the CPU is idle instead, for at most `--idle-max`
+    /// seconds after which `--cd` applies.
+    #[clap(long, env = "BENCH_CD_UNTIL_IDLE")]
+    cd_until_idle: bool,
+
+    /// Start the first member right after the builds, without cooling
+    /// down first. Members still cool down between each other.
+    #[clap(long, env = "BENCH_SKIP_COOLDOWN_FIRST")]
+    skip_cooldown_first: bool,
+
+    /// Aggregate CPU usage in percent below which the machine is idle.
+    #[clap(long, default_value = "5", env = "BENCH_IDLE_THRESHOLD")]
+    idle_threshold: f32,
+
+    /// Seconds the CPU has to stay idle in a row.
+    #[clap(long, default_value = "3", env = "BENCH_IDLE_WINDOW")]
+    idle_window: u64,
+
+    /// Longest wait for an idle CPU in seconds.
+    #[clap(long, default_value = "60", env = "BENCH_IDLE_MAX")]
+    idle_max: u64,
+
+    /// Before benchmarking, warn about the other processes using at least
+    /// `--busy-threshold` percent of a core, which would skew the results.
+    #[clap(long, env = "BENCH_WARN_BACKGROUND_PROCESSES")]
+    warn_background_processes: bool,
+
+    /// Abort instead of warning when another process is that busy, implies
+    /// `--warn-background-processes`.
+    #[clap(long, env = "BENCH_FAIL_ON_BUSY")]
+    fail_on_busy: bool,
+
+    /// CPU usage in percent of a core from which another process counts as
+    /// background load.
+    #[clap(long, default_value = "10", env = "BENCH_BUSY_THRESHOLD")]
+    busy_threshold: f32,
+
+    /// Send `Accept-Encoding` with every request, e.g. `gzip`.
+    #[clap(long, env = "BENCH_ACCEPT_ENCODING")]
+    accept_encoding: Option<String>,
+
+    /// Read the member paths from stdin, one per line, instead of the
+    /// workspace's `members`, e.g. `find ... | bench-bot --members-from-stdin`.
+    /// Paths are relative to the workspace or point into it, the checks
+    /// and `--max-members` still apply.
+    #[clap(long, env = "BENCH_MEMBERS_FROM_STDIN")]
+    members_from_stdin: bool,
+
+    /// Only benchmark the first n members, for quick smoke runs.
+    #[clap(long, env = "BENCH_MAX_MEMBERS")]
+    max_members: Option<usize>,
+
+    /// Memory limit of each server in MB, enforced by a cgroup v2 (Linux
+    /// only, needs root and `--allow-root`). Servers killed by the OOM
+    /// killer are reported as such.
+    #[clap(long, env = "BENCH_MEM_LIMIT")]
+    mem_limit: Option<u64>,
+
+    /// Kill a server as soon as its resident memory, sampled alongside the
+    /// other statistics, goes above this many MB, end the load generator's
+    /// run and report the member as failed. The `cargo run`/`go run`
+    /// wrapper's own memory doesn't count. Unlike `--mem-limit` this needs
+    /// neither cgroups nor root.
+    #[clap(long, env = "BENCH_MEM_ABORT")]
+    mem_abort: Option<u64>,
+
+    /// Build (and run) every member with the same optimization settings,
+    /// ignoring per-member release profiles.
+    #[clap(long, env = "BENCH_FAIR_BUILD")]
+    fair_build: bool,
+
+    /// Target directory shared by every Rust member, as `CARGO_TARGET_DIR`,
+    /// so that dependencies they have in common are compiled once. Go
+    /// members are unaffected.
+    #[clap(long, env = "BENCH_SHARED_TARGET")]
+    shared_target: Option<PathBuf>,
+
+    /// Cargo profiles every Rust member is built and benchmarked with, each
+    /// reported as `framework [profile]`, e.g. `release,release-lto`. Only
+    /// `--release` by default. Go members are built once.
+    #[clap(long, use_value_delimiter = true, env = "BENCH_PROFILES")]
+    profiles: Vec<String>,
+
+    /// Disable keep-alive (`Connection: close`), so every request opens a
+    /// new connection and the connection accept rate is reported.
+    #[clap(long, env = "BENCH_NO_KEEPALIVE")]
+    no_keepalive: bool,
+
+    /// Load generator used for the benchmarks.
+    #[clap(long, value_enum, default_value = "rewrk", env = "BENCH_TOOL")]
+    tool: Tool,
+
+    /// Command of the custom tool, with `{url}`, `{urls}` (every `-u`),
+    /// `{connections}`, `{duration}`, `{threads}`, `{pipeline}`,
+    /// `{body_file}`, `{body_files}` (every `--body-dir` file),
+    /// `{timeout_ms}`, `{client_cert}` and `{client_key}` placeholders.
+    /// Split into arguments as a shell would, quotes included.
+    #[clap(long, required_if_eq("tool", "custom"), env = "BENCH_TOOL_COMMAND")]
+    tool_command: Option<String>,
+
+    /// Regex parsing the custom tool's stdout. The named groups `rps`
+    /// (required), `total`, `avg`, `stdev`, `min`, `max`, `p99`, `p999`,
+    /// `p9999`, `transfer`, `transfer_rate`, `errors`, `timeouts`, `opened`
+    /// (connections) and `ttfb_avg`, `ttfb_p50`, `ttfb_p99` (time to first
+    /// byte) are mapped onto the report.
+    #[clap(long, required_if_eq("tool", "custom"), env = "BENCH_PARSE_REGEX")]
+    parse_regex: Option<String>,
+
+    /// Instead of a single run at `-c` connections, ramp up from
+    /// `--ramp-start` connections, doubling until requests/sec plateau or
+    /// `-c` is reached, and report the saturation point.
+    #[clap(long, env = "BENCH_RAMP")]
+    ramp: bool,
+
+    /// First connection count of a ramp.
+    #[clap(long, default_value = "8", env = "BENCH_RAMP_START")]
+    ramp_start: usize,
+
+    /// Duration of each step of a ramp in seconds.
+    #[clap(long, default_value = "5", env = "BENCH_RAMP_STEP")]
+    ramp_step: usize,
+
+    /// Minimum relative requests/sec gain for a ramp to keep going.
+    #[clap(long, default_value = "0.05", env = "BENCH_RAMP_TOLERANCE")]
+    ramp_tolerance: f64,
+
+    /// After the regular run, also benchmark every combination of
+    /// `--matrix-connections` and `--matrix-durations`, reported as a grid
+    /// per framework.
+    #[clap(long, env = "BENCH_MATRIX")]
+    matrix: bool,
+
+    /// Connection counts of `--matrix`, comma separated, `-c` by default.
+    #[clap(long, use_value_delimiter = true, env = "BENCH_MATRIX_CONNECTIONS")]
+    matrix_connections: Vec<usize>,
+
+    /// Durations in seconds of `--matrix`, comma separated, `-d` by default.
+    #[clap(long, use_value_delimiter = true, env = "BENCH_MATRIX_DURATIONS")]
+    matrix_durations: Vec<usize>,
+
+    /// Output files written for every benchmark type, comma separated:
+    /// `md` for `{bench_type}.md`, `json` for `{bench_type}.json`, `txt`
+    /// for an aligned plain text table in `{bench_type}.txt`, `html` for a
+    /// self-contained `{bench_type}.html` with a sortable table and charts,
+    /// and `csv` for one `results.csv` of every framework.
+    #[clap(
+        long,
+        value_enum,
+        use_value_delimiter = true,
+        default_value = "md",
+        env = "BENCH_FORMAT"
+    )]
+    format: Vec<Format>,
+
+    /// Path of a WebSocket echo endpoint, e.g. `/ws`. When set, every member
+    /// is also benchmarked with `-c` persistent WebSocket connections
+    /// echoing messages for `-d` seconds.
+    #[clap(long, env = "BENCH_WEBSOCKET")]
+    websocket: Option<String>,
+
+    /// Benchmark every member this many times and report the median run.
+    #[clap(long, default_value = "1", env = "BENCH_RUNS")]
+    runs: usize,
+
+    /// Benchmark every member one more time before its `--runs`, throwing
+    /// that full run away, e.g. for cold caches that warmup requests don't
+    /// warm. Ignored with `--ramp`.
+    #[clap(long, env = "BENCH_DISCARD_FIRST_RUN")]
+    discard_first_run: bool,
+
+    /// With `--runs` above 1, warn that a result is noisy when the
+    /// coefficient of variation of its requests/sec exceeds this.
+    #[clap(long, default_value = "0.1", env = "BENCH_WARN_ON_HIGH_VARIANCE")]
+    warn_on_high_variance: f64,
+
+    /// Warn that a category's comparison is unfair when the largest body
+    /// its frameworks answered the sample request with is more than this
+    /// much larger than the smallest, e.g. `0.5` for 50%.
+    #[clap(long, default_value = "0.5", env = "BENCH_RESPONSE_SIZE_TOLERANCE")]
+    response_size_tolerance: f64,
+
+    /// Instead of a fixed `--runs`, rerun every framework until two
+    /// successive runs are within `--tolerance` of each other. The better
+    /// of those two is reported, the median of all runs when it never
+    /// stabilizes.
+    #[clap(long, env = "BENCH_UNTIL_STABLE")]
+    until_stable: bool,
+
+    /// Relative requests/sec difference under which `--until-stable`
+    /// considers two runs to agree.
+    #[clap(long, default_value = "0.03", env = "BENCH_TOLERANCE")]
+    tolerance: f64,
+
+    /// Most runs of a framework with `--until-stable`.
+    #[clap(long, default_value = "10", env = "BENCH_MAX_RUNS")]
+    max_runs: usize,
+
+    /// HTTP pipelining depth, i.e. requests in flight per connection. rewrk
+    /// can't pipeline, use a custom tool with the `{pipeline}` placeholder.
+    #[clap(long, default_value = "1", env = "BENCH_PIPELINE")]
+    pipeline: usize,
+
+    /// Drop the page cache during the cooldown between members, so that
+    /// every member starts from a cold cache. Linux only, needs root and
+    /// `--allow-root`.
+    #[clap(long, env = "BENCH_ISOLATE")]
+    isolate: bool,
+
+    /// Run as root. Without it bench-bot refuses to, servers running as
+    /// root see other limits than they would in production. Needed by
+    /// `--isolate` and `--mem-limit`.
+    #[clap(long, env = "BENCH_ALLOW_ROOT")]
+    allow_root: bool,
+
+    /// Memory statistic headlined in reports, next to the absolute peak.
+    #[clap(long, value_enum, default_value = "peak", env = "BENCH_MEM_STAT")]
+    mem_stat: MemStat,
+
+    /// Discard memory samples taken within this many milliseconds of the
+    /// server being ready, leaving a startup allocation spike out of the
+    /// reported memory.
+    #[clap(long, env = "BENCH_MEM_WARMUP_MS")]
+    mem_warmup_ms: Option<u64>,
+
+    /// Path Go members collect garbage on, e.g. `/debug/gc`. When set, it
+    /// is requested after the benchmark and the memory of the server is
+    /// sampled again, reported as post-GC memory next to the peak, which
+    /// includes garbage not yet collected.
+    #[clap(long, env = "BENCH_GC_ENDPOINT")]
+    gc_endpoint: Option<String>,
+
+    /// Also report the memory sampled at these seconds after the benchmark
+    /// started, comma separated, e.g. `5,15,25`. Unlike the peak, the same
+    /// points in time compare across runs.
+    #[clap(long, use_value_delimiter = true, env = "BENCH_MEM_AT")]
+    mem_at: Vec<u64>,
+
+    /// With `--mem-warmup-ms`, also report the peak memory including the
+    /// discarded startup samples.
+    #[clap(long, env = "BENCH_KEEP_STARTUP_PEAK")]
+    keep_startup_peak: bool,
+
+    /// Show build output live instead of capturing it and only logging it
+    /// when a build fails.
+    #[clap(long, env = "BENCH_STREAM_BUILD")]
+    stream_build: bool,
+
+    /// Keep only the last bytes of the stderr of a failed build or load
+    /// generator, where the error usually is, and of the stdout of a load
+    /// generator whose stats don't parse, when logging them. 0 keeps them
+    /// whole.
+    #[clap(long, default_value = "8192", env = "BENCH_STDERR_LIMIT")]
+    stderr_limit: usize,
+
+    /// Build a member once more after a short delay when its build failed
+    /// on a lock held by another cargo, instead of excluding it. Compile
+    /// errors still fail right away. Not with `--stream-build`, whose
+    /// output isn't captured.
+    #[clap(long, env = "BENCH_RETRY_BUILD_ONCE_ON_LOCK")]
+    retry_build_once_on_lock: bool,
+
+    /// Profile every server during its benchmark, writing
+    /// `<framework>.svg` flamegraphs to the output directory. Linux only,
+    /// needs `perf` and inferno.
+    #[clap(long, value_enum, env = "BENCH_PROFILE")]
+    profile: Option<Profile>,
+
+    /// Also build every Rust member for this target triple, e.g.
+    /// `x86_64-unknown-linux-musl`, and report the size of its binary. The
+    /// native build is still the one benchmarked.
+    #[clap(long, env = "BENCH_BUILD_TARGET")]
+    build_target: Option<String>,
+
+    /// Leave the per-framework sections out of the markdown, keeping the
+    /// hardware, methodology and comparison tables.
+    #[clap(long, env = "BENCH_SUMMARY_ONLY")]
+    summary_only: bool,
+
+    /// Before this run, benchmark the workspace at this git tag, checked out
+    /// into a temporary worktree with otherwise the same arguments, and
+    /// compare both runs in `regression.md`.
+    #[clap(long, env = "BENCH_COMPARE_TAG")]
+    compare_tag: Option<String>,
+
+    /// Free-form label of this run, e.g. `before-async-refactor`, in the
+    /// report header, the JSON metadata and the `--sqlite` runs table to
+    /// find the run again later.
+    #[clap(long, env = "BENCH_LABEL")]
+    label: Option<String>,
+
+    /// Write every report to stdout as a line of JSON the moment it is
+    /// measured, for live dashboards. The servers' stdout goes to stderr
+    /// instead.
+    #[clap(long, env = "BENCH_STREAM")]
+    stream: bool,
+
+    /// Print a single line of JSON to stdout once done: the winner of every
+    /// category by Req/Sec, how many frameworks there were and how many
+    /// failed. Logs stay on stderr, the servers' stdout goes there too.
+    #[clap(long, env = "BENCH_SUMMARY_JSON")]
+    summary_json: bool,
+
+    /// Send the `--stream` lines to this Unix socket instead of stdout.
+    #[clap(long, env = "BENCH_STREAM_SOCKET")]
+    stream_socket: Option<PathBuf>,
+
+    /// Markdown file laying out the `{bench_type}.md` reports, with the
+    /// placeholders `{{generated_by}}`, `{{hardware}}`, `{{command}}`,
+    /// `{{methodology}}`, `{{comparison_table}}`, `{{websocket}}` and
+    /// `{{per_framework}}`. The built-in layout is `src/template.md`.
+    #[clap(long, env = "BENCH_TEMPLATE")]
+    template: Option<PathBuf>,
+
+    /// Write `index.md`, aggregating every report per language: median and
+    /// mean Req/Sec and memory, and the best framework of each language in
+    /// every benchmark category.
+    #[clap(long, env = "BENCH_COMPARE_LANGUAGES")]
+    compare_languages: bool,
+
+    /// Indent the JSON output, `--json-pretty false` writes it on a single
+    /// line for pipelines storing it. `--stream` is always a line per report.
+    #[clap(long, default_value_t = true, action = clap::ArgAction::Set, env = "BENCH_JSON_PRETTY")]
+    json_pretty: bool,
+
+    /// Leave numbers of the markdown and text tables without thousands
+    /// separators, for tools parsing them. JSON and CSV are always raw.
+    #[clap(long, env = "BENCH_RAW_NUMBERS")]
+    raw_numbers: bool,
+
+    /// Send `POST` requests with the files of this directory as bodies.
+    /// Tools given `{body_files}` can rotate through them, others only get
+    /// the first one.
+    #[clap(long, env = "BENCH_BODY_DIR")]
+    body_dir: Option<PathBuf>,
+
+    /// Leave the server running after its benchmark until enter is pressed,
+    /// to inspect it by hand. Needs a single member.
+    #[clap(long, env = "BENCH_KEEP_ALIVE_AFTER")]
+    keep_alive_after: bool,
+
+    /// Report the bytes the servers read from and wrote to disk during the
+    /// benchmark, from `/proc/<pid>/io` on Linux.
+    #[clap(long, env = "BENCH_DISK_IO")]
+    disk_io: bool,
+
+    /// Count instructions, cycles and cache misses of the servers during
+    /// the benchmark with `perf stat`, for their IPC and cache-miss rate.
+    /// Linux only, needs `perf`.
+    #[clap(long, env = "BENCH_PERF_STAT")]
+    perf_stat: bool,
+
+    /// Status codes of a successful response, comma separated, e.g.
+    /// `201,204`. Any status below 400 by default. Checked on the sample
+    /// request and counted as errors otherwise by the builtin tool.
+    #[clap(long, use_value_delimiter = true, env = "BENCH_EXPECT_STATUS")]
+    expect_status: Vec<u16>,
+
+    /// Before the frameworks, benchmark a null server on loopback to
+    /// estimate the load generator's ceiling, and flag frameworks measured
+    /// close to it as likely generator-bound.
+    #[clap(long, env = "BENCH_CALIBRATE")]
+    calibrate: bool,
+
+    /// TOML file overriding `-c`, `-d`, `--mem-warmup-ms` and the path of
+    /// every `-u` per benchmark category, under `[category.<name>]`, and
+    /// tagging members under `[tags]`.
+    #[clap(long, env = "BENCH_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Only check that every workspace member is a buildable project, and
+    /// exit with an error if one isn't.
+    #[clap(long, env = "BENCH_VALIDATE_WORKSPACE")]
+    validate_workspace: bool,
+
+    /// Exit with an error on the first failed build instead of skipping the
+    /// member, e.g. to gate CI.
+    #[clap(long, env = "BENCH_STRICT")]
+    strict: bool,
+
+    /// SQLite database every report is also inserted into, created or
+    /// migrated as needed, for trends across runs. Needs `sqlite3`.
+    #[clap(long, env = "BENCH_SQLITE")]
+    sqlite: Option<PathBuf>,
+
+    /// Abort the run once this many members in a row failed to build or
+    /// benchmark, the environment being likely broken. What was measured
+    /// is still written.
+    #[clap(long, env = "BENCH_BAIL_AFTER_FAILURES")]
+    bail_after_failures: Option<usize>,
+
+    /// While the load generator runs, send a probe request to the first
+    /// `-u` every this many milliseconds on a connection of its own, and
+    /// report the probes' latency percentiles: the latency a new client
+    /// sees under the load.
+    #[clap(long, env = "BENCH_PROBE_INTERVAL_MS")]
+    probe_interval_ms: Option<u64>,
+
+    /// Hold the builtin tool at this many requests/sec over all the
+    /// connections instead of as many as possible, e.g. a steady
+    /// background load for `--probe-interval-ms`.
+    #[clap(long, env = "BENCH_RATE")]
+    rate: Option<f64>,
+
+    /// Send this many requests to every framework before the measured runs,
+    /// e.g. to warm caches or a JIT up. Over HTTP/2 or gRPC the tool runs
+    /// for as long as they take at its rate over a first second.
+    #[clap(long, env = "BENCH_WARMUP_REQUESTS")]
+    warmup_requests: Option<u64>,
+
+    /// Extra argument appended to the load generator's command, after the
+    /// ones bench-bot generates, for tool features it has no option of.
+    /// Repeat for several, e.g. `--tool-arg=--pct`.
+    #[clap(long, allow_hyphen_values = true, env = "BENCH_TOOL_ARG")]
+    tool_arg: Vec<String>,
+
+    /// Give up on a request after this many milliseconds, counting it as a
+    /// timed out error. Passed to ghz and to `{timeout_ms}` of a custom
+    /// tool, rewrk has no timeout. 5s for the builtin tool by default.
+    #[clap(long, env = "BENCH_REQUEST_TIMEOUT")]
+    request_timeout: Option<u64>,
+
+    /// Client certificate in PEM for endpoints requiring mutual TLS, with
+    /// `--client-key`. Passed to ghz and to `{client_cert}` of a custom
+    /// tool, rewrk and the builtin tool can't present one. The sample
+    /// request is skipped, it only speaks plain HTTP.
+    #[clap(long, requires = "client-key", env = "BENCH_CLIENT_CERT")]
+    client_cert: Option<PathBuf>,
+
+    /// Private key of `--client-cert` in PEM, `{client_key}` of a custom
+    /// tool.
+    #[clap(long, requires = "client-cert", env = "BENCH_CLIENT_KEY")]
+    client_key: Option<PathBuf>,
+
+    /// Benchmark cleartext HTTP/2 with prior knowledge (h2c), multiplexing
+    /// requests over the `-c` connections.
+    #[clap(long, env = "BENCH_HTTP2")]
+    http2: bool,
+
+    /// Protocol the servers are benchmarked over, `grpc` makes unary calls
+    /// of `--grpc-call` with `ghz`.
+    #[clap(long, value_enum, default_value = "http", env = "BENCH_PROTOCOL")]
+    protocol: Protocol,
+
+    /// Fully qualified method of `--protocol grpc`, e.g.
+    /// `helloworld.Greeter/SayHello`.
+    #[clap(long, required_if_eq("protocol", "grpc"), env = "BENCH_GRPC_CALL")]
+    grpc_call: Option<String>,
+
+    /// Request message of `--grpc-call` as JSON.
+    #[clap(long, default_value = "{}", env = "BENCH_GRPC_DATA")]
+    grpc_data: String,
+
+    /// Service definition of `--grpc-call`, server reflection when not set.
+    #[clap(long, env = "BENCH_GRPC_PROTO")]
+    grpc_proto: Option<PathBuf>,
+
+    /// Cores the servers are pinned to with `taskset`, e.g. `0-3`.
+    #[clap(long, env = "BENCH_SERVER_CORES")]
+    server_cores: Option<CoreSet>,
+
+    /// Cores the load generator is pinned to with `taskset`, e.g. `4-7`.
+    #[clap(long, env = "BENCH_BENCH_CORES")]
+    bench_cores: Option<CoreSet>,
+
+    /// Run this many instances of every framework on consecutive free ports
+    /// behind a built-in round-robin TCP balancer listening on `-u`, memory
+    /// being their total.
+    #[clap(long, default_value = "1", env = "BENCH_INSTANCES")]
+    instances: usize,
+
+    /// Treat a framework as failed, leaving it out of the comparison, when
+    /// more than this fraction of its requests errored, e.g. `0.01`.
+    #[clap(long, env = "BENCH_MAX_ERROR_RATE")]
+    max_error_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+enum Format {
+    Md,
+    Json,
+    Txt,
+    /// A single `results.csv` over every category.
+    Csv,
+    /// A self-contained page with a sortable table and charts.
+    Html,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -58,7 +661,7 @@ struct Workspace {
 }
 
 fn main() {
-    let args = Args::parse();
+    let mut args = Args::parse();
 
     env_logger::builder()
         .format(|buf, record| {
@@ -70,205 +673,2641 @@ fn main() {
         .init();
 
     log::info!("Bench Bot started.");
+    interrupt::install();
 
-    let ws_toml_path = args.workspace_dir.join("Cargo.toml");
-    let ws_toml = fs::read(&ws_toml_path).unwrap();
+    if !args.allow_root {
+        if privilege::is_root() {
+            log::error!(
+                "Refusing to run as root, which changes limits such as ulimits for the servers. \
+                 Run as a dedicated unprivileged user, or pass --allow-root."
+            );
+            std::process::exit(1);
+        }
+        let needs_root = [
+            ("--isolate", args.isolate),
+            ("--mem-limit", args.mem_limit.is_some()),
+        ];
+        if let Some((flag, _)) = needs_root.iter().find(|(_, set)| *set) {
+            log::error!("{} needs root, run as root with --allow-root.", flag);
+            std::process::exit(1);
+        }
+    }
 
-    let cargo: Cargo = toml::from_slice(&ws_toml).unwrap();
-    let members = expand_members(cargo.workspace.members, &args.workspace_dir);
+    if args.mem_limit.is_some() {
+        if let Err(e) = Cgroup::supported() {
+            log::error!("--mem-limit is not available: {}", e);
+            std::process::exit(1);
+        }
+    }
 
-    let mut exclude = Vec::new();
+    if let Some(profile) = args.profile {
+        if let Err(e) = profile.check() {
+            log::error!("{}", e);
+            std::process::exit(1);
+        }
+    }
 
-    for member in &members {
-        log::info!("Building {:?}", member);
+    if args.members_from_stdin && args.compare_tag.is_some() {
+        log::error!(
+            "--members-from-stdin can't be combined with --compare-tag, whose run of the tag \
+             would find stdin already read."
+        );
+        std::process::exit(1);
+    }
 
-        // go build -o my_go_app
-        let member_lossy = member.to_string_lossy(); // Bind the result to a variable
-        let member_split = member_lossy.split("/");  // Now split can borrow safely
-        let member_str = member_split.last().unwrap(); // Use the result safely
-        let output = if member_str.starts_with("go_") {
-            Command::new("go")
-                .args(&["mod", "tidy"])
-                .current_dir(args.workspace_dir.join(&member))
-                .output()
-                .expect("Failed to execute Go build");
-            Command::new("go")
-                .args(&["build"])
-                .current_dir(args.workspace_dir.join(&member))
-                .output()
-                .expect("Failed to execute Go build")
-        } else {
-            // Default case: use "cargo build --release"
-            Command::new("cargo")
-                .args(&["build", "--release"])
-                .current_dir(args.workspace_dir.join(&member))
-                .output()
-                .expect("Failed to execute Cargo build")
-        };
+    if args.perf_stat {
+        if let Err(e) = perf::check() {
+            log::error!("{}", e);
+            std::process::exit(1);
+        }
+    }
 
-        if !output.status.success() {
+    let custom_parser = match (args.tool, &args.parse_regex) {
+        (Tool::Custom, Some(pattern)) => match CustomParser::new(pattern) {
+            Ok(parser) => Some(parser),
+            Err(e) => {
+                log::error!("{}", e);
+                std::process::exit(1);
+            }
+        },
+        _ => None,
+    };
+    if args.tool == Tool::Custom
+        && args
+            .tool_command
+            .as_deref()
+            .is_none_or(|c| c.trim().is_empty())
+    {
+        log::error!("--tool-command must not be empty.");
+        std::process::exit(1);
+    }
+    if let Some(Err(e)) = args.tool_command.as_deref().map(tool::split_words) {
+        log::error!("{}", e);
+        std::process::exit(1);
+    }
+    if args.pipeline != 1 {
+        let uses_pipeline = args.tool == Tool::Custom
+            && args
+                .tool_command
+                .as_deref()
+                .is_some_and(|c| c.contains("{pipeline}"));
+        if !uses_pipeline {
             log::error!(
-                "Building {:?} failed: \n{}",
-                member,
-                String::from_utf8_lossy(&output.stderr)
+                "--pipeline needs a tool that pipelines requests: rewrk does not, use \
+                 --tool custom with a --tool-command containing {{pipeline}}."
             );
-            exclude.push(member.clone());
+            std::process::exit(1);
         }
     }
+    if args.warmup_requests.is_some()
+        && (args.http2 || args.protocol == Protocol::Grpc || args.client_cert.is_some())
+    {
+        log::warn!(
+            "--warmup-requests can only be counted exactly over plaintext HTTP/1.1, it is \
+             approximated from the rate of the load generator."
+        );
+    }
+    if args.retry_build_once_on_lock && args.stream_build {
+        log::warn!(
+            "--retry-build-once-on-lock can't tell why a build failed with --stream-build, builds \
+             aren't retried."
+        );
+    }
+    if !args.tool_arg.is_empty() && args.tool == Tool::Builtin {
+        log::error!("--tool-arg needs an external tool, the builtin one takes no arguments.");
+        std::process::exit(1);
+    }
+    if args.request_timeout.is_some() {
+        let uses_timeout = match args.tool {
+            Tool::Custom => args
+                .tool_command
+                .as_deref()
+                .is_some_and(|c| c.contains("{timeout_ms}")),
+            Tool::Builtin => true,
+            Tool::Rewrk => args.protocol == Protocol::Grpc,
+        };
+        if !uses_timeout {
+            log::error!(
+                "--request-timeout needs a tool with a timeout: rewrk has none, use --tool builtin \
+                 or --tool custom with a --tool-command containing {{timeout_ms}}."
+            );
+            std::process::exit(1);
+        }
+        if args.request_timeout == Some(0) {
+            log::error!("--request-timeout must be above 0 ms.");
+            std::process::exit(1);
+        }
+    }
+    if let (Some(cert), Some(key)) = (&args.client_cert, &args.client_key) {
+        let presents_cert = match args.tool {
+            Tool::Custom => args
+                .tool_command
+                .as_deref()
+                .is_some_and(|c| c.contains("{client_cert}")),
+            Tool::Builtin => false,
+            Tool::Rewrk => args.protocol == Protocol::Grpc,
+        };
+        if !presents_cert {
+            log::error!(
+                "--client-cert needs a tool speaking mutual TLS: use --protocol grpc, or --tool \
+                 custom with a --tool-command containing {{client_cert}} and {{client_key}}."
+            );
+            std::process::exit(1);
+        }
+        if let Some(missing) = [cert, key].into_iter().find(|path| !path.is_file()) {
+            log::error!("{:?} of --client-cert/--client-key is not a file.", missing);
+            std::process::exit(1);
+        }
+        if args.probe_interval_ms.is_some() {
+            log::error!(
+                "--probe-interval-ms sends plain HTTP probes, which an mTLS endpoint refuses."
+            );
+            std::process::exit(1);
+        }
+    }
+    if args.url.len() > 1 {
+        let uses_urls = args.tool == Tool::Custom
+            && args
+                .tool_command
+                .as_deref()
+                .is_some_and(|c| c.contains("{urls}"));
+        if !uses_urls {
+            log::error!(
+                "Several -u need a tool that spreads load across URLs: rewrk does not, use \
+                 --tool custom with a --tool-command containing {{urls}}."
+            );
+            std::process::exit(1);
+        }
+    }
+    let bodies = args.body_dir.as_ref().map(|dir| {
+        Bodies::load(dir).unwrap_or_else(|e| {
+            log::error!("Could not read --body-dir {:?}: {}", dir, e);
+            std::process::exit(1);
+        })
+    });
+    let rotates_bodies = args.tool == Tool::Custom
+        && args
+            .tool_command
+            .as_deref()
+            .is_some_and(|c| c.contains("{body_files}"));
+    if let Some(bodies) = bodies.as_ref().filter(|bodies| bodies.files().len() > 1) {
+        if !rotates_bodies {
+            log::warn!(
+                "The tool only supports a single body, using {:?} of {} files. Use --tool \
+                 custom with a --tool-command containing {{body_files}} to rotate them.",
+                bodies.files()[0],
+                bodies.files().len()
+            );
+        }
+    }
+    if args.server_cores.is_some() || args.bench_cores.is_some() {
+        let taskset = Command::new("taskset")
+            .arg("--version")
+            .stdout(Stdio::null())
+            .status();
+        if taskset.is_err() {
+            log::error!("--server-cores and --bench-cores need taskset, which was not found.");
+            std::process::exit(1);
+        }
+    }
+    if let (Some(server), Some(bench)) = (&args.server_cores, &args.bench_cores) {
+        let overlap = server.overlap(bench);
+        if !overlap.is_empty() {
+            log::warn!(
+                "--server-cores and --bench-cores share the cores {:?}, server and load \
+                 generator compete for them.",
+                overlap
+            );
+        }
+    }
+    if args.sqlite.is_some() {
+        if let Err(e) = sqlite::check() {
+            log::error!("{}.", e);
+            std::process::exit(1);
+        }
+    }
+    if args.bail_after_failures == Some(0) {
+        log::error!("--bail-after-failures must be at least 1.");
+        std::process::exit(1);
+    }
+    if args.instances == 0 {
+        log::error!("--instances must be at least 1.");
+        std::process::exit(1);
+    }
+    if args.http2 && args.no_keepalive {
+        log::error!(
+            "--http2 can't be combined with --no-keepalive, HTTP/2 has no Connection header."
+        );
+        std::process::exit(1);
+    }
+    if args.http2 && args.tool == Tool::Builtin {
+        log::error!("--http2 needs a tool that speaks h2c, the builtin tool only speaks HTTP/1.1.");
+        std::process::exit(1);
+    }
+    if args.protocol == Protocol::Grpc {
+        if args.http2 || args.tool != Tool::Rewrk {
+            log::error!("--protocol grpc runs ghz, it can't be combined with --http2 or --tool.");
+            std::process::exit(1);
+        }
+        let ghz = Command::new("ghz")
+            .arg("--version")
+            .stdout(Stdio::null())
+            .status();
+        if ghz.is_err() {
+            log::error!("--protocol grpc needs ghz, which was not found.");
+            std::process::exit(1);
+        }
+        if args.accept_encoding.is_some() || args.no_keepalive || args.body_dir.is_some() {
+            log::warn!(
+                "--accept-encoding, --no-keepalive and --body-dir are ignored by --protocol grpc."
+            );
+        }
+    }
+    if !args.expect_status.is_empty() && args.tool != Tool::Builtin {
+        log::warn!(
+            "--expect-status is only checked on the sample request, only the builtin tool \
+             counts unexpected statuses as errors."
+        );
+    }
+    if args.disk_io && cfg!(not(target_os = "linux")) {
+        log::warn!("--disk-io reads /proc/<pid>/io, which is only available on Linux.");
+    }
+    if let Some(dir) = &args.shared_target {
+        // cargo resolves a relative one from each member's directory.
+        match fs::create_dir_all(dir).and_then(|()| fs::canonicalize(dir)) {
+            Ok(dir) => args.shared_target = Some(dir),
+            Err(e) => {
+                log::error!("Could not create --shared-target {:?}: {}", dir, e);
+                std::process::exit(1);
+            }
+        }
+    }
+    if args.mem_abort == Some(0) {
+        log::error!("--mem-abort must be above 0 MB.");
+        std::process::exit(1);
+    }
+    if args.rate.is_some() && args.tool != Tool::Builtin {
+        log::error!("--rate paces the builtin tool, it needs --tool builtin.");
+        std::process::exit(1);
+    }
+    if args.rate.is_some_and(|rate| rate <= 0.0) || args.probe_interval_ms == Some(0) {
+        log::error!("--rate and --probe-interval-ms must be above 0.");
+        std::process::exit(1);
+    }
+    if args.probe_interval_ms.is_some() && (args.http2 || args.protocol == Protocol::Grpc) {
+        log::error!(
+            "--probe-interval-ms probes over HTTP/1.1, it can't be combined with --http2 or gRPC."
+        );
+        std::process::exit(1);
+    }
+    if args.calibrate && (args.http2 || args.protocol == Protocol::Grpc) {
+        log::error!("--calibrate needs HTTP/1.1, the null server speaks neither h2c nor gRPC.");
+        std::process::exit(1);
+    }
+    if args.bench_cores.is_some() && args.tool == Tool::Builtin {
+        log::warn!("--bench-cores is ignored by the builtin tool, which runs inside bench-bot.");
+    }
+    if args.discard_first_run && args.ramp {
+        log::warn!("--discard-first-run is ignored with --ramp, which has no repeated runs.");
+    }
+    if args.http2 && args.tool == Tool::Custom {
+        log::warn!("--http2 is not passed to --tool-command, make sure the tool speaks h2c.");
+    }
+    if args.tool == Tool::Custom && (args.accept_encoding.is_some() || args.no_keepalive) {
+        log::warn!("Headers are not added to --tool-command, pass them to the tool yourself.");
+    }
 
-    let sys = System::new_all();
-
-    let cpu_name = sys.global_cpu_info().brand();
-    let cpu_count = (sys.cpus().len() - 1).to_string();
-    let conn_count = args.connections.to_string();
-    let duration = format!("{}s", args.duration);
-    let cd = args.cd;
-    let members_len = members.len();
-
-    let rewrk_args = [
-        "-t",
-        &cpu_count,
-        "-c",
-        &conn_count,
-        "-d",
-        &duration,
-        "-h",
-        &args.url,
-    ];
-
-    let mut bench_command = "rewrk".to_owned();
-    for arg in rewrk_args {
-        bench_command.push(' ');
-        bench_command.push_str(arg);
+    let template = match &args.template {
+        Some(path) => fs::read_to_string(path).unwrap_or_else(|e| {
+            log::error!("Could not read --template {:?}: {}", path, e);
+            std::process::exit(1);
+        }),
+        None => template::DEFAULT.to_string(),
+    };
+    if let Err(e) = template::check(&template) {
+        log::error!("--template {}", e);
+        std::process::exit(1);
     }
 
-    let mut base_md = Markdown::new();
+    let mut sink = if args.stream || args.stream_socket.is_some() {
+        match stream::Sink::open(args.stream_socket.as_deref()) {
+            Ok(sink) => Some(sink),
+            Err(e) => {
+                log::error!(
+                    "Could not open {:?} for --stream: {}",
+                    args.stream_socket,
+                    e
+                );
+                std::process::exit(1);
+            }
+        }
+    } else {
+        None
+    };
 
-    base_md.add_item("Generated by bench-bot.");
-    base_md.add_item("# Hardware");
-    base_md.add_item("## Cpu");
-    base_md.add_item(cpu_name);
-    base_md.add_item("# Benchmark");
-    base_md.add_item("Command:");
-    base_md.add_item(format!("```\n{}\n```", bench_command));
+    let previous = args.compare_tag.as_ref().map(|tag| {
+        regression::run_tag(&args.workspace_dir, tag).unwrap_or_else(|e| {
+            log::error!("--compare-tag failed: {}", e);
+            std::process::exit(1);
+        })
+    });
 
-    let mut output_map = HashMap::new();
-    let mut reports = Vec::with_capacity(members.len());
+    let mut members = if args.members_from_stdin {
+        let mut input = String::new();
+        if let Err(e) = io::stdin().read_to_string(&mut input) {
+            log::error!("Could not read the members from stdin: {}", e);
+            std::process::exit(1);
+        }
+        stdin_members(&input, &args.workspace_dir).unwrap_or_else(|e| {
+            log::error!("--members-from-stdin: {}", e);
+            std::process::exit(1);
+        })
+    } else {
+        let ws_toml_path = args.workspace_dir.join("Cargo.toml");
+        let ws_toml = fs::read(&ws_toml_path).unwrap();
 
-    for (index, member) in members.iter().enumerate() {
-        if exclude.contains(member) {
-            log::warn!("Skipping {:?} because build was failed.", member);
-        } else {
-            let bench_type = member
-                .parent()
-                .unwrap()
-                .file_name()
-                .unwrap()
-                .to_str()
-                .unwrap();
+        let cargo: Cargo = toml::from_slice(&ws_toml).unwrap();
+        expand_members(cargo.workspace.members, &args.workspace_dir)
+    };
 
-            let framework_name = member.file_name().unwrap().to_str().unwrap();
+    // stale `members` entries and glob matches that aren't projects would
+    // otherwise only fail deep in their build.
+    let member_count = members.len();
+    members.retain(|member| {
+        match Language::of(member).missing_project(&args.workspace_dir.join(member)) {
+            Some(reason) => {
+                log::warn!("Excluding {:?}: {}.", member, reason);
+                false
+            }
+            None => true,
+        }
+    });
+    if args.validate_workspace {
+        log::info!(
+            "{} of {} members are buildable projects.",
+            members.len(),
+            member_count
+        );
+        std::process::exit(if members.len() == member_count { 0 } else { 1 });
+    }
 
-            let result_md = output_map.entry(bench_type).or_insert(Markdown::new());
+    if let Some(max_members) = args.max_members {
+        if members.len() > max_members {
+            log::warn!(
+                "Truncating members to the first {}, skipping {}. This is not a full run.",
+                max_members,
+                members.len() - max_members
+            );
+            members.truncate(max_members);
+        }
+    }
+    if args.keep_alive_after && members.len() != 1 {
+        log::error!(
+            "--keep-alive-after needs exactly one member, found {}. Narrow the workspace down, \
+             e.g. with --max-members 1.",
+            members.len()
+        );
+        std::process::exit(1);
+    }
 
-            log::info!("Benchmarking {:?}", member);
+    let config = match &args.config {
+        Some(path) => Config::load(path).unwrap_or_else(|e| {
+            log::error!("--config {}", e);
+            std::process::exit(1);
+        }),
+        None => Config::default(),
+    };
+    for category in config.category.keys() {
+        if !members.iter().any(|member| bench_type(member) == category) {
+            log::warn!(
+                "--config overrides category {:?}, which has no members.",
+                category
+            );
+        }
+    }
+    for member in config.tags.keys() {
+        if !members.contains(member) {
+            log::warn!("--config tags {:?}, which is not a member.", member);
+        }
+    }
+    let metas = members
+        .iter()
+        .filter_map(
+            |member| match Meta::load(&args.workspace_dir.join(member)) {
+                Ok(meta) => meta.map(|meta| (member.clone(), meta)),
+                Err(e) => {
+                    log::warn!("Ignoring the {} of {:?}: {}", meta::FILE, member, e);
+                    None
+                }
+            },
+        )
+        .collect::<HashMap<_, _>>();
 
-            let member_lossy = member.to_string_lossy(); // Bind the result to a variable
-            let member_split = member_lossy.split("/");  // Now split can borrow safely
-            let member_str = member_split.last().unwrap(); // Use the result safely
+    if !args.profiles.is_empty() && members.iter().any(|member| is_go(member)) {
+        log::warn!("--profiles only applies to Rust members, Go members are built once.");
+    }
+    if !args.profiles.is_empty() && args.fair_build {
+        log::warn!(
+            "--fair-build overrides the release profile, which the --profiles inheriting it \
+             follow."
+        );
+    }
+    let variants = variants(&members, &args.profiles);
 
-            let mut server = if member_str.starts_with("go_") {
-                // If the member starts with "go_", use "go run"
-                Command::new("go")
-                    .args(&["run", "."])  // `.` indicates the current directory for Go run
-                    .current_dir(args.workspace_dir.join(&member))
-                    .spawn()
-                    .expect("Failed to execute Go run")
-            } else {
-                // Default case: use "cargo run --release -q"
-                Command::new("cargo")
-                    .args(&["run", "--release", "-q"])
-                    .current_dir(args.workspace_dir.join(&member))
-                    .spawn()
-                    .expect("Failed to execute Cargo run")
-            };
+    let budget_members = variants
+        .iter()
+        .map(|(member, _)| member.clone())
+        .collect::<Vec<_>>();
+    let budget = category_budget(&args, &config, &budget_members).as_secs();
+    log::info!(
+        "Worst-case time budget: {}h {:02}m {:02}s for {} members, builds not included.",
+        budget / 3600,
+        budget / 60 % 60,
+        budget % 60,
+        variants.len()
+    );
 
-            thread::sleep(Duration::from_secs(1));
+    let mut exclude = Vec::new();
+    let mut binary_sizes = HashMap::new();
+    let mut build_times = HashMap::new();
+    let mut package_names = HashMap::new();
 
-            let pid = PidExt::from_u32(server.id());
-            let (tx, rx) = mpsc::channel::<()>();
+    for member in &members {
+        match package_name(member, &args.workspace_dir.join(member)) {
+            Some(name) => {
+                package_names.insert(member.clone(), name);
+            }
+            None => log::warn!(
+                "Could not read the package name of {:?}, using its directory.",
+                member
+            ),
+        }
+    }
 
-            let mem_usage_thread = thread::spawn(move || {
-                let mut sys = System::new();
-                let mut max_memory = 0;
-                while rx.try_recv().is_err() {
-                    sys.refresh_process(pid);
-                    max_memory =
-                        max_memory.max(sys.process(pid).map(ProcessExt::memory).unwrap_or(0));
+    for variant in &variants {
+        let (member, profile) = variant;
+        let profile = profile.as_deref();
+        match profile {
+            Some(profile) => log::info!("Building {:?} with profile {}", member, profile),
+            None => log::info!("Building {:?}", member),
+        }
+        let build_start = Instant::now();
 
-                    thread::sleep(Duration::from_millis(100));
-                }
-                max_memory
-            });
+        let build_env = build_env(member, args.fair_build);
+        let target_env = target_env(member, args.shared_target.as_deref());
 
-            let output = Command::new("rewrk").args(rewrk_args).output().unwrap();
+        // go build -o my_go_app
+        let result = if is_go(member) {
+            let mut tidy = Command::new("go");
+            tidy.args(["mod", "tidy"])
+                .current_dir(args.workspace_dir.join(member));
+            let _ = build(&mut tidy, args.stream_build);
 
-            tx.send(()).unwrap();
-            let _ = server.kill();
-            let max_memory = mem_usage_thread.join().unwrap();
-            let max_memory =
-                f64::from(u32::try_from(max_memory).expect("mem usage too high")) / 1024.0;
+            let mut command = Command::new("go");
+            command
+                .args(["build"])
+                .current_dir(args.workspace_dir.join(member))
+                .envs(build_env.iter().copied());
+            build_retrying(
+                &mut command,
+                args.stream_build,
+                args.retry_build_once_on_lock,
+            )
+        } else {
+            // Default case: use "cargo build --release"
+            let mut command = Command::new("cargo");
+            command
+                .arg("build")
+                .args(profile_args(profile))
+                .current_dir(args.workspace_dir.join(member))
+                .envs(build_env.iter().copied())
+                .envs(target_env);
+            build_retrying(
+                &mut command,
+                args.stream_build,
+                args.retry_build_once_on_lock,
+            )
+        };
+
+        build_times.insert(variant.clone(), build_start.elapsed());
 
-            if output.stderr.len() > 0 {
+        if let Err(stderr) = result {
+            if args.stream_build {
+                log::error!("Building {:?} failed, see its output above.", member);
+            } else {
                 log::error!(
-                    "Benchmarking {:?} failed: \n{}",
+                    "Building {:?} failed: \n{}",
                     member,
-                    String::from_utf8_lossy(&output.stderr)
+                    bench::tail("stderr", &stderr, args.stderr_limit)
                 );
-            } else {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-
-                result_md.add_item(format!("## {}", framework_name));
-                result_md.add_item(format!("Maximum Memory Usage: {:.1} MB", max_memory));
-                result_md.add_item(format!("```\n{}\n```", stdout.trim()));
-
-                if let Ok(metrics) = stdout.parse::<Metrics>() {
-                    reports.push(Report::new(
-                        framework_name,
-                        max_memory,
-                        metrics,
-                    ));
-                } else {
-                    log::warn!("Could not parse benchmark result: {}", stdout);
-                }
             }
-
-            // lets CPU cooling down, ignore last member.
-            if index != members_len - 1 {
-                thread::sleep(Duration::from_secs(cd));
+            if args.strict {
+                log::error!("Aborting, --strict allows no build failure.");
+                std::process::exit(1);
             }
+            exclude.push(variant.clone());
+            continue;
         }
-    }
 
-    for (bench_type, result_md) in output_map {
-        let mut output_md = base_md.clone();
+        if let (Some(target), false) = (&args.build_target, is_go(member)) {
+            let member_dir = args.workspace_dir.join(member);
+            let mut command = Command::new("cargo");
+            command
+                .args(["build", "--target", target])
+                .args(profile_args(profile))
+                .current_dir(&member_dir)
+                .envs(build_env.iter().copied())
+                .envs(target_env);
 
-        output_md.add_item("## Comparisons");
-        output_md.add_item(Report::generate_from(&reports));
+            let target_dir = args
+                .shared_target
+                .clone()
+                .unwrap_or_else(|| default_target_dir(&args.workspace_dir));
+            match build_retrying(
+                &mut command,
+                args.stream_build,
+                args.retry_build_once_on_lock,
+            ) {
+                Ok(()) => match target_binary_size(&target_dir, &member_dir, target, profile) {
+                    Some(size) => {
+                        binary_sizes.insert(variant.clone(), size);
+                    }
+                    None => log::warn!("Could not find the {} binary of {:?}.", target, member),
+                },
+                Err(stderr) => log::warn!(
+                    "Building {:?} for {} failed: \n{}",
+                    member,
+                    target,
+                    bench::tail("stderr", &stderr, args.stderr_limit)
+                ),
+            }
+        }
+    }
 
-        output_md.add_item(result_md.finish());
+    if args.warn_background_processes || args.fail_on_busy {
+        let busy = cpu::background(args.busy_threshold);
+        for process in &busy {
+            log::warn!(
+                "{} (PID {}) is using {:.0}% of a core, background load may skew the results.",
+                process.name,
+                process.pid,
+                process.usage
+            );
+        }
+        if args.fail_on_busy && !busy.is_empty() {
+            log::error!(
+                "{} process(es) use at least {}% of a core, aborting for --fail-on-busy.",
+                busy.len(),
+                args.busy_threshold
+            );
+            std::process::exit(1);
+        }
+    }
 
-        let output_path = args.output_dir.join(format!("{}.md", bench_type));
+    let sys = System::new_all();
 
-        log::info!("Writing output to {:?}.", output_path);
-        fs::write(output_path, output_md.finish()).unwrap();
+    let hardware = HardwareInfo::of(&sys);
+    let cpus = sys.cpus().len();
+    let cpu_count = match args.bench_threads {
+        Some(0) => {
+            log::error!("--bench-threads must be at least 1.");
+            std::process::exit(1);
+        }
+        Some(threads) => {
+            if threads > cpus {
+                log::warn!(
+                    "--bench-threads {} exceeds the {} CPUs of this machine, the load generator \
+                     is oversubscribed.",
+                    threads,
+                    cpus
+                );
+            }
+            threads
+        }
+        None => cpus.saturating_sub(1).max(1),
+    };
+    let cd = args.cd;
+    let members_len = variants.len();
+
+    let body_note = bodies.as_ref().map(|bodies| match bodies.files() {
+        files if rotates_bodies && files.len() > 1 => format!(
+            "Request bodies: `POST` requests rotating through the {} files of `{}`.",
+            files.len(),
+            args.body_dir.as_deref().unwrap_or(Path::new("")).display()
+        ),
+        files => format!(
+            "Request bodies: `POST` requests, all with the body of `{}`.",
+            files[0].display()
+        ),
+    });
+
+    // every artifact of this run goes to its own directory.
+    let archive_root = args.output_dir_per_run.then(|| args.output_dir.clone());
+    if let Some(root) = &archive_root {
+        match archive::create(root) {
+            Ok(run_dir) => {
+                log::info!("Writing this run to {:?}.", run_dir);
+                args.output_dir = run_dir;
+            }
+            Err(e) => {
+                log::error!("Could not create the run directory in {:?}: {}", root, e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let bench = Bench::new(&args, cpu_count, custom_parser, bodies);
+    let bench_command = bench.display(&args.url);
+    for flag in bench.tool_arg_conflicts(&args.url) {
+        log::warn!(
+            "--tool-arg {} is already passed by bench-bot, the tool gets it twice.",
+            flag
+        );
+    }
+    let category_args = config
+        .category
+        .iter()
+        .map(|(category, overrides)| (category.as_str(), overrides.apply(&args)))
+        .collect::<HashMap<_, _>>();
+    let category_benches = category_args
+        .iter()
+        .map(|(category, args)| (*category, bench.with_args(args)))
+        .collect::<HashMap<_, _>>();
+
+    let mut methodology = Methodology {
+        tool: bench.command(&args.url, args.connections, args.duration)[0].clone(),
+        command: bench_command.clone(),
+        startup_wait: STARTUP_WAIT,
+        sample_interval: SAMPLE_INTERVAL,
+        cooldown: Duration::from_secs(cd),
+        warmup: None,
+        notes: Vec::new(),
+    };
+    // once per connection count, categories may override `-c`.
+    let mut calibrated = iter::once(args.connections)
+        .chain(category_args.values().map(|args| args.connections))
+        .collect::<Vec<_>>();
+    calibrated.sort_unstable();
+    calibrated.dedup();
+    let ceilings = calibrated
+        .into_iter()
+        .filter(|_| args.calibrate)
+        .filter_map(|connections| Some((connections, calibrate_generator(&bench, connections)?)))
+        .collect::<Vec<_>>();
+    if !ceilings.is_empty() {
+        let reached = ceilings
+            .iter()
+            .map(|(connections, ceiling)| {
+                format!("{:.0} requests/sec at {} connections", ceiling, connections)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        methodology.add_note(format!(
+            "Calibration: the load generator reached {} against a null server on loopback. \
+             Frameworks above {:.0}% of it at their connection count are flagged as likely \
+             limited by the generator rather than the server.",
+            reached,
+            calibrate::GENERATOR_BOUND * 100.0
+        ));
+    }
+    let mut categories = category_args.iter().collect::<Vec<_>>();
+    categories.sort_unstable_by_key(|(category, _)| *category);
+    for (category, args) in categories {
+        methodology.add_note(category::describe(category, args));
+    }
+    if args.tool == Tool::Builtin {
+        methodology.add_note(format!(
+            "Load generator: bench-bot's built-in HTTP/1.1 client, one thread per connection \
+             on this {} CPU machine. It is slower than rewrk, fast servers may be measured at \
+             the client's limit.",
+            cpus
+        ));
+    } else {
+        methodology.add_note(format!(
+            "Threads: the load generator runs {} threads on this {} CPU machine{}.",
+            cpu_count,
+            cpus,
+            if args.bench_threads.is_some() {
+                ", set with `--bench-threads`"
+            } else {
+                ""
+            }
+        ));
+    }
+    methodology.add_note(
+        "Core utilization: usage of every core, sampled along with memory and averaged over the \
+         run. The load generator shares the machine and is included.",
+    );
+    if let Some(encoding) = &args.accept_encoding {
+        methodology.add_note(format!(
+            "Compression: requests send `Accept-Encoding: {}`, the encoding the server answered \
+             with is shown next to each framework.",
+            encoding
+        ));
+    }
+    if let Some(mem_limit) = args.mem_limit {
+        methodology.add_note(format!(
+            "Memory limit: {} MB per server through a cgroup v2, OOM-killed servers are reported \
+             instead of measured.",
+            mem_limit
+        ));
+    }
+
+    if let Some(mem_abort) = args.mem_abort {
+        methodology.add_note(format!(
+            "Memory ceiling: servers sampled above {} MB of resident memory are killed and \
+             reported instead of measured.",
+            mem_abort
+        ));
+    }
+
+    if args.no_keepalive {
+        methodology.add_note(
+            "Keep-alive: disabled, every request opens a new connection. Conn/Sec is the \
+             request total divided by the duration.",
+        );
+    }
+    if args.ramp {
+        methodology.add_note(format!(
+            "Ramp: {}s runs from {} connections, doubling until requests/sec gain less than \
+             {:.0}% or {} connections are reached. The table shows the best run of each ramp, \
+             the ramp of every framework its peak memory at each step.",
+            args.ramp_step,
+            args.ramp_start,
+            args.ramp_tolerance * 100.0,
+            args.connections
+        ));
+    }
+    if let Some(path) = &args.websocket {
+        methodology.add_note(format!(
+            "WebSocket: after the HTTP run, {} connections to `{}` echo a 13 byte text message \
+             for {}s with bench-bot's built-in client; latency is the round trip of a message. \
+             Memory is not sampled during this phase.",
+            args.connections, path, args.duration
+        ));
+    }
+    if args.pipeline > 1 {
+        methodology.add_note(format!(
+            "Pipelining: {} requests are in flight per connection. Numbers are not comparable \
+             with runs without pipelining.",
+            args.pipeline
+        ));
+    }
+    if args.url.len() > 1 {
+        methodology.add_note(format!(
+            "Mixed traffic: load is spread by the tool across {} at once, results are the \
+             aggregate over all of them.",
+            args.url
+                .iter()
+                .map(|url| format!("`{}`", url))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    if args.matrix {
+        let (connections, durations) = matrix_axes(&args);
+        methodology.add_note(format!(
+            "Matrix: after the regular run, every framework is also benchmarked at {} \
+             connections for {} seconds, every combination once, with the peak memory of each.",
+            connections
+                .iter()
+                .map(usize::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+            durations
+                .iter()
+                .map(usize::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    if args.until_stable && !args.ramp {
+        methodology.add_note(format!(
+            "Runs: every framework is benchmarked until two successive runs are within {:.0}% \
+             requests/sec of each other, at most {} times. The better of those two runs is \
+             reported, or the median one when a framework never stabilizes.",
+            args.tolerance * 100.0,
+            args.max_runs
+        ));
+    } else if args.runs > 1 && !args.ramp {
+        methodology.add_note(format!(
+            "Runs: every framework is benchmarked {} times back to back, the run with the median \
+             requests/sec is reported. Results whose requests/sec vary by more than {:.0}% \
+             (coefficient of variation) are flagged as noisy.",
+            args.runs,
+            args.warn_on_high_variance * 100.0
+        ));
+    }
+    if args.mem_stat != MemStat::Peak {
+        methodology.add_note(format!(
+            "Memory statistic: the {} of the memory samples is headlined in place of the peak, \
+             which discards short spikes, the peak follows it.",
+            args.mem_stat.label()
+        ));
+    }
+    if !args.mem_at.is_empty() {
+        methodology.add_note(format!(
+            "Memory at: the first memory sample at or after {} seconds since the benchmark \
+             started is also reported, `-` when the run ended before.",
+            args.mem_at
+                .iter()
+                .map(u64::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    if let Some(warmup) = args.mem_warmup_ms {
+        methodology.add_note(format!(
+            "Memory warmup: memory samples of the first {} ms after a server is ready are \
+             discarded{}.",
+            warmup,
+            if args.keep_startup_peak {
+                ", the peak including them is shown as Memory.Incl. Startup"
+            } else {
+                ""
+            }
+        ));
+    }
+    if let Some(path) = &args.gc_endpoint {
+        methodology.add_note(format!(
+            "Post-GC memory: after the benchmark, Go members are asked to collect garbage at \
+             `{}` and their memory is sampled again {} ms later. Rust members have no garbage \
+             to collect, their peak is the comparable figure.",
+            path,
+            GC_SETTLE.as_millis()
+        ));
+    }
+    if args.profile.is_some() {
+        methodology.add_note(
+            "Profiling: `perf` sampled every server at 99Hz during its benchmark, which costs \
+             some throughput.",
+        );
+    }
+    if !args.profiles.is_empty() {
+        methodology.add_note(format!(
+            "Build profiles: Rust members are built and benchmarked with each of `{}`, reported \
+             as `framework [profile]`.",
+            args.profiles.join("`, `")
+        ));
+    }
+    if let Some(target) = &args.build_target {
+        methodology.add_note(format!(
+            "Build target: Rust members are also built for `{}` to report their binary size, \
+             the benchmarked servers are native builds.",
+            target
+        ));
+    }
+    if args.server_cores.is_some() || args.bench_cores.is_some() {
+        let cores = |cores: &Option<CoreSet>| {
+            cores
+                .as_ref()
+                .map_or("any core".to_string(), |cores| format!("cores {}", cores))
+        };
+        methodology.add_note(format!(
+            "Pinning: servers run on {}, the load generator on {}, through `taskset`.",
+            cores(&args.server_cores),
+            cores(&args.bench_cores)
+        ));
+    }
+    if let Some(requests) = args.warmup_requests {
+        methodology.warmup = Some(if args.http2 || args.protocol == Protocol::Grpc {
+            format!(
+                "about {} requests before the measured runs, the load generator running for as \
+                 long as they take at its rate over a first second",
+                requests
+            )
+        } else {
+            format!(
+                "{} requests over HTTP/1.1 before the measured runs, not measured",
+                requests
+            )
+        });
+    }
+    if args.discard_first_run && !args.ramp {
+        let discarded = format!(
+            "every framework is first benchmarked once for {}s at full load and that run is \
+             thrown away, the reported runs follow it",
+            args.duration
+        );
+        methodology.warmup = Some(match methodology.warmup.take() {
+            Some(requests) => format!("{}, then {}", requests, discarded),
+            None => discarded,
+        });
+    }
+    if let Some(timeout) = args.request_timeout {
+        methodology.add_note(format!(
+            "Request timeout: {} ms, requests taking longer count as errors and as timeouts.",
+            timeout
+        ));
+    }
+    if let Some(max_error_rate) = args.max_error_rate {
+        methodology.add_note(format!(
+            "Errors: frameworks with more than {}% failed requests are left out of the \
+             comparison.",
+            max_error_rate * 100.0
+        ));
+    }
+    if args.instances > 1 {
+        methodology.add_note(format!(
+            "Instances: {} instances of every framework run on their own ports behind \
+             bench-bot's round-robin TCP balancer, which shares the machine. Memory is the total \
+             over the instances{}.",
+            args.instances,
+            if args.mem_limit.is_some() {
+                " and so is the memory limit"
+            } else {
+                ""
+            }
+        ));
+    }
+    if let (Protocol::Grpc, Some(call)) = (args.protocol, &args.grpc_call) {
+        methodology.add_note(format!(
+            "gRPC: unary calls of `{}` with `ghz`, one call in flight per connection. \
+             Requests/sec counts only calls answered `OK`, other status codes are errors. \
+             Response headers are not sampled.",
+            call
+        ));
+    }
+    if args.http2 {
+        methodology.add_note(
+            "HTTP/2: cleartext with prior knowledge (h2c). Requests are multiplexed over the \
+             connections, so a connection is not one request in flight as with HTTP/1.1. \
+             Response headers are not sampled.",
+        );
+    }
+    if let Some(note) = body_note {
+        methodology.add_note(note);
+    }
+    if let Some(interval) = args.probe_interval_ms {
+        let background = args.rate.map_or(
+            "as many requests/sec as the load generator manages".to_string(),
+            |rate| format!("a steady {} requests/sec", rate),
+        );
+        methodology.add_note(format!(
+            "Probe latency: under a background load of {}, a `GET` of the first URL every {} ms on \
+             a new connection, timed from connecting to the end of the response.",
+            background, interval
+        ));
+    } else if let Some(rate) = args.rate {
+        methodology.add_note(format!(
+            "Load: a steady {} requests/sec over the connections.",
+            rate
+        ));
+    }
+    if args.perf_stat {
+        methodology.add_note(
+            "Perf stat: instructions, cycles and cache misses of the servers during the benchmark, \
+             counted with `perf stat`. IPC is instructions per cycle, the cache-miss rate the \
+             share of cache references that missed.",
+        );
+    }
+    if args.disk_io {
+        methodology.add_note(
+            "Disk I/O: bytes the servers read from and wrote to storage during the benchmark, from \
+             `/proc/<pid>/io`. Reads served by the page cache don't count.",
+        );
+    }
+    if args.cd_until_idle {
+        methodology.add_note(format!(
+            "Cooldown: until the CPU is below {}% usage for {}s, for at most {}s and then {}s \
+             more, instead of a fixed {}s. The cooldown before every framework is in its timings.",
+            args.idle_threshold, args.idle_window, args.idle_max, cd, cd
+        ));
+    }
+    if args.isolate && cfg!(target_os = "linux") {
+        methodology.add_note(
+            "Isolation: the page cache is dropped during the cooldown between frameworks.",
+        );
+    }
+    if args.skip_cooldown_first {
+        methodology.add_note(
+            "Cooldown: none before the first framework, which starts right after the builds.",
+        );
+    }
+    if args.fair_build {
+        let format_env = |env: &[(&str, &str)]| {
+            env.iter()
+                .map(|(key, value)| format!("`{}={}`", key, value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        methodology.add_note(format!(
+            "Fair build: Rust members are built with {}, Go members with {}.",
+            format_env(&FAIR_RUST_ENV),
+            format_env(&FAIR_GO_ENV)
+        ));
+    }
+
+    let revision = Revision::of(&args.workspace_dir);
+    match &revision {
+        Some(revision) => log::info!("Workspace is at commit {}.", revision.short()),
+        None => log::info!("Workspace is not a git repository, no commit is recorded."),
+    }
+
+    let database = args.sqlite.as_ref().map(|path| {
+        let git_hash = revision.as_ref().map(|revision| revision.commit.as_str());
+        sqlite::Database::open(path, git_hash, args.label.as_deref(), &bench_command)
+            .unwrap_or_else(|e| {
+                log::error!("Could not open --sqlite {:?}: {}", path, e);
+                std::process::exit(1);
+            })
+    });
+
+    let metadata = Metadata {
+        cpu: hardware.cpu.clone(),
+        hardware: hardware.clone(),
+        command: bench_command.clone(),
+        git: revision.clone(),
+        label: args.label.clone(),
+    };
+
+    let framework_names = report_names(&members, &package_names);
+
+    let mut output_map = HashMap::new();
+    let mut reports = Vec::with_capacity(members.len());
+    let mut all_spawned = Vec::new();
+    let mut ws_map = HashMap::new();
+
+    // consecutive members without a report, and the reports before the
+    // current member.
+    let mut failures = 0;
+    let mut reports_before = 0;
+    let mut bailed = None;
+    let mut cooldown = Cooldown::new(!args.skip_cooldown_first);
+
+    for (index, variant) in variants.iter().enumerate() {
+        let (member, profile) = variant;
+        let profile = profile.as_deref();
+        if index > 0 {
+            failures = if reports.len() > reports_before {
+                0
+            } else {
+                failures + 1
+            };
+        }
+        reports_before = reports.len();
+        if let Some(max) = args.bail_after_failures.filter(|max| failures >= *max) {
+            let reason = format!(
+                "Bailed out: the last {} members failed in a row, the environment is likely \
+                 broken. The other {} members were not benchmarked.",
+                max,
+                members_len - index
+            );
+            log::error!("{}", reason);
+            bailed = Some(reason);
+            break;
+        }
+
+        if exclude.contains(variant) {
+            log::warn!("Skipping {:?} because build was failed.", member);
+        } else {
+            let bench_type = bench_type(member);
+            // overrides of the category apply to everything below.
+            let (args, bench) = match (
+                category_args.get(bench_type),
+                category_benches.get(bench_type),
+            ) {
+                (Some(args), Some(bench)) => (args, bench),
+                _ => (&args, &bench),
+            };
+
+            let framework_name = match profile {
+                Some(profile) => format!("{} [{}]", framework_names[member], profile),
+                None => framework_names[member].clone(),
+            };
+            let framework_name = framework_name.as_str();
+
+            let result_md = output_map.entry(bench_type).or_insert_with(Markdown::new);
+
+            let mut timings = Timings {
+                build: build_times.get(variant).copied(),
+                ..Timings::default()
+            };
+            // lets CPU cooling down from the builds or the last measured run,
+            // before the port and the cgroup are taken so that neither goes
+            // stale while waiting.
+            if cooldown.due() {
+                timings.cooldown = Some(cool_down(args, cd));
+            }
+
+            let (urls, port) = match free_urls(&args.url) {
+                Some(free) => free,
+                None => {
+                    log::error!("Skipping {:?} because no free port was found.", member);
+                    continue;
+                }
+            };
+
+            log::info!("Benchmarking {:?} on port {}", member, port);
+
+            let mut command = if is_go(member) {
+                // If the member starts with "go_", use "go run"
+                let mut command = Command::new("go");
+                command.args(["run", "."]); // `.` indicates the current directory for Go run
+                command
+            } else {
+                // Default case: use "cargo run --release -q"
+                let mut command = Command::new("cargo");
+                command.args(["run", "-q"]).args(profile_args(profile));
+                command
+            };
+            // the run step must see the build settings too, otherwise cargo
+            // rebuilds the member with its own profile.
+            command
+                .current_dir(args.workspace_dir.join(member))
+                .envs(build_env(member, args.fair_build).iter().copied())
+                .envs(target_env(member, args.shared_target.as_deref()))
+                .env("BENCH_PORT", port.to_string());
+
+            if let Some(cores) = &args.server_cores {
+                command = cores.wrap(&command);
+            }
+
+            let cgroup = match args
+                .mem_limit
+                .map(|limit| Cgroup::create(framework_name, limit))
+            {
+                Some(Ok(cgroup)) => {
+                    command = cgroup.wrap(&command);
+                    Some(cgroup)
+                }
+                Some(Err(e)) => {
+                    log::error!(
+                        "Skipping {:?} because its cgroup could not be created: {}",
+                        member,
+                        e
+                    );
+                    continue;
+                }
+                None => None,
+            };
+
+            let startup_start = Instant::now();
+
+            let (balancer, instance_ports) = match spawn_balancer(&urls[0], port, args.instances) {
+                Ok(balanced) => balanced,
+                Err(e) => {
+                    log::error!(
+                        "Skipping {:?} because its balancer could not start: {}",
+                        member,
+                        e
+                    );
+                    continue;
+                }
+            };
+            if args.summary_json || matches!(sink, Some(stream::Sink::Stdout)) {
+                // keep stdout to the report lines.
+                command.stdout(io::stderr());
+            }
+            let (mut servers, tracked): (Vec<_>, Vec<_>) = instance_ports
+                .iter()
+                .map(|port| {
+                    interrupt::spawn(command.env("BENCH_PORT", port.to_string()))
+                        .expect("Failed to execute server")
+                })
+                .unzip();
+
+            thread::sleep(STARTUP_WAIT);
+
+            // before the sample request, the first one the servers get.
+            let idle_memory = process::memory(
+                &servers
+                    .iter()
+                    .map(|server| PidExt::from_u32(server.id()))
+                    .collect::<Vec<Pid>>(),
+            );
+            check_listeners(member, &instance_ports, &servers);
+
+            // one sample request to see what the server actually answers,
+            // which the load generator doesn't tell.
+            let mut sample_headers = Vec::new();
+            if let Some(encoding) = &args.accept_encoding {
+                sample_headers.push(("Accept-Encoding", encoding.as_str()));
+            }
+            // the sample and the websocket phase only target the first url.
+            let url = &urls[0];
+            let sample = if args.protocol == Protocol::Grpc {
+                // the servers answer gRPC only, there is no page to sample.
+                None
+            } else if args.client_cert.is_some() {
+                // the sample client can't present a certificate.
+                None
+            } else if args.http2 {
+                // the sample client only speaks HTTP/1.1, check h2c instead.
+                if let Err(e) = probe::h2c(url) {
+                    log::warn!("{:?} does not seem to speak h2c: {}", member, e);
+                }
+                None
+            } else {
+                probe::get(url, &sample_headers)
+                    .map_err(|e| log::warn!("Sample request to {:?} failed: {}", member, e))
+                    .ok()
+            };
+
+            if let Some(response) = sample
+                .as_ref()
+                .filter(|response| !probe::is_expected(response.status, &args.expect_status))
+            {
+                log::warn!(
+                    "Sample request to {:?} was answered {}, which is not an expected status.",
+                    member,
+                    response.status
+                );
+            }
+
+            let encoding = args.accept_encoding.as_ref().map(|_| match &sample {
+                Some(response) => response
+                    .header("Content-Encoding")
+                    .unwrap_or("identity")
+                    .to_owned(),
+                None => "unknown".to_owned(),
+            });
+            let response_size = sample.as_ref().map(|response| response.body.len());
+            let headers = sample.map(|response| response.headers).unwrap_or_default();
+
+            // before the samplers, which would otherwise measure it.
+            if let Some(requests) = args.warmup_requests {
+                log::info!("Warming {:?} up with {} requests", member, requests);
+                let sent = bench.warm_up(&urls, requests);
+                if sent < requests {
+                    log::warn!(
+                        "Only {} of the {} warmup requests to {:?} were sent.",
+                        sent,
+                        requests,
+                        member
+                    );
+                }
+            }
+
+            let pids = servers
+                .iter()
+                .map(|server| PidExt::from_u32(server.id()))
+                .collect::<Vec<Pid>>();
+            let wrapper_pids = pids.clone();
+            let (tx, rx) = mpsc::channel::<()>();
+
+            let disk_io = args.disk_io;
+            let mem_abort = args.mem_abort;
+            bench::resume();
+            let mem_usage_thread = thread::spawn(move || {
+                let mut sys = System::new();
+                let mut mem_samples = Vec::new();
+                // when every memory sample was taken.
+                let mut mem_taken = Vec::new();
+                let mut core_samples = Vec::new();
+                let mut min_frequency = cpu::MinFrequency::default();
+                let mut server_pids = Vec::new();
+                let mut spawned = Vec::new();
+                let mut server_memory = 0;
+                let mut disk_usage = DiskUsage::default();
+                // memory in KB the servers were killed at by `--mem-abort`.
+                let mut exceeded = None;
+                // sysinfo may not see a just spawned process yet, which would
+                // be sampled as 0 bytes and drag percentiles down.
+                for &pid in &pids {
+                    for _ in 0..PID_RETRIES {
+                        if sys.refresh_process(pid) {
+                            break;
+                        }
+                        thread::sleep(PID_RETRY_INTERVAL);
+                    }
+                }
+                // usage is computed against the previous refresh.
+                sys.refresh_cpu();
+                let ready = Instant::now();
+                while rx.try_recv().is_err() {
+                    thread::sleep(SAMPLE_INTERVAL);
+
+                    // total over every instance.
+                    let mut memory = None;
+                    for pid in &pids {
+                        if sys.refresh_process(*pid) {
+                            *memory.get_or_insert(0) +=
+                                sys.process(*pid).map_or(0, ProcessExt::memory);
+                        }
+                    }
+                    if let Some(memory) = memory {
+                        mem_samples.push(memory);
+                        mem_taken.push(Instant::now());
+                    }
+
+                    // the wrappers' children, i.e. the actual servers.
+                    if server_pids.is_empty() {
+                        sys.refresh_processes();
+                        server_pids = pids
+                            .iter()
+                            .flat_map(|pid| process::descendants(&sys, *pid))
+                            .collect();
+                        spawned = pids
+                            .iter()
+                            .chain(&server_pids)
+                            .filter_map(|pid| Spawned::of(&sys, *pid))
+                            .collect();
+                    }
+                    let mut children = 0;
+                    for child in &server_pids {
+                        if sys.refresh_process(*child) {
+                            children += sys.process(*child).map_or(0, ProcessExt::memory);
+                        }
+                    }
+                    server_memory = server_memory.max(children);
+
+                    // the servers only, the `cargo run`/`go run` wrappers using
+                    // memory of their own.
+                    if exceeded.is_none()
+                        && mem_abort.is_some_and(|ceiling| memory::exceeds(children, ceiling))
+                    {
+                        process::reap(&spawned);
+                        bench::stop();
+                        exceeded = Some(children);
+                    }
+
+                    if disk_io {
+                        for pid in pids.iter().chain(&server_pids) {
+                            disk_usage.sample(pid.as_u32());
+                        }
+                    }
+
+                    sys.refresh_cpu();
+                    let cpus = cpu::usage_and_frequency(&sys);
+                    min_frequency.sample(&cpus);
+                    core_samples.push(cpus.iter().map(|(usage, _)| *usage).collect());
+                }
+                let server_memory = (!server_pids.is_empty()).then_some(server_memory);
+                (
+                    spawned,
+                    ready,
+                    mem_samples,
+                    mem_taken,
+                    CoreUsage::from_samples(&core_samples),
+                    min_frequency.get(),
+                    server_memory,
+                    disk_usage.total(),
+                    exceeded,
+                )
+            });
+
+            let profile_name = framework_name.replace('/', "-");
+            let recording = args.profile.and_then(|Profile::Flamegraph| {
+                let data = env::temp_dir().join(format!("bench-bot-{}.perf.data", profile_name));
+                profile::record(&servers.iter().map(Child::id).collect::<Vec<_>>(), data)
+                    .map_err(|e| log::warn!("Could not profile {:?}: {}", member, e))
+                    .ok()
+            });
+
+            // the servers `cargo run` and `go run` spawned along with them.
+            let mut sys = System::new();
+            sys.refresh_processes();
+            let server_pids = wrapper_pids
+                .iter()
+                .flat_map(|pid| iter::once(*pid).chain(process::descendants(&sys, *pid)))
+                .collect::<Vec<_>>();
+            let counting = args
+                .perf_stat
+                .then(|| {
+                    perf::start(&server_pids)
+                        .map_err(|e| log::warn!("Could not run perf stat on {:?}: {}", member, e))
+                        .ok()
+                })
+                .flatten();
+
+            timings.startup = startup_start.elapsed();
+            let benchmark_start = Instant::now();
+
+            let prober = args.probe_interval_ms.map(|interval| {
+                Prober::start(url, Duration::from_millis(interval), &args.expect_status)
+            });
+            let (output, ramp, samples) = if args.ramp {
+                let (output, ramp) = bench.ramp(&urls, member, &server_pids);
+                (output, Some(ramp), Vec::new())
+            } else {
+                let (output, samples) = bench.repeat(&urls, member);
+                (output, None, samples)
+            };
+
+            let probe_latency = prober.map(Prober::stop);
+            let perf_stat = counting.and_then(|counting| {
+                counting
+                    .finish()
+                    .map_err(|e| log::warn!("perf stat counted nothing on {:?}: {}", member, e))
+                    .ok()
+            });
+            tx.send(()).unwrap();
+            let (
+                spawned,
+                ready,
+                all_mem_samples,
+                mem_taken,
+                core_usage,
+                min_frequency,
+                server_memory,
+                disk_io,
+                mem_exceeded,
+            ) = mem_usage_thread.join().unwrap();
+
+            let gc_url = args
+                .gc_endpoint
+                .as_ref()
+                .filter(|_| is_go(member))
+                .and_then(|path| {
+                    port::host_port(url)
+                        .map(|(host, _)| format!("http://{}:{}{}", host, port, path))
+                });
+            let post_gc_memory = gc_url.and_then(|gc_url| {
+                match probe::get(&gc_url, &[]) {
+                    Ok(response) if response.status < 400 => {}
+                    Ok(response) => {
+                        log::warn!(
+                            "{} answered {}, no post-GC memory for {:?}.",
+                            gc_url,
+                            response.status,
+                            member
+                        );
+                        return None;
+                    }
+                    Err(e) => {
+                        log::warn!(
+                            "Could not request {}, no post-GC memory for {:?}: {}",
+                            gc_url,
+                            member,
+                            e
+                        );
+                        return None;
+                    }
+                }
+                thread::sleep(GC_SETTLE);
+
+                // the server itself rather than its `go run` wrapper, when found.
+                let children = spawned
+                    .iter()
+                    .map(|spawned| spawned.pid)
+                    .filter(|pid| !wrapper_pids.contains(pid))
+                    .collect::<Vec<_>>();
+                process::memory(if children.is_empty() {
+                    &wrapper_pids
+                } else {
+                    &children
+                })
+            });
+
+            if let Some(recording) = recording {
+                let svg = args.output_dir.join(format!("{}.svg", profile_name));
+                match recording.finish(&svg) {
+                    Ok(()) => log::info!("Wrote flamegraph to {:?}.", svg),
+                    Err(e) => log::warn!("Could not render the flamegraph of {:?}: {}", member, e),
+                }
+            }
+
+            if let (Some(path), Some((host, _))) = (&args.websocket, port::host_port(url)) {
+                let ws_url = format!("ws://{}:{}{}", host, port, path);
+                log::info!("Benchmarking {:?} over WebSocket at {}", member, ws_url);
+
+                let ws_duration = Duration::from_secs(args.duration as u64);
+                match websocket::bench(&ws_url, args.connections, ws_duration) {
+                    Ok(stats) => ws_map
+                        .entry(bench_type)
+                        .or_insert_with(Vec::new)
+                        .push((framework_name.to_owned(), stats)),
+                    Err(e) => log::warn!("WebSocket benchmark of {:?} failed: {}", member, e),
+                }
+            }
+
+            let matrix = args.matrix.then(|| {
+                let (connections, durations) = matrix_axes(args);
+                bench.matrix(&urls, member, &server_pids, &connections, &durations)
+            });
+
+            // before the prompt, however long it is left open.
+            timings.benchmark = benchmark_start.elapsed();
+
+            if args.keep_alive_after {
+                log::info!(
+                    "{:?} is still running as PID {:?} at {}, press enter to stop it.",
+                    member,
+                    servers.iter().map(Child::id).collect::<Vec<_>>(),
+                    url
+                );
+                let _ = std::io::stdin().read_line(&mut String::new());
+            }
+
+            for server in &mut servers {
+                let _ = server.kill();
+                let _ = server.wait();
+            }
+            drop(tracked);
+            drop(balancer);
+            all_spawned.extend(spawned);
+            let throttled = min_frequency
+                .zip(hardware.base_frequency_mhz)
+                .is_some_and(|(min, base)| min < base);
+            let to_mb = |kb: Option<u64>| {
+                f64::from(u32::try_from(kb.unwrap_or(0)).expect("mem usage too high")) / 1024.0
+            };
+            let mem_warmup = Duration::from_millis(args.mem_warmup_ms.unwrap_or(0));
+            // samples taken within `--mem-warmup-ms`, at the start of `all_mem_samples`.
+            let warmup_samples = mem_taken
+                .iter()
+                .filter(|taken| taken.duration_since(ready) < mem_warmup)
+                .count();
+            let mem_samples = if warmup_samples < all_mem_samples.len() {
+                &all_mem_samples[warmup_samples..]
+            } else {
+                if warmup_samples > 0 {
+                    log::warn!(
+                        "{:?} ran shorter than --mem-warmup-ms, its memory includes startup.",
+                        member
+                    );
+                }
+                &all_mem_samples[..]
+            };
+            let max_memory = to_mb(MemStat::Peak.of(mem_samples));
+            let startup_peak = (args.keep_startup_peak && args.mem_warmup_ms.is_some())
+                .then(|| to_mb(MemStat::Peak.of(&all_mem_samples)));
+            // `--mem-at` counts from the start of the load, not of the sampler.
+            let (bench_elapsed, bench_samples): (Vec<_>, Vec<_>) = mem_taken
+                .iter()
+                .zip(&all_mem_samples)
+                .filter_map(|(taken, sample)| {
+                    Some((taken.checked_duration_since(benchmark_start)?, *sample))
+                })
+                .unzip();
+            let mem_at = args
+                .mem_at
+                .iter()
+                .zip(memory::at(&bench_elapsed, &bench_samples, &args.mem_at))
+                .map(|(secs, kb)| (*secs, kb.map(|kb| to_mb(Some(kb)))))
+                .collect::<Vec<_>>();
+            let server_memory = server_memory.map(|kb| to_mb(Some(kb)));
+            let idle_memory = idle_memory.map(|kb| to_mb(Some(kb)));
+            // next to the peak of the same processes, the servers' when found
+            // as for the post-GC sample.
+            let post_gc_memory =
+                post_gc_memory.map(|kb| (to_mb(Some(kb)), server_memory.unwrap_or(max_memory)));
+            let mem_stat = (args.mem_stat != MemStat::Peak)
+                .then(|| (args.mem_stat, to_mb(args.mem_stat.of(mem_samples))));
+
+            let mut pending = None;
+
+            // whether load was actually applied, i.e. there is heat to let go.
+            let measured = if cgroup.as_ref().is_some_and(Cgroup::oom_killed) {
+                let mem_limit = args.mem_limit.unwrap_or_default();
+                log::error!(
+                    "{:?} was killed for exceeding {} MB of memory.",
+                    member,
+                    mem_limit
+                );
+
+                result_md.add_item(format!("## {}", framework_name));
+                result_md.add_item(format!(
+                    "Out of memory: killed at the {} MB limit.",
+                    mem_limit
+                ));
+                true
+            } else if let Some(exceeded) = mem_exceeded {
+                let mem_abort = args.mem_abort.unwrap_or_default();
+                log::error!(
+                    "{:?} was killed for exceeding {} MB of memory, at {:.1} MB.",
+                    member,
+                    mem_abort,
+                    to_mb(Some(exceeded))
+                );
+
+                result_md.add_item(format!("## {}", framework_name));
+                result_md.add_item(format!(
+                    "Memory exceeded: killed at {:.1} MB, above the {} MB ceiling.",
+                    to_mb(Some(exceeded)),
+                    mem_abort
+                ));
+                true
+            } else if let Some(failure) = bench.failure(&output) {
+                log::error!("Benchmarking {:?} failed: {}", member, failure.trim_end());
+                false
+            } else {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                if !output.stderr.is_empty() {
+                    log::warn!(
+                        "The load generator wrote to stderr benchmarking {:?}: \n{}",
+                        member,
+                        bench::tail(
+                            "stderr",
+                            &String::from_utf8_lossy(&output.stderr),
+                            args.stderr_limit
+                        )
+                        .trim_end()
+                    );
+                }
+
+                result_md.add_item(format!("## {}", framework_name));
+                for item in metas.get(member).map(Meta::items).unwrap_or_default() {
+                    result_md.add_item(item);
+                }
+                if let Some((stat, memory)) = mem_stat {
+                    result_md.add_item(format!(
+                        "Memory Usage ({}): {:.1} MB",
+                        stat.label(),
+                        memory
+                    ));
+                }
+                result_md.add_item(format!("Maximum Memory Usage: {:.1} MB", max_memory));
+                if let Some(idle_memory) = idle_memory {
+                    result_md.add_item(format!(
+                        "Idle Memory Usage: {:.1} MB, {:+.1} MB under load",
+                        idle_memory,
+                        max_memory - idle_memory
+                    ));
+                }
+                if let Some(startup_peak) = startup_peak {
+                    result_md.add_item(format!(
+                        "Maximum Memory Usage (including startup): {:.1} MB",
+                        startup_peak
+                    ));
+                }
+                if let Some(server_memory) = server_memory {
+                    result_md.add_item(format!(
+                        "Maximum Server Memory Usage: {:.1} MB (children of the `cargo run`/`go \
+                         run` process above)",
+                        server_memory
+                    ));
+                    if (server_memory - max_memory).abs()
+                        > max_memory.max(server_memory) * MEMORY_GAP
+                    {
+                        log::warn!(
+                            "Memory of {:?} is {:.1} MB for its wrapper but {:.1} MB for the \
+                             server, the reported usage is the wrapper's only.",
+                            member,
+                            max_memory,
+                            server_memory
+                        );
+                    }
+                }
+                if !mem_at.is_empty() {
+                    result_md.add_item(format!("Memory Usage at {}", memory::render_at(&mem_at)));
+                }
+                if let Some(probe_latency) = &probe_latency {
+                    result_md.add_item(format!("Probe Latency: {}", probe_latency.render()));
+                }
+                if let Some(disk_io) = &disk_io {
+                    result_md.add_item(format!("Disk Read/Write: {}", disk_io.render()));
+                }
+                if let Some(perf_stat) = &perf_stat {
+                    result_md.add_item(format!("Perf Stat: {}", perf_stat.render()));
+                }
+                if let Some((post_gc_memory, peak)) = post_gc_memory {
+                    result_md.add_item(format!(
+                        "Memory Usage After GC: {:.1} MB, of a {:.1} MB peak",
+                        post_gc_memory, peak
+                    ));
+                }
+                if let Some(frequency) = min_frequency {
+                    result_md.add_item(format!("Min. CPU Frequency: {} MHz", frequency));
+                }
+                if throttled {
+                    log::warn!(
+                        "{:?} was possibly throttled, a loaded core ran below base clock.",
+                        member
+                    );
+                    result_md.add_item(
+                        "**Possibly throttled:** a loaded core ran below its base clock.",
+                    );
+                }
+                if let (Some(target), Some(size)) = (&args.build_target, binary_sizes.get(variant))
+                {
+                    result_md.add_item(format!(
+                        "Binary Size ({}): {:.1} MB",
+                        target,
+                        *size as f64 / 1024.0 / 1024.0
+                    ));
+                }
+                if let Some(usage) = &core_usage {
+                    result_md.add_item(format!(
+                        "Core utilization: min {:.1}%, max {:.1}%, mean {:.1}%",
+                        usage.min, usage.max, usage.mean
+                    ));
+                }
+                if let Some(encoding) = &encoding {
+                    result_md.add_item(format!("Content-Encoding: {}", encoding));
+                }
+                if !headers.is_empty() {
+                    let headers = headers
+                        .iter()
+                        .map(|(key, value)| format!("{}: {}", key, value))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    result_md.add_item("Response headers:");
+                    result_md.add_item(format!("```\n{}\n```", headers));
+                }
+                if let Some(ramp) = &ramp {
+                    result_md.add_item("Ramp:");
+                    result_md.add_item(ramp.render());
+                }
+                if let Some(matrix) = &matrix {
+                    result_md.add_item("Matrix (Req/Sec):");
+                    result_md.add_item(matrix.render());
+                }
+                // the best connection count of the ramp, or else of the matrix.
+                let optimal_connections = ramp
+                    .as_ref()
+                    .and_then(|ramp| ramp.max())
+                    .map(|max| max.connections)
+                    .or_else(|| {
+                        matrix
+                            .as_ref()
+                            .and_then(|matrix| matrix.optimal_connections())
+                    });
+                if let Some(optimal) = optimal_connections {
+                    result_md.add_item(format!("Optimal Concurrency: {} connections", optimal));
+                }
+                let cv = report::coefficient_of_variation(&samples);
+                if let Some(cv) = cv.filter(|cv| *cv > args.warn_on_high_variance) {
+                    log::warn!(
+                        "Requests/sec of {:?} vary by {:.1}% over {} runs, the result is noisy. \
+                         Background load, thermal throttling or a noisy VM neighbor may make \
+                         this machine unsuitable for benchmarking.",
+                        member,
+                        cv * 100.0,
+                        samples.len()
+                    );
+                    result_md.add_item(format!(
+                        "**Noisy result:** requests/sec vary by {:.1}% over {} runs, this \
+                         machine may not be suitable for benchmarking.",
+                        cv * 100.0,
+                        samples.len()
+                    ));
+                }
+                let req_per_sec = bench
+                    .parse(&output)
+                    .ok()
+                    .and_then(|metrics| metrics.req_per_sec());
+                let ceiling = ceilings
+                    .iter()
+                    .find(|(connections, _)| *connections == args.connections)
+                    .map(|(_, ceiling)| *ceiling);
+                if let Some((ceiling, req_per_sec)) =
+                    ceiling.zip(req_per_sec).filter(|(ceiling, req_per_sec)| {
+                        calibrate::generator_bound(*req_per_sec, *ceiling)
+                    })
+                {
+                    log::warn!(
+                        "{:?} reached {:.0} requests/sec, close to the load generator's ceiling \
+                         of {:.0}. The result is likely generator-bound.",
+                        member,
+                        req_per_sec,
+                        ceiling
+                    );
+                    result_md.add_item(format!(
+                        "**Likely generator-bound:** {:.0}% of the load generator's ceiling.",
+                        req_per_sec / ceiling * 100.0
+                    ));
+                }
+                if samples.len() > 1 {
+                    let samples = samples
+                        .iter()
+                        .map(|rps| format!("{:.2}", rps))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    result_md.add_item(format!("Req/Sec of each run: {}", samples));
+                }
+                let metrics = bench.parse(&output).ok();
+                if let Some((min, avg, max)) = metrics.as_ref().and_then(Metrics::latency_summary) {
+                    result_md.add_item(format!(
+                        "Latency: min {:.2} ms, avg {:.2} ms, max {:.2} ms",
+                        min, avg, max
+                    ));
+                }
+                let tail = metrics
+                    .as_ref()
+                    .map(Metrics::tail_latency)
+                    .unwrap_or_default();
+                if tail.len() > 1 {
+                    let tail = tail
+                        .iter()
+                        .map(|(name, value)| format!("{} {}", name, value))
+                        .collect::<Vec<_>>();
+                    result_md.add_item(format!("Tail Latency: {}", tail.join(", ")));
+                }
+                let ttfb = metrics.as_ref().map(Metrics::ttfb).unwrap_or_default();
+                if !ttfb.is_empty() {
+                    let ttfb = ttfb
+                        .iter()
+                        .map(|(name, value)| format!("{} {}", name, value))
+                        .collect::<Vec<_>>();
+                    result_md.add_item(format!("Time to First Byte: {}", ttfb.join(", ")));
+                }
+                if let Some(size) = response_size {
+                    result_md.add_item(format!("Response Size: {} bytes", size));
+                }
+                result_md.add_collapsible("Raw output", format!("```\n{}\n```", stdout.trim()));
+
+                let timeouts = bench
+                    .parse(&output)
+                    .ok()
+                    .and_then(|metrics| metrics.timeouts());
+                if let (Some(timeout), Some(timeouts)) = (args.request_timeout, timeouts) {
+                    result_md.add_item(format!(
+                        "Timeouts: {} requests after {} ms",
+                        timeouts, timeout
+                    ));
+                }
+                let error_rate = bench
+                    .parse(&output)
+                    .ok()
+                    .and_then(|metrics| metrics.error_rate());
+                if let Some(error_rate) = error_rate.filter(|_| args.max_error_rate.is_some()) {
+                    result_md.add_item(format!("Error Rate: {:.2}%", error_rate * 100.0));
+                }
+                let too_many_errors = args
+                    .max_error_rate
+                    .zip(error_rate)
+                    .is_some_and(|(max, rate)| rate > max);
+
+                if too_many_errors {
+                    log::error!(
+                        "{:?} failed: {:.2}% of its requests errored, more than --max-error-rate.",
+                        member,
+                        error_rate.unwrap_or_default() * 100.0
+                    );
+                    result_md.add_item(
+                        "**Failed:** too many requests errored, left out of the comparison.",
+                    );
+                } else if let Ok(metrics) = bench.parse(&output) {
+                    let connections = ramp
+                        .as_ref()
+                        .and_then(|ramp| ramp.max())
+                        .map_or(args.connections, |max| max.connections);
+                    let mut report = Report::new(framework_name, max_memory, metrics)
+                        .with_bench_type(bench_type)
+                        .with_language(Language::of(member))
+                        .with_tags(config.tags.get(member).cloned().unwrap_or_default())
+                        .with_meta(metas.get(member).cloned())
+                        .with_connections(connections)
+                        .with_encoding(encoding)
+                        .with_optimal_connections(optimal_connections)
+                        .with_ramp(ramp)
+                        .with_matrix(matrix)
+                        .with_samples(samples)
+                        .with_headers(headers)
+                        .with_response_size(response_size)
+                        .with_binary_size(binary_sizes.get(variant).copied())
+                        .with_core_usage(core_usage)
+                        .with_protocol(match args.protocol {
+                            Protocol::Grpc => Some("gRPC"),
+                            Protocol::Http => args.http2.then_some("h2c"),
+                        })
+                        .with_server_memory(server_memory)
+                        .with_min_frequency(min_frequency, throttled)
+                        .with_mem_stat(mem_stat)
+                        .with_startup_peak(startup_peak)
+                        .with_idle_memory(idle_memory, max_memory)
+                        .with_post_gc_memory(post_gc_memory)
+                        .with_mem_at(mem_at)
+                        .with_disk_io(disk_io)
+                        .with_probe_latency(probe_latency)
+                        .with_perf_stat(perf_stat);
+                    if args.no_keepalive {
+                        let duration = if args.ramp {
+                            args.ramp_step
+                        } else {
+                            args.duration
+                        };
+                        report = report.with_connection_rate(duration);
+                    }
+                    pending = Some(report);
+                } else {
+                    log::warn!("Could not parse benchmark result: {}", stdout);
+                }
+                true
+            };
+
+            cooldown.ran(measured);
+
+            if let Some(report) = pending {
+                result_md.add_item(format!("Timings: {}", timings.render()));
+                let report = report.with_timings(timings);
+                if let Some(stream) = &mut sink {
+                    if let Err(e) = stream.emit(&report) {
+                        log::warn!(
+                            "Could not stream the report of {:?}, not streaming anymore: {}",
+                            member,
+                            e
+                        );
+                        sink = None;
+                    }
+                }
+                if let Some(database) = &database {
+                    if let Err(e) = database.insert(&report) {
+                        log::warn!(
+                            "Could not insert the report of {:?} into --sqlite: {}",
+                            member,
+                            e
+                        );
+                    }
+                }
+                reports.push(report);
+            }
+        }
+    }
+
+    // `kill` only reaches the wrapper, a server it spawned may outlive it.
+    for leaked in process::reap(&all_spawned) {
+        log::warn!(
+            "Killed process {} left running by a benchmarked server.",
+            leaked.pid
+        );
+    }
+
+    for uneven in Report::uneven_responses(&reports, args.response_size_tolerance) {
+        log::warn!("{}", uneven);
+        methodology.add_note(format!("**{}**", uneven));
+    }
+    if let Some(reason) = &bailed {
+        methodology.add_note(format!("**{}**", reason));
+    }
+    let generated_by = match &revision {
+        Some(revision) => format!(
+            "Generated by bench-bot at workspace commit `{}`.",
+            revision.short()
+        ),
+        None => "Generated by bench-bot.".to_string(),
+    };
+    let generated_by = match &args.label {
+        Some(label) => format!("{} Run label: `{}`.", generated_by, label),
+        None => generated_by,
+    };
+    let hardware_md = hardware.render();
+    let methodology_md = methodology.render();
+
+    let mut base_md = Markdown::new();
+
+    base_md.add_item(&generated_by);
+    base_md.add_item("# Hardware");
+    base_md.add_item(&hardware_md);
+    base_md.add_item("# Benchmark");
+    base_md.add_item("Command:");
+    base_md.add_item(format!("```\n{}\n```", bench_command));
+    base_md.add_item("## Methodology");
+    base_md.add_item(&methodology_md);
+
+    for (bench_type, result_md) in output_map {
+        let comparison_table = Report::generate_from(&reports, !args.raw_numbers);
+        let websocket = ws_map.get(bench_type).map_or(String::new(), |ws_stats| {
+            format!("## WebSocket\n\n{}", WsStats::generate_from(ws_stats))
+        });
+        let per_framework = if args.summary_only {
+            String::new()
+        } else {
+            result_md.finish()
+        };
+
+        if args.format.contains(&Format::Md) {
+            let output_path = args.output_dir.join(format!("{}.md", bench_type));
+            let output_md = template::render(
+                &template,
+                &[
+                    ("generated_by", &generated_by),
+                    ("hardware", &hardware_md),
+                    ("command", &bench_command),
+                    ("methodology", &methodology_md),
+                    ("comparison_table", &comparison_table),
+                    ("websocket", &websocket),
+                    ("per_framework", &per_framework),
+                ],
+            );
+
+            log::info!("Writing output to {:?}.", output_path);
+            fs::write(output_path, output_md).unwrap();
+        }
+
+        if args.format.contains(&Format::Txt) {
+            let txt_path = args.output_dir.join(format!("{}.txt", bench_type));
+
+            log::info!("Writing output to {:?}.", txt_path);
+            fs::write(
+                txt_path,
+                Report::generate_text(&reports, !args.raw_numbers) + "\n",
+            )
+            .unwrap();
+        }
+
+        if args.format.contains(&Format::Html) {
+            let html_path = args.output_dir.join(format!("{}.html", bench_type));
+            let output_html = html::page(
+                bench_type,
+                &generated_by,
+                &[
+                    (
+                        "Comparison",
+                        Report::generate_html(&reports, !args.raw_numbers),
+                    ),
+                    ("Command", html::preformatted(&bench_command)),
+                    ("Hardware", html::preformatted(&hardware_md)),
+                    ("Methodology", html::preformatted(&methodology_md)),
+                    (
+                        "WebSocket",
+                        ws_map.get(bench_type).map_or(String::new(), |ws_stats| {
+                            html::preformatted(&WsStats::generate_from(ws_stats))
+                        }),
+                    ),
+                    ("Frameworks", html::preformatted(&per_framework)),
+                ],
+            );
+
+            log::info!("Writing output to {:?}.", html_path);
+            fs::write(html_path, output_html).unwrap();
+        }
+
+        if args.format.contains(&Format::Json) {
+            let json_path = args.output_dir.join(format!("{}.json", bench_type));
+
+            log::info!("Writing output to {:?}.", json_path);
+            fs::write(
+                json_path,
+                json::generate(&metadata, &reports, args.json_pretty),
+            )
+            .unwrap();
+        }
+    }
+
+    if args.compare_languages {
+        let mut index_md = base_md.clone();
+
+        index_md.add_item("## Rust vs Go");
+        index_md.add_item(Report::generate_languages(&reports, !args.raw_numbers));
+
+        let index_path = args.output_dir.join("index.md");
+
+        log::info!("Writing output to {:?}.", index_path);
+        fs::write(index_path, index_md.finish()).unwrap();
+    }
+
+    if args.format.contains(&Format::Csv) {
+        let csv_path = args.output_dir.join("results.csv");
+
+        log::info!("Writing output to {:?}.", csv_path);
+        fs::write(csv_path, Report::generate_csv(&reports) + "\n").unwrap();
+    }
+
+    if let (Some(tag), Some(previous)) = (&args.compare_tag, &previous) {
+        let mut regression_md = base_md.clone();
+
+        regression_md.add_item(format!("## Compared with {}", tag));
+        regression_md.add_item(regression::render(
+            tag,
+            previous,
+            &regression::Row::of(&reports),
+        ));
+
+        let regression_path = args.output_dir.join("regression.md");
+
+        log::info!("Writing output to {:?}.", regression_path);
+        fs::write(regression_path, regression_md.finish()).unwrap();
+    }
+
+    if let Some(root) = &archive_root {
+        if let Err(e) = archive::update_latest(root, &args.output_dir) {
+            log::warn!(
+                "Could not point {:?} at this run: {}",
+                root.join("latest"),
+                e
+            );
+        }
+    }
+
+    if args.summary_json {
+        println!(
+            "{}",
+            json::to_string(&json::Summary::of(&reports, members_len), false)
+        );
+    }
+
+    if bailed.is_some() {
+        std::process::exit(1);
+    }
+}
+
+/// Whether the next member to run cools down first: the first one to let
+/// the builds' heat go unless `--skip-cooldown-first`, the others only
+/// after a member that applied load. Excluded or skipped members don't
+/// count, so no cooldown follows the last member that ran.
+#[derive(Debug)]
+struct Cooldown {
+    due: bool,
+}
+
+impl Cooldown {
+    fn new(first: bool) -> Self {
+        Self { due: first }
+    }
+
+    fn due(&self) -> bool {
+        self.due
+    }
+
+    /// After a member ran, `measured` when load was actually applied.
+    fn ran(&mut self, measured: bool) {
+        self.due = measured;
+    }
+}
+
+/// Cool down for `cd` seconds, or until the CPU is idle with
+/// `--cd-until-idle`, and how long it took.
+fn cool_down(args: &Args, cd: u64) -> Duration {
+    let cooldown_start = Instant::now();
+    if args.isolate {
+        drop_caches();
+    }
+    if args.cd_until_idle {
+        let idle = cpu::Idle::new(args.idle_threshold, Duration::from_secs(args.idle_window));
+        match cpu::wait_idle(idle, Duration::from_secs(args.idle_max)) {
+            Some(waited) => log::info!("CPU idle after {:.1}s.", waited.as_secs_f64()),
+            None => {
+                log::warn!(
+                    "CPU not idle after {}s, cooling down {}s more.",
+                    args.idle_max,
+                    cd
+                );
+                thread::sleep(Duration::from_secs(cd));
+            }
+        }
+    } else {
+        thread::sleep(Duration::from_secs(cd));
+    }
+
+    cooldown_start.elapsed()
+}
+
+/// Warn when a port of `member` isn't served by one of its `servers` or
+/// what they spawned: the server bound another port than `BENCH_PORT`,
+/// or a leftover server answers in its place.
+fn check_listeners(member: &Path, ports: &[u16], servers: &[Child]) {
+    let mut sys = System::new();
+    sys.refresh_processes();
+    let pids = servers
+        .iter()
+        .map(|server| Pid::from_u32(server.id()))
+        .flat_map(|pid| iter::once(pid).chain(process::descendants(&sys, pid)))
+        .map(|pid| pid.as_u32())
+        .collect::<Vec<_>>();
+
+    for port in ports {
+        match port::listener(*port) {
+            Some(port::Listener::Nobody) => log::warn!(
+                "Nothing listens on port {} after startup, {:?} may bind another port than BENCH_PORT.",
+                port,
+                member
+            ),
+            Some(port::Listener::Pids(listeners)) if !listeners.iter().any(|pid| pids.contains(pid)) => {
+                log::warn!(
+                    "Port {} is served by process(es) {:?}, not by {:?}. A leftover server may be \
+                     benchmarked instead.",
+                    port,
+                    listeners,
+                    member
+                )
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Flush dirty pages and drop the page cache, dentries and inodes. Only
+/// warns when it can't, e.g. without root.
+fn drop_caches() {
+    if cfg!(not(target_os = "linux")) {
+        return;
+    }
+
+    let _ = Command::new("sync").status();
+    if let Err(e) = fs::write("/proc/sys/vm/drop_caches", "3") {
+        log::warn!("Could not drop the page cache, --isolate needs root: {}", e);
+    }
+}
+
+/// Run a build step, either showing its output live with `stream` or
+/// capturing it. Errors with the captured stderr, empty when streamed.
+fn build(command: &mut Command, stream: bool) -> Result<(), String> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    if !stream {
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+
+    let (child, _tracked) = interrupt::spawn(command)
+        .unwrap_or_else(|e| panic!("Failed to execute {} build: {}", program, e));
+    let output = child
+        .wait_with_output()
+        .unwrap_or_else(|e| panic!("Failed to execute {} build: {}", program, e));
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).into_owned())
+    }
+}
+
+/// `build`, run a second time with `retry` when the first failed on a
+/// `TRANSIENT_BUILD_ERRORS`.
+fn build_retrying(command: &mut Command, stream: bool, retry: bool) -> Result<(), String> {
+    match build(command, stream) {
+        Err(stderr) if retry && is_transient_build_error(&stderr) => {
+            log::warn!(
+                "Build failed on a lock, retrying in {}s.",
+                BUILD_RETRY_DELAY.as_secs()
+            );
+            thread::sleep(BUILD_RETRY_DELAY);
+            build(command, stream)
+        }
+        result => result,
+    }
+}
+
+fn is_transient_build_error(stderr: &str) -> bool {
+    TRANSIENT_BUILD_ERRORS
+        .iter()
+        .any(|error| stderr.contains(error))
+        && !COMPILE_ERRORS.iter().any(|error| stderr.contains(error))
+}
+
+fn is_go(member: &Path) -> bool {
+    Language::of(member) == Language::Go
+}
+
+/// Size in bytes of the binary `cargo build --target` produced with
+/// `profile` in `target_dir` for the member in `member_dir`, named after its
+/// package.
+fn target_binary_size(
+    target_dir: &Path,
+    member_dir: &Path,
+    target: &str,
+    profile: Option<&str>,
+) -> Option<u64> {
+    let manifest = fs::read_to_string(member_dir.join("Cargo.toml")).ok()?;
+    let name = cargo_package(&manifest)?;
+
+    let binary = target_dir
+        .join(target)
+        .join(profile_dir(profile))
+        .join(name);
+
+    fs::metadata(binary).ok().map(|metadata| metadata.len())
+}
+
+/// With more than one instance, start a balancer on `port` and pick free
+/// ports after it for the instances. Otherwise, the only instance listens on
+/// `port` itself.
+fn spawn_balancer(
+    url: &str,
+    port: u16,
+    instances: usize,
+) -> std::io::Result<(Option<Balancer>, Vec<u16>)> {
+    let host = port::host_port(url).map_or("127.0.0.1", |(host, _)| host);
+    if instances <= 1 {
+        return Ok((None, vec![port]));
+    }
+
+    let mut ports = Vec::with_capacity(instances);
+    let mut next = port;
+    for _ in 0..instances {
+        next = port::find_free(host, next.saturating_add(1))
+            .ok_or_else(|| std::io::Error::other("no free port for an instance"))?;
+        ports.push(next);
+    }
+
+    let backends = ports
+        .iter()
+        .map(|port| {
+            (host, *port)
+                .to_socket_addrs()
+                .map(|mut addrs| addrs.next())
+        })
+        .collect::<std::io::Result<Option<Vec<_>>>>()?
+        .ok_or_else(|| std::io::Error::other(format!("{} does not resolve", host)))?;
+
+    Ok((Some(Balancer::start(host, port, backends)?), ports))
+}
+
+/// Longest a run of `members` members can take once built: startup, every
+/// run (or ramp step and WebSocket phase) and the cooldowns in between.
+fn time_budget(args: &Args, members: usize) -> Duration {
+    let secs = |secs: usize| Duration::from_secs(secs as u64);
+
+    let load = if args.ramp {
+        let mut steps = 1;
+        let mut connections = args.ramp_start.max(1);
+        while connections * 2 <= args.connections {
+            connections *= 2;
+            steps += 1;
+        }
+        secs(steps * args.ramp_step)
+    } else if args.until_stable {
+        secs((args.max_runs.max(1) + args.discard_first_run as usize) * args.duration)
+    } else {
+        secs((args.runs.max(1) + args.discard_first_run as usize) * args.duration)
+    };
+    let websocket = if args.websocket.is_some() {
+        secs(args.duration)
+    } else {
+        Duration::ZERO
+    };
+    let matrix = if args.matrix {
+        let (connections, durations) = matrix_axes(args);
+        secs(connections.len() * durations.iter().sum::<usize>())
+    } else {
+        Duration::ZERO
+    };
+
+    let warmup = if args.warmup_requests.is_some_and(|requests| requests > 0) {
+        bench::WARMUP_BUDGET
+    } else {
+        Duration::ZERO
+    };
+
+    let member = STARTUP_WAIT + warmup + load + websocket + matrix;
+    let cooldowns = cooldown_budget(args) * members.saturating_sub(1) as u32;
+
+    member * members as u32 + cooldowns
+}
+
+/// Requests/sec of the load generator against a null server, `None` when
+/// the null server or the run failed.
+fn calibrate_generator(bench: &Bench, connections: usize) -> Option<f64> {
+    let server = calibrate::NullServer::start()
+        .map_err(|e| log::warn!("Could not start the null server of --calibrate: {}", e))
+        .ok()?;
+    log::info!("Calibrating the load generator against a null server.");
+
+    let output = bench.run(&[server.url()], connections, CALIBRATION_SECS);
+    if let Some(failure) = bench.failure(&output) {
+        log::warn!(
+            "Calibrating the load generator failed: {}",
+            failure.trim_end()
+        );
+        return None;
+    }
+    let ceiling = bench.parse(&output).ok()?.req_per_sec()?;
+    log::info!("The load generator reaches {:.0} requests/sec.", ceiling);
+
+    Some(ceiling)
+}
+
+/// Longest cooldown between two members.
+fn cooldown_budget(args: &Args) -> Duration {
+    let cooldown = if args.cd_until_idle {
+        args.idle_max + args.cd
+    } else {
+        args.cd
+    };
+    Duration::from_secs(cooldown)
+}
+
+/// `time_budget` of every category of `members` with its `--config`
+/// overrides, the cooldowns between categories and the one before the
+/// first member.
+fn category_budget(args: &Args, config: &Config, members: &[PathBuf]) -> Duration {
+    let mut counts = HashMap::<&str, usize>::new();
+    for member in members {
+        *counts.entry(bench_type(member)).or_default() += 1;
+    }
+
+    let categories = counts
+        .iter()
+        .map(|(category, count)| match config.category.get(*category) {
+            Some(overrides) => time_budget(&overrides.apply(args), *count),
+            None => time_budget(args, *count),
+        })
+        .sum::<Duration>();
+
+    let calibration = if args.calibrate {
+        Duration::from_secs(CALIBRATION_SECS as u64)
+    } else {
+        Duration::ZERO
+    };
+
+    let first = if args.skip_cooldown_first || members.is_empty() {
+        0
+    } else {
+        1
+    };
+
+    calibration
+        + categories
+        + cooldown_budget(args) * (counts.len().saturating_sub(1) + first) as u32
+}
+
+/// Benchmark category of `member`, the directory it is in.
+fn bench_type(member: &Path) -> &str {
+    member
+        .parent()
+        .and_then(Path::file_name)
+        .and_then(OsStr::to_str)
+        .unwrap_or_default()
+}
+
+/// Connection counts and durations of `--matrix`, defaulting to `-c` and
+/// `-d`.
+fn matrix_axes(args: &Args) -> (Vec<usize>, Vec<usize>) {
+    let or = |values: &[usize], default: usize| {
+        if values.is_empty() {
+            vec![default]
+        } else {
+            values.to_vec()
+        }
+    };
+
+    (
+        or(&args.matrix_connections, args.connections),
+        or(&args.matrix_durations, args.duration),
+    )
+}
+
+/// Every member with each of `profiles`, `None` being `--release`. Go
+/// members have no profiles and are built once.
+fn variants(members: &[PathBuf], profiles: &[String]) -> Vec<(PathBuf, Option<String>)> {
+    members
+        .iter()
+        .flat_map(|member| {
+            let profiles = if profiles.is_empty() || is_go(member) {
+                vec![None]
+            } else {
+                profiles.iter().cloned().map(Some).collect()
+            };
+            profiles
+                .into_iter()
+                .map(move |profile| (member.clone(), profile))
+        })
+        .collect()
+}
+
+/// Arguments selecting `profile` for `cargo build` and `cargo run`.
+fn profile_args(profile: Option<&str>) -> Vec<&str> {
+    match profile {
+        None => vec!["--release"],
+        Some(profile) => vec!["--profile", profile],
+    }
+}
+
+/// Directory of the target directory cargo puts the binaries of `profile`
+/// in, the `dev` and `test` profiles sharing `debug`.
+fn profile_dir(profile: Option<&str>) -> &str {
+    match profile {
+        None | Some("bench") => "release",
+        Some("dev" | "test") => "debug",
+        Some(profile) => profile,
+    }
+}
+
+/// Target directory of the workspace's builds without `--shared-target`.
+fn default_target_dir(workspace_dir: &Path) -> PathBuf {
+    env::var_os("CARGO_TARGET_DIR").map_or_else(|| workspace_dir.join("target"), PathBuf::from)
+}
+
+/// `CARGO_TARGET_DIR` of a Rust member with `--shared-target`.
+fn target_env<'a>(
+    member: &Path,
+    shared_target: Option<&'a Path>,
+) -> Option<(&'static str, &'a Path)> {
+    shared_target
+        .filter(|_| !is_go(member))
+        .map(|dir| ("CARGO_TARGET_DIR", dir))
+}
+
+/// Extra environment of the build and run commands of `member`.
+fn build_env(member: &Path, fair_build: bool) -> &'static [(&'static str, &'static str)] {
+    match (fair_build, is_go(member)) {
+        (false, _) => &[],
+        (true, true) => &FAIR_GO_ENV,
+        (true, false) => &FAIR_RUST_ENV,
+    }
+}
+
+/// Returns `urls` with their port replaced by the first free one from the
+/// first url's, they all point at the same server, along with that port. A
+/// busy port usually means a leftover server from a previous run, which
+/// would otherwise make every member fail to bind.
+fn free_urls(urls: &[String]) -> Option<(Vec<String>, u16)> {
+    let (host, port) = port::host_port(urls.first()?)?;
+    let free = port::find_free(host, port)?;
+
+    if free != port {
+        log::warn!("Port {} is busy, using port {} instead.", port, free);
+    }
+
+    let urls = urls.iter().map(|url| port::with_port(url, free)).collect();
+    Some((urls, free))
+}
+
+/// `[package] name` of a `Cargo.toml`.
+fn cargo_package(manifest: &str) -> Option<String> {
+    let manifest = toml::from_str::<toml::Value>(manifest).ok()?;
+    Some(manifest.get("package")?.get("name")?.as_str()?.to_string())
+}
+
+/// `module` path of a `go.mod`.
+fn go_module(go_mod: &str) -> Option<String> {
+    go_mod
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("module "))
+        .map(|module| module.trim().trim_matches('"').to_string())
+}
+
+/// How `member` calls itself: its Go module or its Cargo package.
+fn package_name(member: &Path, member_dir: &Path) -> Option<String> {
+    if is_go(member) {
+        go_module(&fs::read_to_string(member_dir.join("go.mod")).ok()?)
+    } else {
+        cargo_package(&fs::read_to_string(member_dir.join("Cargo.toml")).ok()?)
+    }
+}
+
+/// Package name of every member when it has a unique one, see
+/// `framework_names` for the others.
+fn report_names(
+    members: &[PathBuf],
+    packages: &HashMap<PathBuf, String>,
+) -> HashMap<PathBuf, String> {
+    let mut names = framework_names(members);
+
+    for (member, name) in names.iter_mut() {
+        let Some(package) = packages.get(member) else {
+            continue;
+        };
+        if packages.values().filter(|other| *other == package).count() == 1 {
+            *name = package.clone();
+        }
+    }
+
+    names
+}
+
+/// Name of every member in the report: its directory name, prefixed with as
+/// many parent directories as needed to tell apart members sharing it, e.g.
+/// `hello-world/axum` and `json/axum`.
+fn framework_names(members: &[PathBuf]) -> HashMap<PathBuf, String> {
+    let suffix = |member: &Path, depth: usize| {
+        let components = member.components().collect::<Vec<_>>();
+        let start = components.len().saturating_sub(depth);
+        components[start..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    };
+
+    members
+        .iter()
+        .map(|member| {
+            let depth = (1..=member.components().count())
+                .find(|&depth| {
+                    let name = suffix(member, depth);
+                    members
+                        .iter()
+                        .filter(|other| *other != member)
+                        .all(|other| suffix(other, depth) != name)
+                })
+                .unwrap_or(member.components().count());
+
+            if depth > 1 {
+                log::warn!(
+                    "{:?} shares its directory name with another member, reported as {:?}.",
+                    member,
+                    suffix(member, depth)
+                );
+            }
+
+            (member.clone(), suffix(member, depth))
+        })
+        .collect()
+}
+
+/// Members of `input`, one path per line relative to `ws_dir` or pointing
+/// into it, in the given order without duplicates. Errors on a path
+/// outside of the workspace.
+fn stdin_members(input: &str, ws_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let ws_dir =
+        fs::canonicalize(ws_dir).map_err(|e| format!("could not resolve {:?}: {}", ws_dir, e))?;
+
+    let mut members = Vec::new();
+    for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        // relative to the workspace first, then to the current directory.
+        let path = fs::canonicalize(ws_dir.join(line))
+            .or_else(|_| fs::canonicalize(line))
+            .map_err(|_| format!("{:?} is not a directory", line))?;
+        let member = path
+            .strip_prefix(&ws_dir)
+            .ok()
+            .filter(|member| member.components().next().is_some() && path.is_dir())
+            .ok_or_else(|| format!("{:?} is not a directory inside of {:?}", line, ws_dir))?
+            .to_path_buf();
+
+        if !members.contains(&member) {
+            members.push(member);
+        }
     }
+
+    Ok(members)
 }
 
 fn expand_members(members: Vec<PathBuf>, ws_dir: &Path) -> Vec<PathBuf> {
     let mut new_members = Vec::new();
     for member in members {
-        if member.components().last() == Some(Component::Normal(&OsStr::new("*"))) {
+        if member.components().next_back() == Some(Component::Normal(OsStr::new("*"))) {
             let parent_dir = member.parent().unwrap();
             for entry in fs::read_dir(ws_dir.join(parent_dir)).unwrap() {
                 let entry = entry.unwrap();
@@ -283,3 +3322,388 @@ fn expand_members(members: Vec<PathBuf>, ws_dir: &Path) -> Vec<PathBuf> {
     new_members.sort();
     new_members
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        ffi::OsString,
+        sync::{PoisonError, RwLock},
+    };
+
+    /// Write-locked by the tests setting `BENCH_*` variables, which every
+    /// parse of `Args` reads.
+    static ENV: RwLock<()> = RwLock::new(());
+
+    /// `Args::try_parse_from`, never while a test sets `BENCH_*` variables.
+    pub(crate) fn try_parse<I, T>(args: I) -> Result<Args, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let _env = ENV.read().unwrap_or_else(PoisonError::into_inner);
+        Args::try_parse_from(args)
+    }
+
+    mod is_transient_build_error {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = "    Blocking waiting for file lock on package cache\n\
+                         error: failed to acquire package cache lock";
+
+            assert!(is_transient_build_error(given));
+            assert!(!is_transient_build_error(
+                "error[E0425]: cannot find value `x` in this scope"
+            ));
+        }
+
+        #[test]
+        fn compile_error_after_lock() {
+            let given = "    Blocking waiting for file lock on build directory\n\
+                         error[E0425]: cannot find value `x` in this scope\n\
+                         error: could not compile `axum` due to previous error";
+
+            assert!(!is_transient_build_error(given));
+        }
+    }
+
+    mod stdin_members {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let ws_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+            let given = format!("src\n\n./src/\n{}\n", ws_dir.join("src").display());
+
+            assert_eq!(
+                stdin_members(&given, ws_dir),
+                Ok(vec![PathBuf::from("src")])
+            );
+        }
+
+        #[test]
+        fn outside() {
+            let ws_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+
+            assert!(stdin_members("..\n", &ws_dir).is_err());
+            assert!(stdin_members("main.rs\n", &ws_dir).is_err());
+            assert!(stdin_members("does-not-exist\n", &ws_dir).is_err());
+        }
+    }
+
+    mod framework_names {
+        use super::*;
+
+        #[test]
+        fn unique() {
+            let given = [
+                PathBuf::from("hello-world/axum"),
+                PathBuf::from("hello-world/warp"),
+            ];
+            let actual = framework_names(&given);
+
+            assert_eq!(actual[&given[0]], "axum");
+            assert_eq!(actual[&given[1]], "warp");
+        }
+
+        #[test]
+        fn collision() {
+            let given = [
+                PathBuf::from("hello-world/axum"),
+                PathBuf::from("json/axum"),
+                PathBuf::from("json/warp"),
+            ];
+            let actual = framework_names(&given);
+
+            assert_eq!(actual[&given[0]], "hello-world/axum");
+            assert_eq!(actual[&given[1]], "json/axum");
+            assert_eq!(actual[&given[2]], "warp");
+        }
+    }
+
+    mod variants {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = [
+                PathBuf::from("hello-world/axum"),
+                PathBuf::from("hello-world/go_gin"),
+            ];
+            let profiles = ["release".to_string(), "release-lto".to_string()];
+
+            let actual = variants(&given, &profiles);
+
+            let expect = [
+                (given[0].clone(), Some("release".to_string())),
+                (given[0].clone(), Some("release-lto".to_string())),
+                (given[1].clone(), None),
+            ];
+            assert_eq!(actual, expect);
+            assert_eq!(variants(&given[..1], &[]), [(given[0].clone(), None)]);
+        }
+
+        #[test]
+        fn profile() {
+            assert_eq!(profile_args(None), ["--release"]);
+            assert_eq!(
+                profile_args(Some("release-lto")),
+                ["--profile", "release-lto"]
+            );
+            assert_eq!(profile_dir(None), "release");
+            assert_eq!(profile_dir(Some("dev")), "debug");
+            assert_eq!(profile_dir(Some("release-lto")), "release-lto");
+        }
+    }
+
+    mod target_env {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let shared = Path::new("/tmp/bench-target");
+
+            assert_eq!(
+                target_env(Path::new("hello-world/axum"), Some(shared)),
+                Some(("CARGO_TARGET_DIR", shared))
+            );
+            assert_eq!(
+                target_env(Path::new("hello-world/go_gin"), Some(shared)),
+                None
+            );
+            assert_eq!(target_env(Path::new("hello-world/axum"), None), None);
+        }
+    }
+
+    mod report_names {
+        use super::*;
+
+        #[test]
+        fn package() {
+            let given = [
+                PathBuf::from("hello-world/axum"),
+                PathBuf::from("hello-world/warp"),
+                PathBuf::from("hello-world/go_a"),
+                PathBuf::from("hello-world/go_b"),
+            ];
+            let packages = HashMap::from([
+                (given[0].clone(), "hello-world-axum".to_string()),
+                (given[2].clone(), "bench".to_string()),
+                (given[3].clone(), "bench".to_string()),
+            ]);
+            let actual = report_names(&given, &packages);
+
+            assert_eq!(actual[&given[0]], "hello-world-axum");
+            // no package name, or a shared one
+            assert_eq!(actual[&given[1]], "warp");
+            assert_eq!(actual[&given[2]], "go_a");
+            assert_eq!(actual[&given[3]], "go_b");
+        }
+
+        #[test]
+        fn manifests() {
+            let cargo = "[package]\nname = \"hello-world-axum\"\nversion = \"0.1.0\"\n";
+            let go = "module github.com/example/benchfasthttp\n\ngo 1.23\n";
+
+            assert_eq!(cargo_package(cargo).as_deref(), Some("hello-world-axum"));
+            assert_eq!(
+                go_module(go).as_deref(),
+                Some("github.com/example/benchfasthttp")
+            );
+            assert_eq!(cargo_package("[workspace]\n"), None);
+        }
+    }
+
+    mod cooldown {
+        use super::*;
+
+        // members in order, `None` when excluded, otherwise whether it was
+        // measured, into whether each one cooled down first.
+        fn schedule(skip_first: bool, members: &[Option<bool>]) -> Vec<bool> {
+            let mut cooldown = Cooldown::new(!skip_first);
+            members
+                .iter()
+                .map(|member| match member {
+                    Some(measured) => {
+                        let due = cooldown.due();
+                        cooldown.ran(*measured);
+                        due
+                    }
+                    None => false,
+                })
+                .collect()
+        }
+
+        #[test]
+        fn ok() {
+            assert_eq!(
+                schedule(false, &[Some(true), Some(true), Some(true)]),
+                [true, true, true]
+            );
+            assert_eq!(
+                schedule(true, &[Some(true), Some(true), Some(true)]),
+                [false, true, true]
+            );
+        }
+
+        #[test]
+        fn excluded() {
+            // the first member to run starts right away, even after an
+            // excluded one.
+            assert_eq!(
+                schedule(true, &[None, Some(true), Some(true)]),
+                [false, false, true]
+            );
+            // nor does an excluded member in between skip one.
+            assert_eq!(
+                schedule(true, &[Some(true), None, Some(true), None]),
+                [false, false, true, false]
+            );
+        }
+
+        #[test]
+        fn unmeasured() {
+            // nothing to cool down from after a member that applied no load.
+            assert_eq!(
+                schedule(true, &[Some(true), Some(false), Some(true)]),
+                [false, true, false]
+            );
+        }
+    }
+
+    mod args {
+        use super::*;
+
+        const REQUIRED: [&str; 5] = ["bench-bot", "-w", "../benchmark", "-o", "../result"];
+
+        #[test]
+        fn time_budget() {
+            let given = ["-c", "500", "-d", "10", "--cd", "5", "--runs", "3"];
+            let args = try_parse(REQUIRED.iter().chain(&given)).unwrap();
+
+            // 3 x (1s startup + 3 x 10s) + 2 x 5s cooldown
+            assert_eq!(super::time_budget(&args, 3), Duration::from_secs(103));
+
+            let given = [
+                "-c",
+                "100",
+                "--ramp",
+                "--ramp-start",
+                "8",
+                "--ramp-step",
+                "5",
+            ];
+            let args = try_parse(REQUIRED.iter().chain(&given)).unwrap();
+
+            // 8, 16, 32, 64 connections
+            assert_eq!(super::time_budget(&args, 1), Duration::from_secs(21));
+
+            let given = [
+                "-d",
+                "10",
+                "--matrix",
+                "--matrix-connections",
+                "50,100",
+                "--matrix-durations",
+                "5,15",
+            ];
+            let args = try_parse(REQUIRED.iter().chain(&given)).unwrap();
+
+            // 1s startup + 10s + 2 x (5s + 15s)
+            assert_eq!(super::time_budget(&args, 1), Duration::from_secs(51));
+
+            let given = [
+                "-d",
+                "10",
+                "--cd",
+                "5",
+                "--cd-until-idle",
+                "--idle-max",
+                "30",
+            ];
+            let args = try_parse(REQUIRED.iter().chain(&given)).unwrap();
+
+            // 2 x (1s startup + 10s) + 30s idle wait + 5s cooldown
+            assert_eq!(super::time_budget(&args, 2), Duration::from_secs(57));
+
+            let given = ["-d", "10", "--runs", "2", "--discard-first-run"];
+            let args = try_parse(REQUIRED.iter().chain(&given)).unwrap();
+
+            // 1s startup + (1 discarded + 2) x 10s
+            assert_eq!(super::time_budget(&args, 1), Duration::from_secs(31));
+
+            let given = ["-d", "10", "--warmup-requests", "1000"];
+            let args = try_parse(REQUIRED.iter().chain(&given)).unwrap();
+
+            // 1s startup + at most 61s warmup + 10s
+            assert_eq!(super::time_budget(&args, 1), Duration::from_secs(72));
+        }
+
+        #[test]
+        fn json_pretty() {
+            assert!(try_parse(REQUIRED).unwrap().json_pretty);
+
+            let args = try_parse(REQUIRED.iter().chain(&["--json-pretty", "false"])).unwrap();
+            assert!(!args.json_pretty);
+        }
+
+        #[test]
+        fn client_cert() {
+            let given = ["--client-cert", "client.pem"];
+            assert!(try_parse(REQUIRED.iter().chain(&given)).is_err());
+
+            let given = ["--client-cert", "client.pem", "--client-key", "client.key"];
+            let args = try_parse(REQUIRED.iter().chain(&given)).unwrap();
+
+            assert_eq!(args.client_key.as_deref(), Some(Path::new("client.key")));
+        }
+
+        #[test]
+        fn tool_arg() {
+            let given = [
+                "--tool-arg",
+                "--pct",
+                "--tool-arg=-H",
+                "--tool-arg",
+                "X-Trace: 1",
+            ];
+
+            let args = try_parse(REQUIRED.iter().chain(&given)).unwrap();
+
+            assert_eq!(args.tool_arg, ["--pct", "-H", "X-Trace: 1"]);
+        }
+
+        #[test]
+        fn env_precedence() {
+            let _env = ENV.write().unwrap_or_else(PoisonError::into_inner);
+            std::env::remove_var("BENCH_CONNECTIONS");
+            let args = Args::try_parse_from(REQUIRED).unwrap();
+            assert_eq!(args.connections, 500);
+
+            std::env::set_var("BENCH_CONNECTIONS", "100");
+            let args = Args::try_parse_from(REQUIRED).unwrap();
+            assert_eq!(args.connections, 100);
+
+            let args = Args::try_parse_from(REQUIRED.iter().chain(&["-c", "50"])).unwrap();
+            assert_eq!(args.connections, 50);
+
+            std::env::remove_var("BENCH_CONNECTIONS");
+        }
+
+        #[test]
+        fn env_required() {
+            let _env = ENV.write().unwrap_or_else(PoisonError::into_inner);
+            std::env::set_var("BENCH_WORKSPACE_DIR", "../benchmark");
+            std::env::set_var("BENCH_OUTPUT_DIR", "../result");
+            let args = Args::try_parse_from(["bench-bot"]).unwrap();
+
+            assert_eq!(args.workspace_dir, PathBuf::from("../benchmark"));
+            assert_eq!(args.output_dir, PathBuf::from("../result"));
+
+            std::env::remove_var("BENCH_WORKSPACE_DIR");
+            std::env::remove_var("BENCH_OUTPUT_DIR");
+        }
+    }
+}
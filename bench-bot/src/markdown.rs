@@ -1,3 +1,5 @@
+/// Markdown document built block by block, blocks are separated by a blank
+/// line.
 #[derive(Debug, Default, Clone)]
 pub struct Markdown {
     string: String,
@@ -8,15 +10,127 @@ impl Markdown {
         Self::default()
     }
 
+    /// A block as is, e.g. a heading or a paragraph.
     pub fn add_item(&mut self, s: impl AsRef<str>) {
         self.string.push('\n');
         self.string.push('\n');
         self.string.push_str(s.as_ref());
     }
 
+    /// A block GitHub renders collapsed, showing only `summary`. The blank
+    /// lines let `body` be markdown itself.
+    pub fn add_collapsible(&mut self, summary: impl AsRef<str>, body: impl AsRef<str>) {
+        self.add_item(format!(
+            "<details><summary>{}</summary>\n\n{}\n\n</details>",
+            summary.as_ref(),
+            body.as_ref()
+        ));
+    }
+
+    /// A table of `header` and `rows`, laid out like the report tables.
+    pub fn add_table<S: AsRef<str>>(&mut self, header: &[S], rows: &[Vec<S>]) {
+        let join = |cells: &[S], separator: &str| {
+            cells
+                .iter()
+                .map(AsRef::as_ref)
+                .collect::<Vec<_>>()
+                .join(separator)
+        };
+
+        let mut table = format!(
+            "| {} |\n|{}",
+            join(header, " | "),
+            "---|".repeat(header.len())
+        );
+        for row in rows {
+            table.push_str(&format!("\n|{}|", join(row, "|")));
+        }
+
+        self.add_item(table);
+    }
+
+    /// A paragraph linking to `url`.
+    pub fn add_link(&mut self, text: impl AsRef<str>, url: impl AsRef<str>) {
+        self.add_item(link(text.as_ref(), url.as_ref()));
+    }
+
+    /// The document, with a single trailing newline.
     pub fn finish(self) -> String {
         let mut string = self.string.trim().to_owned();
         string.push('\n');
         string
     }
 }
+
+/// Inline link, for use within other blocks.
+pub fn link(text: &str, url: &str) -> String {
+    format!("[{}]({})", text, url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod markdown {
+        use super::*;
+
+        #[test]
+        fn add_item() {
+            let mut md = Markdown::new();
+            md.add_item("# Title");
+            md.add_item("Some text.");
+
+            assert_eq!(md.finish(), "# Title\n\nSome text.\n");
+        }
+
+        #[test]
+        fn add_collapsible() {
+            let mut md = Markdown::new();
+            md.add_collapsible("Headers", "- a\n- b");
+
+            let expect = "<details><summary>Headers</summary>\n\n- a\n- b\n\n</details>\n";
+
+            assert_eq!(md.finish(), expect);
+        }
+
+        #[test]
+        fn add_table() {
+            let mut md = Markdown::new();
+            md.add_table(
+                &["Framework", "Req/Sec"],
+                &[vec!["axum", "100.00"], vec!["warp", "90.00"]],
+            );
+
+            let expect = r#"
+| Framework | Req/Sec |
+|---|---|
+|axum|100.00|
+|warp|90.00|
+"#
+            .trim_start();
+
+            assert_eq!(md.finish(), expect);
+        }
+
+        #[test]
+        fn add_link() {
+            let mut md = Markdown::new();
+            md.add_link("rewrk", "https://github.com/lnx-search/rewrk");
+
+            assert_eq!(
+                md.finish(),
+                "[rewrk](https://github.com/lnx-search/rewrk)\n"
+            );
+        }
+
+        #[test]
+        fn finish() {
+            assert_eq!(Markdown::new().finish(), "\n");
+
+            let mut md = Markdown::new();
+            md.add_item("  text  \n\n");
+
+            assert_eq!(md.finish(), "text\n");
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use serde::Serialize;
+
+/// Benchmarks at every combination of connection count and duration, which
+/// shows whether a framework holds up under longer or heavier load.
+#[derive(Debug, Clone, Serialize)]
+pub struct Matrix {
+    connections: Vec<usize>,
+    durations: Vec<usize>,
+    /// Requests/sec by connections, then duration. `None` for a failed run.
+    req_per_sec: Vec<Vec<Option<f64>>>,
+    /// Peak memory of the servers in MB, by connections then duration.
+    /// `None` when not sampled.
+    memory_mb: Vec<Vec<Option<f64>>>,
+}
+
+impl Matrix {
+    /// Run `measure(connections, duration)` at every point, connections in
+    /// the outer loop, into requests/sec and peak memory in MB.
+    pub fn sweep(
+        connections: &[usize],
+        durations: &[usize],
+        mut measure: impl FnMut(usize, usize) -> (Option<f64>, Option<f64>),
+    ) -> Self {
+        let (req_per_sec, memory_mb) = connections
+            .iter()
+            .map(|&connections| {
+                durations
+                    .iter()
+                    .map(|&duration| measure(connections, duration))
+                    .unzip()
+            })
+            .unzip();
+
+        Self {
+            connections: connections.to_vec(),
+            durations: durations.to_vec(),
+            req_per_sec,
+            memory_mb,
+        }
+    }
+
+    /// Connection count of the best requests/sec at any duration, `None`
+    /// without at least two connection counts to choose from.
+    pub fn optimal_connections(&self) -> Option<usize> {
+        if self.connections.len() < 2 {
+            return None;
+        }
+
+        self.connections
+            .iter()
+            .zip(&self.req_per_sec)
+            .flat_map(|(connections, row)| {
+                row.iter().flatten().map(move |rps| (*connections, *rps))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(connections, _)| connections)
+    }
+
+    /// Connections, duration, requests/sec and peak memory in MB of every
+    /// cell, connections in the outer loop.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize, Option<f64>, Option<f64>)> + '_ {
+        self.connections
+            .iter()
+            .zip(self.req_per_sec.iter().zip(&self.memory_mb))
+            .flat_map(move |(&connections, (row, memory))| {
+                self.durations.iter().zip(row.iter().zip(memory)).map(
+                    move |(&duration, (&req_per_sec, &memory_mb))| {
+                        (connections, duration, req_per_sec, memory_mb)
+                    },
+                )
+            })
+    }
+
+    /// Markdown grid of requests/sec, a row per connection count and a
+    /// column per duration, each with its peak memory when it was sampled.
+    pub fn render(&self) -> String {
+        let mut res = String::from("| Connections |");
+        for duration in &self.durations {
+            res.push_str(&format!(" {}s |", duration));
+        }
+        res.push_str("\n|---|");
+        res.push_str(&"---|".repeat(self.durations.len()));
+
+        let rows = self
+            .connections
+            .iter()
+            .zip(self.req_per_sec.iter().zip(&self.memory_mb));
+        for (connections, (row, memory)) in rows {
+            res.push_str(&format!("\n|{}|", connections));
+            for (req_per_sec, memory_mb) in row.iter().zip(memory) {
+                match (req_per_sec, memory_mb) {
+                    (Some(req_per_sec), Some(mb)) => {
+                        res.push_str(&format!("{:.2} ({:.1}MB)|", req_per_sec, mb))
+                    }
+                    (Some(req_per_sec), None) => res.push_str(&format!("{:.2}|", req_per_sec)),
+                    (None, _) => res.push_str("failed|"),
+                }
+            }
+        }
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod matrix {
+        use super::*;
+
+        #[test]
+        fn sweep() {
+            let mut order = Vec::new();
+            let given = Matrix::sweep(&[100, 500], &[10, 30], |connections, duration| {
+                order.push((connections, duration));
+                let req_per_sec = (connections != 500 || duration != 30)
+                    .then_some((connections * duration) as f64);
+                (req_per_sec, None)
+            });
+
+            assert_eq!(order, [(100, 10), (100, 30), (500, 10), (500, 30)]);
+
+            let expect = r#"
+| Connections | 10s | 30s |
+|---|---|---|
+|100|1000.00|3000.00|
+|500|5000.00|failed|
+"#
+            .trim();
+
+            assert_eq!(given.render(), expect);
+            assert_eq!(given.optimal_connections(), Some(500));
+            assert_eq!(
+                given.cells().collect::<Vec<_>>(),
+                [
+                    (100, 10, Some(1000.0), None),
+                    (100, 30, Some(3000.0), None),
+                    (500, 10, Some(5000.0), None),
+                    (500, 30, None, None),
+                ]
+            );
+        }
+
+        #[test]
+        fn memory() {
+            let given = Matrix::sweep(&[100, 500], &[10], |connections, _| match connections {
+                500 => (Some(5000.0), Some(12.34)),
+                _ => (Some(1000.0), None),
+            });
+
+            let expect = r#"
+| Connections | 10s |
+|---|---|
+|100|1000.00|
+|500|5000.00 (12.3MB)|
+"#
+            .trim();
+
+            assert_eq!(given.render(), expect);
+        }
+
+        #[test]
+        fn optimal_connections() {
+            let given = Matrix::sweep(
+                &[100, 500, 1000],
+                &[10],
+                |connections, _| match connections {
+                    500 => (Some(9000.0), None),
+                    1000 => (None, None),
+                    _ => (Some(8000.0), None),
+                },
+            );
+
+            assert_eq!(given.optimal_connections(), Some(500));
+            assert_eq!(
+                Matrix::sweep(&[100], &[10, 30], |_, _| (Some(1.0), None)).optimal_connections(),
+                None
+            );
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::stats;
+use std::time::Duration;
+
+/// Which statistic of the memory samples a report headlines. Percentiles
+/// discard the odd spike, e.g. allocation churn at startup.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum MemStat {
+    Peak,
+    P99,
+    P95,
+}
+
+impl MemStat {
+    /// Header of the memory column it headlines.
+    pub fn header(self) -> &'static str {
+        match self {
+            MemStat::Peak => "Max. Memory Usage",
+            MemStat::P99 => "Memory Usage (p99)",
+            MemStat::P95 => "Memory Usage (p95)",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MemStat::Peak => "peak",
+            MemStat::P99 => "p99",
+            MemStat::P95 => "p95",
+        }
+    }
+
+    /// The statistic over `samples`, nearest-rank for percentiles.
+    pub fn of(self, samples: &[u64]) -> Option<u64> {
+        let p = match self {
+            MemStat::Peak => return samples.iter().max().copied(),
+            MemStat::P99 => 0.99,
+            MemStat::P95 => 0.95,
+        };
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        stats::percentile(&sorted, p)
+    }
+}
+
+/// The first of `samples` taken at or after each of `marks` seconds,
+/// `elapsed` being when every sample was taken.
+pub fn at(elapsed: &[Duration], samples: &[u64], marks: &[u64]) -> Vec<Option<u64>> {
+    marks
+        .iter()
+        .map(|mark| {
+            let mark = Duration::from_secs(*mark);
+            elapsed
+                .iter()
+                .zip(samples)
+                .find(|(elapsed, _)| **elapsed >= mark)
+                .map(|(_, sample)| *sample)
+        })
+        .collect()
+}
+
+/// Whether `memory_kb`, as sampled, is above the `--mem-abort` ceiling in
+/// MB.
+pub fn exceeds(memory_kb: u64, ceiling_mb: u64) -> bool {
+    memory_kb > ceiling_mb.saturating_mul(1024)
+}
+
+/// `5s / 15s: 12.1 / - MB`, memory in MB by second.
+pub fn render_at(mem_at: &[(u64, Option<f64>)]) -> String {
+    let secs = mem_at
+        .iter()
+        .map(|(secs, _)| format!("{}s", secs))
+        .collect::<Vec<_>>();
+    let memory = mem_at
+        .iter()
+        .map(|(_, mb)| mb.map_or("-".to_string(), |mb| format!("{:.1}", mb)))
+        .collect::<Vec<_>>();
+
+    format!("{}: {} MB", secs.join(" / "), memory.join(" / "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod at {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let elapsed = [1, 5, 9, 16].map(Duration::from_secs);
+            let samples = [100, 200, 300, 400];
+
+            assert_eq!(
+                at(&elapsed, &samples, &[5, 15, 25]),
+                [Some(200), Some(400), None]
+            );
+        }
+
+        #[test]
+        fn render() {
+            let given = [(5, Some(12.06)), (15, None)];
+
+            assert_eq!(render_at(&given), "5s / 15s: 12.1 / - MB");
+        }
+    }
+
+    mod exceeds {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            assert!(exceeds(512 * 1024 + 1, 512));
+            assert!(!exceeds(512 * 1024, 512));
+            assert!(!exceeds(u64::MAX, u64::MAX));
+        }
+    }
+
+    mod mem_stat {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            // one spike among steady samples.
+            let mut given = vec![100; 99];
+            given.push(1000);
+
+            assert_eq!(MemStat::Peak.of(&given), Some(1000));
+            assert_eq!(MemStat::P99.of(&given), Some(100));
+            assert_eq!(MemStat::P95.of(&given), Some(100));
+        }
+
+        #[test]
+        fn empty() {
+            assert_eq!(MemStat::Peak.of(&[]), None);
+            assert_eq!(MemStat::P99.of(&[]), None);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+
+/// File of a member describing itself, next to its `Cargo.toml` or `go.mod`.
+pub const FILE: &str = "bench-meta.toml";
+
+/// What a member declares about itself in its `bench-meta.toml`, e.g.
+///
+/// ```toml
+/// runtime = "tokio 1.20, multi-threaded"
+/// config = ["no logging", "SO_REUSEPORT"]
+/// notes = "Routes are matched with a radix tree."
+/// ```
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Meta {
+    /// Runtime and its version, e.g. `go 1.19`.
+    pub runtime: Option<String>,
+    /// Notable configuration, one setting each.
+    #[serde(default)]
+    pub config: Vec<String>,
+    /// Free-form notes of the author.
+    pub notes: Option<String>,
+}
+
+impl Meta {
+    /// The metadata of the member at `dir`, `None` without a `bench-meta.toml`.
+    pub fn load(dir: &Path) -> Result<Option<Self>, String> {
+        let path = dir.join(FILE);
+        let toml = match fs::read_to_string(&path) {
+            Ok(toml) => toml,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("could not read {:?}: {}", path, e)),
+        };
+
+        toml::from_str(&toml)
+            .map(Some)
+            .map_err(|e| format!("could not parse {:?}: {}", path, e))
+    }
+
+    /// Items of the framework's section of the report.
+    pub fn items(&self) -> Vec<String> {
+        let mut items = Vec::new();
+        if let Some(runtime) = &self.runtime {
+            items.push(format!("Runtime: {}", runtime));
+        }
+        if !self.config.is_empty() {
+            items.push(format!("Configuration: {}", self.config.join(", ")));
+        }
+        if let Some(notes) = &self.notes {
+            items.push(format!("Notes: {}", notes.trim()));
+        }
+
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod meta {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = "runtime = \"tokio 1.20\"\nconfig = [\"no logging\", \"SO_REUSEPORT\"]\n";
+
+            let actual = toml::from_str::<Meta>(given).unwrap();
+
+            assert_eq!(
+                actual.items(),
+                [
+                    "Runtime: tokio 1.20",
+                    "Configuration: no logging, SO_REUSEPORT"
+                ]
+            );
+        }
+
+        #[test]
+        fn unknown_field() {
+            assert!(toml::from_str::<Meta>("runtim = \"go 1.19\"\n").is_err());
+        }
+
+        #[test]
+        fn missing() {
+            assert_eq!(Meta::load(Path::new("does/not/exist")), Ok(None));
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+/// How the numbers in a report were produced, rendered from the actual
+/// configuration of the run so it can't drift from the results.
+#[derive(Debug, Clone)]
+pub struct Methodology {
+    pub tool: String,
+    pub command: String,
+    pub startup_wait: Duration,
+    pub sample_interval: Duration,
+    pub cooldown: Duration,
+    /// What the server sees before the measured runs, nothing when `None`.
+    pub warmup: Option<String>,
+    pub notes: Vec<String>,
+}
+
+impl Methodology {
+    /// Extra line for settings that only apply to some runs, e.g. a memory
+    /// limit or compression.
+    pub fn add_note(&mut self, note: impl Into<String>) {
+        self.notes.push(note.into());
+    }
+
+    pub fn render(&self) -> String {
+        let mut lines = vec![
+            format!(
+                "- Load generator: `{}`, invoked as `{}`.",
+                self.tool, self.command
+            ),
+            format!(
+                "- Startup: the load starts {} after the server was spawned.",
+                format_duration(self.startup_wait)
+            ),
+            format!(
+                "- Memory: peak RSS of the server process, sampled with sysinfo every {}.",
+                format_duration(self.sample_interval)
+            ),
+            format!(
+                "- Cooldown: {} before every framework.",
+                format_duration(self.cooldown)
+            ),
+            format!(
+                "- Warmup: {}.",
+                self.warmup
+                    .as_deref()
+                    .unwrap_or("none, the measured run is the first load the server sees")
+            ),
+        ];
+
+        lines.extend(self.notes.iter().map(|note| format!("- {}", note)));
+
+        lines.join("\n")
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    if duration.subsec_millis() == 0 {
+        format!("{}s", duration.as_secs())
+    } else {
+        format!("{}ms", duration.as_millis())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod methodology {
+        use super::*;
+
+        #[test]
+        fn render() {
+            let mut given = Methodology {
+                tool: "rewrk".to_string(),
+                command: "rewrk -t 23 -c 500 -d 30s -h http://127.0.0.1:3000".to_string(),
+                startup_wait: Duration::from_secs(1),
+                sample_interval: Duration::from_millis(100),
+                cooldown: Duration::from_secs(5),
+                warmup: None,
+                notes: Vec::new(),
+            };
+            given.add_note("Memory limit: 64 MB per server.");
+
+            let actual = given.render();
+
+            let expect = r#"
+- Load generator: `rewrk`, invoked as `rewrk -t 23 -c 500 -d 30s -h http://127.0.0.1:3000`.
+- Startup: the load starts 1s after the server was spawned.
+- Memory: peak RSS of the server process, sampled with sysinfo every 100ms.
+- Cooldown: 5s before every framework.
+- Warmup: none, the measured run is the first load the server sees.
+- Memory limit: 64 MB per server.
+"#
+            .trim();
+
+            assert_eq!(actual, expect);
+        }
+
+        #[test]
+        fn render_warmup() {
+            let given = Methodology {
+                tool: "rewrk".to_string(),
+                command: "rewrk -t 23 -c 500 -d 30s -h http://127.0.0.1:3000".to_string(),
+                startup_wait: Duration::from_secs(1),
+                sample_interval: Duration::from_millis(100),
+                cooldown: Duration::from_secs(5),
+                warmup: Some("1000 requests, not measured".to_string()),
+                notes: Vec::new(),
+            };
+
+            let actual = given.render();
+
+            assert!(actual.ends_with("\n- Warmup: 1000 requests, not measured."));
+            assert_eq!(actual.matches("Warmup:").count(), 1);
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use crate::interrupt;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    io,
+    process::{Child, Command, Stdio},
+};
+use sysinfo::{Pid, PidExt};
+
+/// Hardware counters `--perf-stat` reads, in `perf stat -e` syntax.
+const EVENTS: &str = "instructions,cycles,cache-references,cache-misses";
+
+/// Check that `perf` can run here, before any member is built.
+pub fn check() -> Result<(), String> {
+    if cfg!(not(target_os = "linux")) {
+        return Err("--perf-stat needs perf, which is only available on Linux".to_string());
+    }
+
+    Command::new("perf")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(drop)
+        .map_err(|_| "--perf-stat needs perf, which was not found".to_string())
+}
+
+/// Hardware counters of the servers over the benchmark, `None` where the
+/// CPU or the kernel doesn't count the event, e.g. in most VMs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct PerfStat {
+    pub instructions: Option<u64>,
+    pub cycles: Option<u64>,
+    pub cache_references: Option<u64>,
+    pub cache_misses: Option<u64>,
+}
+
+impl PerfStat {
+    /// Instructions per cycle.
+    pub fn ipc(&self) -> Option<f64> {
+        ratio(self.instructions?, self.cycles?)
+    }
+
+    /// Share of the cache references that missed, in `0.0..=1.0`.
+    pub fn cache_miss_rate(&self) -> Option<f64> {
+        ratio(self.cache_misses?, self.cache_references?)
+    }
+
+    /// e.g. `IPC 1.85, 2.1% cache misses, 12.3G instructions, 6.6G cycles`.
+    pub fn render(&self) -> String {
+        let giga = |count: u64| format!("{:.1}G", count as f64 / 1e9);
+
+        let mut parts = Vec::new();
+        if let Some(ipc) = self.ipc() {
+            parts.push(format!("IPC {:.2}", ipc));
+        }
+        if let Some(rate) = self.cache_miss_rate() {
+            parts.push(format!("{:.1}% cache misses", rate * 100.0));
+        }
+        if let Some(instructions) = self.instructions {
+            parts.push(format!("{} instructions", giga(instructions)));
+        }
+        if let Some(cycles) = self.cycles {
+            parts.push(format!("{} cycles", giga(cycles)));
+        }
+
+        if parts.is_empty() {
+            "no counter supported".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+fn ratio(numerator: u64, denominator: u64) -> Option<f64> {
+    (denominator > 0).then(|| numerator as f64 / denominator as f64)
+}
+
+/// A running `perf stat`, stopped and parsed by `finish`.
+pub struct Counting {
+    perf: Child,
+}
+
+/// Count `pids` until `finish`, only threads existing by now being
+/// counted.
+pub fn start(pids: &[Pid]) -> io::Result<Counting> {
+    let pids = pids
+        .iter()
+        .map(|pid| pid.as_u32().to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    // the counters go to stderr, `-x,` makes them CSV.
+    let perf = Command::new("perf")
+        .args(["stat", "-x,", "-e", EVENTS, "-p", &pids])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    Ok(Counting { perf })
+}
+
+impl Counting {
+    pub fn finish(self) -> io::Result<PerfStat> {
+        // perf only prints the counters when interrupted, not killed.
+        interrupt::interrupt(self.perf.id())?;
+        let output = self.perf.wait_with_output()?;
+
+        let stat = parse(&String::from_utf8_lossy(&output.stderr));
+        if stat == PerfStat::default() {
+            Err(io::Error::other(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ))
+        } else {
+            Ok(stat)
+        }
+    }
+}
+
+// parse the CSV of `perf stat -x,`, value, unit, event and then details, e.g.
+//      1234567,,instructions:u,1000000,100.00,1.85,insn per cycle
+//      <not supported>,,cache-misses:u,0,100.00,,
+// Hybrid CPUs count the cores of each kind apart, as `cpu_core/cycles/`
+// and `cpu_atom/cycles/`, which are summed.
+fn parse(stat: &str) -> PerfStat {
+    let mut counts = HashMap::<&str, u64>::new();
+    for line in stat.lines().filter(|line| !line.starts_with('#')) {
+        let mut fields = line.split(',');
+        let (Some(value), Some(_unit), Some(event)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let Ok(value) = value.trim().parse::<u64>() else {
+            continue;
+        };
+
+        let event = event.trim();
+        let event = event
+            .split_once('/')
+            .map_or(event, |(_, event)| event.trim_end_matches('/'));
+        let event = event.split_once(':').map_or(event, |(event, _)| event);
+        *counts.entry(event).or_default() += value;
+    }
+
+    PerfStat {
+        instructions: counts.get("instructions").copied(),
+        cycles: counts.get("cycles").copied(),
+        cache_references: counts.get("cache-references").copied(),
+        cache_misses: counts.get("cache-misses").copied(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = "\
+12300000000,,instructions:u,5000000000,100.00,1.85,insn per cycle
+6600000000,,cycles:u,5000000000,100.00,,
+200000000,,cache-references:u,5000000000,100.00,,
+4200000,,cache-misses:u,5000000000,100.00,2.10,of all cache refs
+";
+
+            let actual = parse(given);
+
+            assert_eq!(
+                actual.ipc().map(|ipc| format!("{:.2}", ipc)).as_deref(),
+                Some("1.86")
+            );
+            assert_eq!(
+                actual.render(),
+                "IPC 1.86, 2.1% cache misses, 12.3G instructions, 6.6G cycles"
+            );
+        }
+
+        #[test]
+        fn hybrid() {
+            let given = "\
+1000,,cpu_core/instructions/,100,100.00,,
+500,,cpu_atom/instructions/,100,100.00,,
+<not counted>,,cpu_atom/cycles/,0,0.00,,
+1000,,cpu_core/cycles/,100,100.00,,
+";
+
+            let actual = parse(given);
+
+            assert_eq!(actual.instructions, Some(1500));
+            assert_eq!(actual.cycles, Some(1000));
+        }
+
+        #[test]
+        fn not_supported() {
+            let given =
+                "<not supported>,,instructions,0,100.00,,\n<not supported>,,cycles,0,100.00,,\n";
+
+            assert_eq!(parse(given), PerfStat::default());
+            assert_eq!(PerfStat::default().render(), "no counter supported");
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use std::{fs, net::TcpListener};
+
+/// Highest number of ports probed after the requested one before giving up.
+const MAX_PORT_ATTEMPTS: u16 = 100;
+
+/// Split the authority of `url` into host and port, e.g.
+/// `http://127.0.0.1:3000/path` -> `("127.0.0.1", 3000)`.
+pub fn host_port(url: &str) -> Option<(&str, u16)> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split('/').next()?;
+    let (host, port) = authority.rsplit_once(':')?;
+
+    Some((host, port.parse().ok()?))
+}
+
+/// Replace the port of `url` with `port`, keeping scheme, host and path.
+pub fn with_port(url: &str, port: u16) -> String {
+    let (scheme, rest) = url.split_once("://").unwrap_or(("", url));
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let host = authority
+        .rsplit_once(':')
+        .map_or(authority, |(host, _)| host);
+
+    if scheme.is_empty() {
+        format!("{}:{}{}", host, port, path)
+    } else {
+        format!("{}://{}:{}{}", scheme, host, port, path)
+    }
+}
+
+/// Replace the path of `url` with `path`, keeping scheme, host and port.
+pub fn with_path(url: &str, path: &str) -> String {
+    let scheme_end = url.find("://").map_or(0, |i| i + 3);
+    let authority_end = url[scheme_end..]
+        .find('/')
+        .map_or(url.len(), |i| scheme_end + i);
+
+    format!("{}/{}", &url[..authority_end], path.trim_start_matches('/'))
+}
+
+pub fn is_free(host: &str, port: u16) -> bool {
+    TcpListener::bind((host, port)).is_ok()
+}
+
+/// Find the first bindable port on `host`, starting at `port`.
+pub fn find_free(host: &str, port: u16) -> Option<u16> {
+    (port..=port.saturating_add(MAX_PORT_ATTEMPTS)).find(|&port| is_free(host, port))
+}
+
+/// What listens on a port, as far as `/proc` tells.
+#[derive(Debug, PartialEq)]
+pub enum Listener {
+    Nobody,
+    /// Processes holding the listening socket, several with `SO_REUSEPORT`.
+    Pids(Vec<u32>),
+    /// A socket listens, but no process bench-bot may look into holds it.
+    Hidden,
+}
+
+/// What listens on `port` on any address, `None` where `/proc/net/tcp`
+/// can't be read, e.g. off Linux.
+pub fn listener(port: u16) -> Option<Listener> {
+    let tables = ["/proc/net/tcp", "/proc/net/tcp6"]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .collect::<Vec<_>>();
+    if tables.is_empty() {
+        return None;
+    }
+
+    let inodes = tables
+        .iter()
+        .flat_map(|table| listening_inodes(table, port))
+        .collect::<Vec<_>>();
+    if inodes.is_empty() {
+        return Some(Listener::Nobody);
+    }
+
+    let pids = fs::read_dir("/proc")
+        .ok()?
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|pid| {
+            fs::read_dir(format!("/proc/{}/fd", pid)).is_ok_and(|fds| {
+                fds.filter_map(|fd| fs::read_link(fd.ok()?.path()).ok())
+                    .filter_map(|link| socket_inode(link.to_str()?))
+                    .any(|inode| inodes.contains(&inode))
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Some(if pids.is_empty() {
+        Listener::Hidden
+    } else {
+        Listener::Pids(pids)
+    })
+}
+
+// inodes of the sockets listening on `port` in a `/proc/net/tcp` table, e.g.
+//      sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+//       0: 0100007F:0BB8 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 41231 1 ...
+// the port in hex after the address, `0A` being the LISTEN state.
+fn listening_inodes(table: &str, port: u16) -> Vec<u64> {
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let (_, local_port) = fields.get(1)?.rsplit_once(':')?;
+            let listening =
+                *fields.get(3)? == "0A" && u16::from_str_radix(local_port, 16).ok()? == port;
+            listening.then(|| fields.get(9)?.parse().ok()).flatten()
+        })
+        .collect()
+}
+
+// the inode of a file descriptor's link, e.g. `socket:[41231]`.
+fn socket_inode(link: &str) -> Option<u64> {
+    link.strip_prefix("socket:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod host_port {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            assert_eq!(
+                host_port("http://127.0.0.1:3000"),
+                Some(("127.0.0.1", 3000))
+            );
+            assert_eq!(
+                host_port("http://localhost:8080/hello"),
+                Some(("localhost", 8080))
+            );
+        }
+
+        #[test]
+        fn err() {
+            assert_eq!(host_port("http://127.0.0.1"), None);
+            assert_eq!(host_port("http://127.0.0.1:port"), None);
+        }
+    }
+
+    mod with_port {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            assert_eq!(
+                with_port("http://127.0.0.1:3000", 3001),
+                "http://127.0.0.1:3001"
+            );
+            assert_eq!(
+                with_port("http://localhost:3000/hello", 3002),
+                "http://localhost:3002/hello"
+            );
+        }
+    }
+
+    mod with_path {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            assert_eq!(
+                with_path("http://127.0.0.1:3000", "/json"),
+                "http://127.0.0.1:3000/json"
+            );
+            assert_eq!(
+                with_path("http://localhost:3000/hello", "db"),
+                "http://localhost:3000/db"
+            );
+        }
+    }
+
+    mod find_free {
+        use super::*;
+
+        #[test]
+        fn skips_busy_port() {
+            let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+            let busy = listener.local_addr().unwrap().port();
+
+            let free = find_free("127.0.0.1", busy).unwrap();
+
+            assert_ne!(free, busy);
+            assert!(free > busy);
+        }
+    }
+
+    mod listening_inodes {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0BB8 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 41231 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:0BB8 0100007F:D2F0 01 00000000:00000000 00:00000000 00000000  1000        0 41290 1 0000000000000000 20 4 30 10 -1
+   2: 00000000:0BB9 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 41300 1 0000000000000000 100 0 0 10 0
+";
+
+            assert_eq!(listening_inodes(given, 3000), [41231]);
+            assert_eq!(listening_inodes(given, 3001), [41300]);
+            assert!(listening_inodes(given, 3002).is_empty());
+        }
+    }
+
+    mod socket_inode {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            assert_eq!(socket_inode("socket:[41231]"), Some(41231));
+            assert_eq!(socket_inode("pipe:[41231]"), None);
+            assert_eq!(socket_inode("/dev/null"), None);
+        }
+    }
+
+    mod listener {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+            let port = listener.local_addr().unwrap().port();
+
+            match super::listener(port) {
+                Some(Listener::Pids(pids)) => assert!(pids.contains(&std::process::id())),
+                None => {} // no `/proc` here.
+                other => panic!("{:?}", other),
+            }
+        }
+    }
+}
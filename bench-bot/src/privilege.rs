@@ -0,0 +1,43 @@
+use std::fs;
+
+/// Whether bench-bot runs as root, `false` where it can't tell.
+pub fn is_root() -> bool {
+    fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| effective_uid(&status))
+        == Some(0)
+}
+
+// parse for
+// Name:   bench-bot
+// Uid:    1000    1000    1000    1000
+//
+// the real, effective, saved and filesystem uids.
+fn effective_uid(status: &str) -> Option<u32> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))
+        .and_then(|uids| uids.split_whitespace().nth(1))
+        .and_then(|uid| uid.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod effective_uid {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = "Name:\tbench-bot\nUmask:\t0022\nUid:\t1000\t0\t0\t0\nGid:\t1000\t1000\t1000\t1000\n";
+
+            assert_eq!(effective_uid(given), Some(0));
+        }
+
+        #[test]
+        fn missing() {
+            assert_eq!(effective_uid("Name:\tbench-bot\n"), None);
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use std::{
+    io::{self, Read, Write},
+    net::TcpStream,
+    time::Duration,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A single response fetched outside of the benchmark tool, used to check
+/// what the server actually sends back.
+#[derive(PartialEq, Debug)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Case-insensitive header lookup.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Whether `status` answers a request successfully: one of `expected`, or
+/// any status below 400 when none is expected.
+pub fn is_expected(status: u16, expected: &[u16]) -> bool {
+    if expected.is_empty() {
+        status < 400
+    } else {
+        expected.contains(&status)
+    }
+}
+
+/// Client connection preface of HTTP/2, followed by an empty SETTINGS frame.
+const H2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n\0\0\0\x04\0\0\0\0\0";
+/// Frame type of SETTINGS, which a server must send first.
+const H2_SETTINGS: u8 = 0x4;
+
+/// Send a plain HTTP/1.1 `GET` to `url` with the extra `headers`.
+pub fn get(url: &str, headers: &[(&str, &str)]) -> io::Result<Response> {
+    let (mut stream, authority, path) = connect(url)?;
+
+    let mut request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n",
+        path, authority
+    );
+    for (key, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", key, value));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes())?;
+
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw)?;
+
+    parse(&raw).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed http response"))
+}
+
+/// Check that `url` speaks cleartext HTTP/2 with prior knowledge (h2c),
+/// i.e. answers the connection preface with its SETTINGS.
+pub fn h2c(url: &str) -> io::Result<()> {
+    let (mut stream, _, _) = connect(url)?;
+    stream.write_all(H2_PREFACE)?;
+
+    // length (3), type, flags, stream id (4)
+    let mut frame = [0; 9];
+    stream.read_exact(&mut frame)?;
+
+    if frame[3] == H2_SETTINGS {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "no http/2 settings frame",
+        ))
+    }
+}
+
+fn connect(url: &str) -> io::Result<(TcpStream, &str, &str)> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let path = if path.is_empty() { "/" } else { path };
+
+    let stream = TcpStream::connect(authority)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    Ok((stream, authority, path))
+}
+
+fn parse(raw: &[u8]) -> Option<Response> {
+    let head_end = raw.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let head = std::str::from_utf8(&raw[..head_end]).ok()?;
+    let mut lines = head.split("\r\n");
+
+    // "HTTP/1.1 200 OK"
+    let status = lines.next()?.split_whitespace().nth(1)?.parse().ok()?;
+
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect::<Vec<_>>();
+
+    let mut response = Response {
+        status,
+        headers,
+        body: raw[head_end + 4..].to_vec(),
+    };
+
+    if response
+        .header("Transfer-Encoding")
+        .is_some_and(|encoding| encoding.eq_ignore_ascii_case("chunked"))
+    {
+        response.body = dechunk(&response.body)?;
+    }
+
+    Some(response)
+}
+
+fn dechunk(mut chunked: &[u8]) -> Option<Vec<u8>> {
+    let mut body = Vec::new();
+
+    loop {
+        let line_end = chunked.windows(2).position(|w| w == b"\r\n")?;
+        let size = std::str::from_utf8(&chunked[..line_end]).ok()?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+
+        if size == 0 {
+            return Some(body);
+        }
+
+        let start = line_end + 2;
+        body.extend_from_slice(chunked.get(start..start + size)?);
+        chunked = chunked.get(start + size + 2..)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod h2c {
+        use super::*;
+        use std::{net::TcpListener, thread};
+
+        fn serve(reply: &'static [u8]) -> String {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}/", listener.local_addr().unwrap());
+
+            thread::spawn(move || {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut preface = [0; H2_PREFACE.len()];
+                stream.read_exact(&mut preface).unwrap();
+                stream.write_all(reply).unwrap();
+            });
+
+            url
+        }
+
+        #[test]
+        fn ok() {
+            let url = serve(b"\0\0\0\x04\0\0\0\0\0");
+
+            assert!(h2c(&url).is_ok());
+        }
+
+        #[test]
+        fn http1() {
+            let url = serve(b"HTTP/1.1 400 Bad Request\r\n\r\n");
+
+            assert!(h2c(&url).is_err());
+        }
+    }
+
+    mod is_expected {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            assert!(is_expected(302, &[]));
+            assert!(!is_expected(404, &[]));
+            assert!(is_expected(201, &[201, 204]));
+            assert!(!is_expected(200, &[201, 204]));
+        }
+    }
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = b"HTTP/1.1 200 OK\r\ncontent-type: text/plain\r\nContent-Length: 13\r\n\r\nHello, World!";
+            let actual = parse(given).unwrap();
+
+            assert_eq!(actual.status, 200);
+            assert_eq!(actual.header("Content-Type"), Some("text/plain"));
+            assert_eq!(actual.body, b"Hello, World!");
+        }
+
+        #[test]
+        fn chunked() {
+            let given = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n7\r\nHello, \r\n6\r\nWorld!\r\n0\r\n\r\n";
+            let actual = parse(given).unwrap();
+
+            assert_eq!(actual.body, b"Hello, World!");
+        }
+
+        #[test]
+        fn err() {
+            assert_eq!(parse(b"HTTP/1.1 200 OK\r\n"), None);
+        }
+    }
+}
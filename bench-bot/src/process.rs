@@ -0,0 +1,186 @@
+use std::{
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+
+/// A process bench-bot spawned, told apart from a later one reusing its PID
+/// by its start time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spawned {
+    pub pid: Pid,
+    start_time: u64,
+}
+
+impl Spawned {
+    /// `pid` as of the last refresh of `sys`.
+    pub fn of(sys: &System, pid: Pid) -> Option<Self> {
+        sys.process(pid).map(|process| Self {
+            pid,
+            start_time: process.start_time(),
+        })
+    }
+}
+
+/// Resident memory in KB summed over the `pids` still alive, `None` if none
+/// is.
+pub fn memory(pids: &[Pid]) -> Option<u64> {
+    let mut sys = System::new();
+    let mut memory = None;
+
+    for pid in pids {
+        if sys.refresh_process(*pid) {
+            *memory.get_or_insert(0) += sys.process(*pid).map_or(0, ProcessExt::memory);
+        }
+    }
+
+    memory
+}
+
+/// Peak resident memory of processes from its start to its stop, sampled
+/// on its own thread, e.g. over one step of a ramp.
+pub struct PeakMemory {
+    stop: Sender<()>,
+    thread: JoinHandle<Option<u64>>,
+}
+
+impl PeakMemory {
+    pub fn start(pids: &[Pid], interval: Duration) -> Self {
+        let (stop, stopped) = mpsc::channel();
+        let pids = pids.to_vec();
+        let thread = thread::spawn(move || {
+            let mut peak = None;
+            loop {
+                if let Some(memory) = memory(&pids) {
+                    peak = Some(peak.map_or(memory, |peak: u64| peak.max(memory)));
+                }
+                match stopped.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Ok(()) | Err(RecvTimeoutError::Disconnected) => return peak,
+                }
+            }
+        });
+
+        Self { stop, thread }
+    }
+
+    /// The peak in KB, `None` if no process could be sampled.
+    pub fn stop(self) -> Option<u64> {
+        let _ = self.stop.send(());
+        self.thread.join().unwrap_or_default()
+    }
+}
+
+/// Kill every one of `spawned` that is still alive, returning those.
+pub fn reap(spawned: &[Spawned]) -> Vec<Spawned> {
+    let mut sys = System::new();
+
+    spawned
+        .iter()
+        .filter(|spawned| {
+            sys.refresh_process(spawned.pid)
+                && sys.process(spawned.pid).is_some_and(|process| {
+                    process.start_time() == spawned.start_time && process.kill()
+                })
+        })
+        .copied()
+        .collect()
+}
+
+/// Every process `pid` spawned, directly or not, as of the last refresh of
+/// `sys`. `cargo run` and `go run` are only the parents of the server.
+pub fn descendants(sys: &System, pid: Pid) -> Vec<Pid> {
+    let parents = sys
+        .processes()
+        .iter()
+        .filter_map(|(child, process)| Some((child.as_u32(), process.parent()?.as_u32())))
+        .collect::<Vec<_>>();
+
+    walk(&parents, pid.as_u32())
+        .into_iter()
+        .map(Pid::from_u32)
+        .collect()
+}
+
+/// Breadth-first over `(child, parent)` pairs.
+fn walk(parents: &[(u32, u32)], pid: u32) -> Vec<u32> {
+    let mut found = vec![pid];
+    let mut i = 0;
+    while let Some(parent) = found.get(i).copied() {
+        found.extend(
+            parents
+                .iter()
+                .filter(|(child, p)| *p == parent && !found.contains(child))
+                .map(|(child, _)| *child)
+                .collect::<Vec<_>>(),
+        );
+        i += 1;
+    }
+
+    found.remove(0);
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod reap {
+        use super::*;
+        use std::process::Command;
+
+        #[test]
+        fn ok() {
+            let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+            let pid = Pid::from_u32(child.id());
+            let mut sys = System::new();
+            sys.refresh_process(pid);
+            let given = Spawned::of(&sys, pid).unwrap();
+            // same pid, another process.
+            let reused = Spawned {
+                start_time: given.start_time + 1,
+                ..given
+            };
+
+            assert!(reap(&[reused]).is_empty());
+            assert_eq!(reap(&[given]), [given]);
+            assert!(!child.wait().unwrap().success());
+        }
+    }
+
+    mod peak_memory {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let own = Pid::from_u32(std::process::id());
+
+            let peak = PeakMemory::start(&[own], Duration::from_millis(10));
+            thread::sleep(Duration::from_millis(50));
+
+            assert!(peak.stop().is_some_and(|memory| memory > 0));
+            assert_eq!(
+                PeakMemory::start(&[], Duration::from_millis(10)).stop(),
+                None
+            );
+        }
+    }
+
+    mod walk {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            // cargo (10) -> sh (11) -> server (12), unrelated 20 -> 21
+            let given = [(11, 10), (12, 11), (21, 20)];
+
+            assert_eq!(walk(&given, 10), [11, 12]);
+        }
+
+        #[test]
+        fn none() {
+            assert!(walk(&[(21, 20)], 10).is_empty());
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::interrupt;
+use crate::process;
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+};
+use sysinfo::{Pid, PidExt, System, SystemExt};
+
+/// Profiler attached to every server during its benchmark.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Profile {
+    /// `perf` samples rendered as an SVG flamegraph by inferno.
+    Flamegraph,
+}
+
+const TOOLS: [&str; 3] = ["perf", "inferno-collapse-perf", "inferno-flamegraph"];
+
+/// Sampling frequency, off the usual 100Hz timers to avoid lockstep.
+const FREQUENCY: &str = "99";
+
+impl Profile {
+    /// Check that the profiler can run here, before any member is built.
+    pub fn check(self) -> Result<(), String> {
+        if cfg!(not(target_os = "linux")) {
+            return Err("--profile needs perf, which is only available on Linux".to_string());
+        }
+
+        let missing = TOOLS
+            .iter()
+            .filter(|tool| {
+                Command::new(tool)
+                    .arg("--help")
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status()
+                    .is_err()
+            })
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("--profile flamegraph needs {:?} in PATH", missing))
+        }
+    }
+}
+
+/// A running `perf record`, stopped and rendered by `finish`.
+pub struct Recording {
+    perf: Child,
+    data: PathBuf,
+}
+
+/// Record `pids` and every process they spawned, `cargo run` and `go run`
+/// only being the parents of the actual server.
+pub fn record(pids: &[u32], data: PathBuf) -> io::Result<Recording> {
+    let pids = pids
+        .iter()
+        .flat_map(|pid| with_descendants(*pid))
+        .map(|pid| pid.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let perf = Command::new("perf")
+        .args(["record", "-F", FREQUENCY, "-g", "-p", &pids, "-o"])
+        .arg(&data)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    Ok(Recording { perf, data })
+}
+
+impl Recording {
+    /// Stop recording and write the flamegraph to `svg`.
+    pub fn finish(mut self, svg: &Path) -> io::Result<()> {
+        // perf only writes a readable file when interrupted, not killed.
+        interrupt::interrupt(self.perf.id())?;
+        self.perf.wait()?;
+
+        let mut script = Command::new("perf")
+            .args(["script", "-i"])
+            .arg(&self.data)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let mut collapse = Command::new("inferno-collapse-perf")
+            .stdin(script.stdout.take().expect("piped stdout"))
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let status = Command::new("inferno-flamegraph")
+            .stdin(collapse.stdout.take().expect("piped stdout"))
+            .stdout(File::create(svg)?)
+            .status()?;
+        script.wait()?;
+        collapse.wait()?;
+
+        let _ = fs::remove_file(&self.data);
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!(
+                "inferno-flamegraph exited with {}",
+                status
+            )))
+        }
+    }
+}
+
+fn with_descendants(pid: u32) -> Vec<u32> {
+    let mut sys = System::new();
+    sys.refresh_processes();
+
+    let mut pids = vec![pid];
+    pids.extend(
+        process::descendants(&sys, Pid::from_u32(pid))
+            .iter()
+            .map(|pid| pid.as_u32()),
+    );
+    pids
+}
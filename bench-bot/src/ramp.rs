@@ -0,0 +1,160 @@
+use serde::Serialize;
+
+/// One short benchmark of a ramp.
+#[derive(PartialEq, Debug, Clone, Copy, Serialize)]
+pub struct RampPoint {
+    pub connections: usize,
+    pub req_per_sec: f64,
+    /// Peak memory of the servers over the step in MB, their `cargo run`
+    /// or `go run` wrappers included.
+    pub memory_mb: Option<f64>,
+}
+
+/// Successive benchmarks at increasing connection counts, stopped once
+/// requests/sec no longer improve by more than `tolerance` (e.g. `0.05` for
+/// 5%), which finds the saturation point of a framework.
+#[derive(Debug, Clone, Serialize)]
+pub struct Ramp {
+    tolerance: f64,
+    points: Vec<RampPoint>,
+}
+
+impl Ramp {
+    pub fn new(tolerance: f64) -> Self {
+        Self {
+            tolerance,
+            points: Vec::new(),
+        }
+    }
+
+    /// Record a point, returns whether requests/sec have plateaued.
+    pub fn push(&mut self, point: RampPoint) -> bool {
+        self.points.push(point);
+        self.knee().is_some()
+    }
+
+    /// The last point that still improved on its predecessor beyond the
+    /// tolerance, if the ramp plateaued at all.
+    pub fn knee(&self) -> Option<&RampPoint> {
+        self.points
+            .windows(2)
+            .find(|pair| pair[1].req_per_sec < pair[0].req_per_sec * (1.0 + self.tolerance))
+            .map(|pair| &pair[0])
+    }
+
+    /// Every step of the ramp, in the order they ran.
+    pub fn points(&self) -> &[RampPoint] {
+        &self.points
+    }
+
+    /// The best requests/sec seen over the whole ramp.
+    pub fn max(&self) -> Option<&RampPoint> {
+        self.points
+            .iter()
+            .max_by(|a, b| a.req_per_sec.total_cmp(&b.req_per_sec))
+    }
+
+    /// Markdown table of the full ramp curve, with the memory of every
+    /// step when it was sampled.
+    pub fn render(&self) -> String {
+        if !self.points.iter().any(|point| point.memory_mb.is_some()) {
+            let mut res = String::from("| Connections | Req/Sec |\n|---|---|");
+            for point in &self.points {
+                res.push_str(&format!(
+                    "\n|{}|{:.2}|",
+                    point.connections, point.req_per_sec
+                ));
+            }
+            return res;
+        }
+
+        let mut res = String::from("| Connections | Req/Sec | Peak Memory |\n|---|---|---|");
+        for point in &self.points {
+            let memory = point
+                .memory_mb
+                .map_or("-".to_string(), |mb| format!("{:.1}MB", mb));
+            res.push_str(&format!(
+                "\n|{}|{:.2}|{}|",
+                point.connections, point.req_per_sec, memory
+            ));
+        }
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(connections: usize, req_per_sec: f64) -> RampPoint {
+        RampPoint {
+            connections,
+            req_per_sec,
+            memory_mb: None,
+        }
+    }
+
+    mod ramp {
+        use super::*;
+
+        #[test]
+        fn plateau() {
+            let mut given = Ramp::new(0.05);
+
+            assert!(!given.push(point(8, 100_000.0)));
+            assert!(!given.push(point(16, 180_000.0)));
+            assert!(given.push(point(32, 185_000.0)));
+
+            assert_eq!(given.knee(), Some(&point(16, 180_000.0)));
+            assert_eq!(given.max(), Some(&point(32, 185_000.0)));
+        }
+
+        #[test]
+        fn no_plateau() {
+            let mut given = Ramp::new(0.05);
+            given.push(point(8, 100_000.0));
+            given.push(point(16, 200_000.0));
+
+            assert_eq!(given.knee(), None);
+            assert_eq!(given.max(), Some(&point(16, 200_000.0)));
+        }
+
+        #[test]
+        fn render() {
+            let mut given = Ramp::new(0.05);
+            given.push(point(8, 100_000.0));
+            given.push(point(16, 180_000.5));
+
+            let expect = r#"
+| Connections | Req/Sec |
+|---|---|
+|8|100000.00|
+|16|180000.50|
+"#
+            .trim();
+
+            assert_eq!(given.render(), expect);
+        }
+
+        #[test]
+        fn render_memory() {
+            let mut given = Ramp::new(0.05);
+            given.push(RampPoint {
+                memory_mb: Some(12.34),
+                ..point(8, 100_000.0)
+            });
+            given.push(point(16, 180_000.5));
+
+            let expect = r#"
+| Connections | Req/Sec | Peak Memory |
+|---|---|---|
+|8|100000.00|12.3MB|
+|16|180000.50|-|
+"#
+            .trim();
+
+            assert_eq!(given.render(), expect);
+        }
+    }
+}
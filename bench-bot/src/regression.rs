@@ -0,0 +1,276 @@
+use crate::{git::Worktree, report::Report};
+use serde_json::Value;
+use std::{env, fs, path::Path, process::Command};
+
+/// Options the run of the previous tag gets from this run instead of from
+/// the command line or the environment, with whether they take a value.
+const OVERRIDDEN: [(&str, bool, &str); 11] = [
+    ("--compare-tag", true, "BENCH_COMPARE_TAG"),
+    ("-w", true, "BENCH_WORKSPACE_DIR"),
+    ("-o", true, "BENCH_OUTPUT_DIR"),
+    ("--format", true, "BENCH_FORMAT"),
+    ("--output-dir-per-run", false, "BENCH_OUTPUT_DIR_PER_RUN"),
+    ("--compare-languages", false, "BENCH_COMPARE_LANGUAGES"),
+    ("--stream", false, "BENCH_STREAM"),
+    ("--stream-socket", true, "BENCH_STREAM_SOCKET"),
+    ("--keep-alive-after", false, "BENCH_KEEP_ALIVE_AFTER"),
+    ("--sqlite", true, "BENCH_SQLITE"),
+    ("--summary-json", false, "BENCH_SUMMARY_JSON"),
+];
+
+/// The numbers of one framework compared between two runs.
+#[derive(Debug, PartialEq)]
+pub struct Row {
+    bench_type: String,
+    framework: String,
+    req_per_sec: Option<f64>,
+    memory_mb: Option<f64>,
+}
+
+impl Row {
+    /// From a report as serialized into the JSON output.
+    fn from_json(report: &Value) -> Option<Self> {
+        let number =
+            |value: &Value| -> Option<f64> { value.as_str()?.trim_end_matches("MB").parse().ok() };
+
+        Some(Self {
+            bench_type: report["bench_type"].as_str()?.to_string(),
+            framework: report["framework_name"].as_str()?.to_string(),
+            req_per_sec: number(&report["metrics"]["request"]["req_per_sec"]),
+            memory_mb: number(&report["max_memory"]),
+        })
+    }
+
+    pub fn of(reports: &[Report]) -> Vec<Self> {
+        let reports = serde_json::to_value(reports).expect("serialize reports");
+        rows(&reports)
+    }
+}
+
+fn rows(reports: &Value) -> Vec<Row> {
+    reports
+        .as_array()
+        .map_or(&[][..], Vec::as_slice)
+        .iter()
+        .filter_map(Row::from_json)
+        .collect()
+}
+
+/// Benchmark `tag` of the repository `workspace_dir` is in: check it out
+/// into a temporary worktree and run bench-bot on it with the arguments of
+/// this run, writing JSON to a temporary directory.
+pub fn run_tag(workspace_dir: &Path, tag: &str) -> Result<Vec<Row>, String> {
+    let prefix = crate::git::prefix(workspace_dir)
+        .ok_or_else(|| format!("{:?} is not in a git repository", workspace_dir))?;
+    let tmp = env::temp_dir().join(format!(
+        "bench-bot-{}-{}",
+        tag.replace('/', "-"),
+        std::process::id()
+    ));
+    let output_dir = tmp.join("result");
+    fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("could not create {:?}: {}", output_dir, e))?;
+
+    let worktree = Worktree::add(workspace_dir, tag, &tmp.join("worktree"))
+        .map_err(|e| format!("could not check out {}: {}", tag, e))?;
+
+    let exe = env::current_exe().map_err(|e| format!("could not find bench-bot itself: {}", e))?;
+    let mut args = child_args(env::args().skip(1));
+    args.extend([
+        "-w".to_string(),
+        worktree.path.join(prefix).display().to_string(),
+    ]);
+    args.extend(["-o".to_string(), output_dir.display().to_string()]);
+    args.extend(["--format".to_string(), "json".to_string()]);
+
+    log::info!("Benchmarking {} in {:?}.", tag, worktree.path);
+    let mut command = Command::new(exe);
+    command.args(&args);
+    for (_, _, env) in OVERRIDDEN {
+        command.env_remove(env);
+    }
+    let status = command
+        .status()
+        .map_err(|e| format!("could not run bench-bot on {}: {}", tag, e))?;
+    drop(worktree);
+
+    let rows = if status.success() {
+        read_rows(&output_dir)
+    } else {
+        None
+    };
+    let _ = fs::remove_dir_all(&tmp);
+
+    rows.ok_or_else(|| format!("the run of {} produced no reports", tag))
+}
+
+/// Reports of any `{bench_type}.json`, each holds all of them.
+fn read_rows(output_dir: &Path) -> Option<Vec<Row>> {
+    let json = fs::read_dir(output_dir)
+        .ok()?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .find(|path| path.extension().is_some_and(|ext| ext == "json"))?;
+    let output = serde_json::from_str::<Value>(&fs::read_to_string(json).ok()?).ok()?;
+
+    Some(rows(&output["reports"]))
+}
+
+/// `args` without the options of `OVERRIDDEN`, in any of the `-w dir`,
+/// `-wdir`, `--format json` and `--format=json` forms.
+fn child_args(args: impl Iterator<Item = String>) -> Vec<String> {
+    let mut kept = Vec::new();
+    let mut skip_value = false;
+
+    for arg in args {
+        if skip_value {
+            skip_value = false;
+            continue;
+        }
+
+        let overridden = OVERRIDDEN.iter().find(|(name, takes_value, _)| {
+            arg == *name
+                || (*takes_value
+                    && name.starts_with("--")
+                    && arg.starts_with(&format!("{}=", name)))
+                || (*takes_value && !name.starts_with("--") && arg.starts_with(*name))
+        });
+        match overridden {
+            Some((name, true, _)) => skip_value = arg == *name,
+            Some((_, false, _)) => {}
+            None => kept.push(arg),
+        }
+    }
+
+    kept
+}
+
+/// Markdown comparing `previous`, the run of `tag`, with `current`, a row
+/// per framework of either.
+pub fn render(tag: &str, previous: &[Row], current: &[Row]) -> String {
+    let mut keys = previous
+        .iter()
+        .chain(current)
+        .map(|row| (row.bench_type.as_str(), row.framework.as_str()))
+        .collect::<Vec<_>>();
+    keys.sort_unstable();
+    keys.dedup();
+
+    fn find<'a>(rows: &'a [Row], key: (&str, &str)) -> Option<&'a Row> {
+        rows.iter()
+            .find(|row| (row.bench_type.as_str(), row.framework.as_str()) == key)
+    }
+    let cell = |value: Option<f64>, unit: &str| {
+        value.map_or("-".to_string(), |value| format!("{:.2}{}", value, unit))
+    };
+    let change = |previous: Option<f64>, current: Option<f64>| match (previous, current) {
+        (Some(previous), Some(current)) if previous > 0.0 => {
+            format!("{:+.1}%", (current - previous) / previous * 100.0)
+        }
+        _ => "-".to_string(),
+    };
+
+    let mut res = format!(
+        "| Category | Framework Name | Req/Sec ({tag}) | Req/Sec | Change | Memory ({tag}) | Memory | Change |\n\
+         |---|---|---|---|---|---|---|---|",
+        tag = tag
+    );
+    for key in keys {
+        let previous = find(previous, key);
+        let current = find(current, key);
+        let rps = (
+            previous.and_then(|r| r.req_per_sec),
+            current.and_then(|r| r.req_per_sec),
+        );
+        let mem = (
+            previous.and_then(|r| r.memory_mb),
+            current.and_then(|r| r.memory_mb),
+        );
+
+        res.push_str(&format!(
+            "\n|{}|{}|{}|{}|{}|{}|{}|{}|",
+            key.0,
+            key.1,
+            cell(rps.0, ""),
+            cell(rps.1, ""),
+            change(rps.0, rps.1),
+            cell(mem.0, "MB"),
+            cell(mem.1, "MB"),
+            change(mem.0, mem.1)
+        ));
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod child_args {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = [
+                "-w",
+                "../benchmark",
+                "-o../result",
+                "--compare-tag",
+                "v1",
+                "-c",
+                "100",
+                "--format=md,json",
+                "--stream",
+                "--runs",
+                "3",
+            ];
+
+            let actual = child_args(given.iter().map(|arg| arg.to_string()));
+
+            assert_eq!(actual, ["-c", "100", "--runs", "3"]);
+        }
+    }
+
+    mod render {
+        use super::*;
+
+        fn row(framework: &str, req_per_sec: f64, memory_mb: f64) -> Row {
+            Row {
+                bench_type: "hello-world".to_string(),
+                framework: framework.to_string(),
+                req_per_sec: Some(req_per_sec),
+                memory_mb: Some(memory_mb),
+            }
+        }
+
+        #[test]
+        fn ok() {
+            let previous = [row("axum", 100.0, 10.0)];
+            let current = [row("axum", 110.0, 9.0), row("warp", 90.0, 8.0)];
+
+            let actual = render("v1", &previous, &current);
+
+            let expect = r#"
+| Category | Framework Name | Req/Sec (v1) | Req/Sec | Change | Memory (v1) | Memory | Change |
+|---|---|---|---|---|---|---|---|
+|hello-world|axum|100.00|110.00|+10.0%|10.00MB|9.00MB|-10.0%|
+|hello-world|warp|-|90.00|-|-|8.00MB|-|
+"#
+            .trim();
+
+            assert_eq!(actual, expect);
+        }
+
+        #[test]
+        fn from_json() {
+            let given = serde_json::json!([{
+                "bench_type": "hello-world",
+                "framework_name": "axum",
+                "max_memory": "12.4MB",
+                "metrics": { "request": { "req_per_sec": "1006342.33" } },
+            }]);
+
+            assert_eq!(rows(&given), [row("axum", 1006342.33, 12.4)]);
+        }
+    }
+}
@@ -1,49 +1,482 @@
+use crate::cpu::CoreUsage;
+use crate::disk::DiskIo;
+use crate::html;
+use crate::language::Language;
+use crate::latency::ProbeLatency;
+use crate::matrix::Matrix;
+use crate::memory::{self, MemStat};
+use crate::meta::Meta;
+use crate::perf::PerfStat;
+use crate::ramp::Ramp;
+use crate::stats;
+use crate::timing::Timings;
+use serde::Serialize;
 use std::str::FromStr;
 
 #[derive(PartialEq, Debug)]
 pub enum MetricsError {
-    ParseError
+    ParseError,
 }
 
+#[derive(Serialize)]
 pub struct Report {
+    bench_type: String,
     framework_name: String,
+    language: Language,
+    /// Connections of the reported run, the best one of a ramp.
+    connections: usize,
     max_memory: String,
     metrics: Metrics,
+    encoding: Option<String>,
+    conn_per_sec: Option<f64>,
+    ramp: Option<Ramp>,
+    matrix: Option<Matrix>,
+    /// Connections of the best Req/Sec over `--ramp` or `--matrix`.
+    optimal_connections: Option<usize>,
+    /// Requests/sec of every run, with `--runs`.
+    samples: Vec<f64>,
+    /// Response headers of a sample request sent before the benchmark.
+    headers: Vec<(String, String)>,
+    /// Body bytes of the sample response.
+    response_size: Option<usize>,
+    /// Peak memory of the processes the `cargo run`/`go run` wrapper
+    /// spawned, the wrapper's own being `max_memory`.
+    server_memory: Option<String>,
+    timings: Timings,
+    /// Protocol when not HTTP/1.1.
+    protocol: Option<String>,
+    /// Bytes of the `--build-target` binary.
+    binary_size: Option<u64>,
+    /// Per-core CPU utilization while the load generator ran.
+    core_usage: Option<CoreUsage>,
+    /// Lowest clock of a loaded core in MHz, and whether it was below the
+    /// base clock.
+    min_frequency_mhz: Option<u64>,
+    throttled: bool,
+    /// Memory statistic chosen with `--mem-stat`, when not the peak.
+    mem_p99: Option<String>,
+    mem_p95: Option<String>,
+    /// Which memory figure the table headlines, the peak `max_memory`
+    /// following it when that is another one.
+    #[serde(skip)]
+    mem_stat: MemStat,
+    /// Peak memory including the samples discarded by `--mem-warmup-ms`.
+    startup_peak: Option<String>,
+    /// Memory once started, before any request, and how much the peak is
+    /// above it.
+    idle_memory: Option<String>,
+    load_delta: Option<String>,
+    /// Memory sampled after `--gc-endpoint` collected garbage.
+    post_gc_memory: Option<String>,
+    /// Memory in MB at the seconds of `--mem-at`, `None` past the run.
+    mem_at: Vec<(u64, Option<f64>)>,
+    /// Tags of the framework in `--config`, e.g. `minimal`.
+    tags: Vec<String>,
+    /// Storage I/O of the servers during the benchmark, with `--disk-io`.
+    disk_io: Option<DiskIo>,
+    /// Latency of the `--probe-interval-ms` requests sent under the load.
+    probe_latency: Option<ProbeLatency>,
+    /// Hardware counters of the servers, with `--perf-stat`.
+    perf_stat: Option<PerfStat>,
+    /// What the member declares about itself in its `bench-meta.toml`.
+    meta: Option<Meta>,
 }
 
-const REPORT_HEADER: &str = "| Framework Name | Latency.Avg | Latency.Stdev | Latency.Min | Latency.Max | Request.Total | Request.Req/Sec | Transfer.Total | Transfer.Rate | Max. Memory Usage |";
-const TABLE_SEPARATOR: &str = "\n|---|---|---|---|---|---|---|---|---|---|\n";
+const REPORT_HEADER: [&str; 10] = [
+    "Framework Name",
+    "Latency.Avg",
+    "Latency.Stdev",
+    "Latency.Min",
+    "Latency.Max",
+    "Request.Total",
+    "Request.Req/Sec",
+    "Transfer.Total",
+    "Transfer.Rate",
+    "Max. Memory Usage",
+];
+
+type Column = (&'static str, fn(&Report) -> Option<String>);
+
+// columns only shown when at least one report has a value for them.
+const OPTIONAL_COLUMNS: [Column; 25] = [
+    ("Protocol", |r| r.protocol.clone()),
+    ("Latency.P99", |r| r.metrics.latency.p99.clone()),
+    ("Latency.P99.9", |r| r.metrics.latency.p999.clone()),
+    ("Latency.P99.99", |r| r.metrics.latency.p9999.clone()),
+    ("TTFB.P50", |r| r.metrics.ttfb.as_ref()?.p50.clone()),
+    ("TTFB.P99", |r| r.metrics.ttfb.as_ref()?.p99.clone()),
+    ("Timeouts", |r| {
+        r.metrics
+            .timeouts
+            .filter(|timeouts| *timeouts > 0)
+            .map(|timeouts| timeouts.to_string())
+    }),
+    ("Conn/Sec", |r| {
+        r.conn_per_sec.map(|rate| format!("{:.2}", rate))
+    }),
+    ("Req/Conn", |r| {
+        r.metrics.reuse_ratio().map(|ratio| format!("{:.1}", ratio))
+    }),
+    ("Ramp.Knee", |r| {
+        let ramp = r.ramp.as_ref()?;
+        Some(ramp.knee().map_or("not reached".to_string(), |knee| {
+            knee.connections.to_string()
+        }))
+    }),
+    ("Ramp.Max Req/Sec", |r| {
+        let max = r.ramp.as_ref()?.max()?;
+        Some(format!("{:.2}@{}", max.req_per_sec, max.connections))
+    }),
+    ("Optimal Concurrency", |r| {
+        r.optimal_connections
+            .map(|connections| connections.to_string())
+    }),
+    ("Req/Sec CV", |r| {
+        coefficient_of_variation(&r.samples).map(|cv| format!("{:.1}%", cv * 100.0))
+    }),
+    ("Memory.Peak", |r| {
+        (r.mem_stat != MemStat::Peak).then(|| r.max_memory.clone())
+    }),
+    ("Memory.Incl. Startup", |r| r.startup_peak.clone()),
+    ("Memory.Idle", |r| r.idle_memory.clone()),
+    ("Memory.Load Delta", |r| r.load_delta.clone()),
+    ("Memory.Post-GC", |r| r.post_gc_memory.clone()),
+    ("Memory.At", |r| {
+        (!r.mem_at.is_empty()).then(|| memory::render_at(&r.mem_at))
+    }),
+    ("Server Memory", |r| r.server_memory.clone()),
+    ("Response Size", |r| {
+        r.response_size.map(|size| format!("{}B", size))
+    }),
+    ("Disk Read/Write", |r| {
+        r.disk_io.map(|disk_io| disk_io.render())
+    }),
+    ("Probe.P99", |r| r.probe_latency.as_ref()?.p99()),
+    ("Perf.IPC", |r| {
+        r.perf_stat?.ipc().map(|ipc| format!("{:.2}", ipc))
+    }),
+    ("Perf.Cache Miss", |r| {
+        r.perf_stat?
+            .cache_miss_rate()
+            .map(|rate| format!("{:.1}%", rate * 100.0))
+    }),
+];
+
+// cell of a value the tool did not report.
+const MISSING: &str = "-";
+
+const CSV_HEADER: [&str; 13] = [
+    "bench_type",
+    "framework",
+    "phase",
+    "connections",
+    "duration_secs",
+    "req_per_sec",
+    "transfer_total_mb",
+    "transfer_rate_mb_per_sec",
+    "max_memory_mb",
+    "cpu_mean_percent",
+    "latency_p50_ms",
+    "latency_p99_ms",
+    "startup_ms",
+];
 
 impl Report {
-    pub fn new(framework_name: &str,
-               max_memory: f64,
-               metrics: Metrics) -> Self {
+    pub fn new(framework_name: &str, max_memory: f64, metrics: Metrics) -> Self {
         Self {
+            bench_type: String::new(),
             framework_name: framework_name.to_string(),
+            language: Language::Rust,
+            connections: 0,
             metrics,
             max_memory: format!("{:.1}MB", max_memory),
+            encoding: None,
+            conn_per_sec: None,
+            ramp: None,
+            matrix: None,
+            optimal_connections: None,
+            samples: Vec::new(),
+            headers: Vec::new(),
+            response_size: None,
+            core_usage: None,
+            binary_size: None,
+            protocol: None,
+            timings: Timings::default(),
+            server_memory: None,
+            mem_p99: None,
+            mem_p95: None,
+            mem_stat: MemStat::Peak,
+            startup_peak: None,
+            idle_memory: None,
+            load_delta: None,
+            post_gc_memory: None,
+            mem_at: Vec::new(),
+            tags: Vec::new(),
+            disk_io: None,
+            probe_latency: None,
+            perf_stat: None,
+            meta: None,
+            min_frequency_mhz: None,
+            throttled: false,
+        }
+    }
+
+    pub fn with_min_frequency(mut self, min_frequency_mhz: Option<u64>, throttled: bool) -> Self {
+        self.min_frequency_mhz = min_frequency_mhz;
+        self.throttled = throttled;
+        self
+    }
+
+    /// Memory statistic other than the peak, in MB, headlined in place of
+    /// the peak.
+    pub fn with_mem_stat(mut self, mem_stat: Option<(MemStat, f64)>) -> Self {
+        let Some((stat, memory)) = mem_stat else {
+            return self;
+        };
+        let memory = Some(format!("{:.1}MB", memory));
+        match stat {
+            MemStat::P99 => self.mem_p99 = memory,
+            MemStat::P95 => self.mem_p95 = memory,
+            MemStat::Peak => return self,
+        }
+        self.mem_stat = stat;
+        self
+    }
+
+    /// Peak memory including startup, in MB.
+    pub fn with_startup_peak(mut self, startup_peak: Option<f64>) -> Self {
+        self.startup_peak = startup_peak.map(|memory| format!("{:.1}MB", memory));
+        self
+    }
+
+    pub fn with_mem_at(mut self, mem_at: Vec<(u64, Option<f64>)>) -> Self {
+        self.mem_at = mem_at;
+        self
+    }
+
+    pub fn with_disk_io(mut self, disk_io: Option<DiskIo>) -> Self {
+        self.disk_io = disk_io;
+        self
+    }
+
+    pub fn with_probe_latency(mut self, probe_latency: Option<ProbeLatency>) -> Self {
+        self.probe_latency = probe_latency;
+        self
+    }
+
+    pub fn with_perf_stat(mut self, perf_stat: Option<PerfStat>) -> Self {
+        self.perf_stat = perf_stat;
+        self
+    }
+
+    pub fn with_meta(mut self, meta: Option<Meta>) -> Self {
+        self.meta = meta;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Memory before any request and the peak it grew to, in MB.
+    pub fn with_idle_memory(mut self, idle_memory: Option<f64>, peak: f64) -> Self {
+        self.idle_memory = idle_memory.map(|memory| format!("{:.1}MB", memory));
+        self.load_delta = idle_memory.map(|memory| format!("{:+.1}MB", peak - memory));
+        self
+    }
+
+    /// Memory after garbage collection next to the peak of the same
+    /// processes, in MB.
+    pub fn with_post_gc_memory(mut self, post_gc_memory: Option<(f64, f64)>) -> Self {
+        self.post_gc_memory =
+            post_gc_memory.map(|(memory, peak)| format!("{:.1}MB of {:.1}MB", memory, peak));
+        self
+    }
+
+    /// Benchmark category, i.e. the workspace directory of the member.
+    pub fn with_bench_type(mut self, bench_type: &str) -> Self {
+        self.bench_type = bench_type.to_string();
+        self
+    }
+
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    pub fn with_connections(mut self, connections: usize) -> Self {
+        self.connections = connections;
+        self
+    }
+
+    pub fn with_server_memory(mut self, server_memory: Option<f64>) -> Self {
+        self.server_memory = server_memory.map(|memory| format!("{:.1}MB", memory));
+        self
+    }
+
+    pub fn with_timings(mut self, timings: Timings) -> Self {
+        self.timings = timings;
+        self
+    }
+
+    pub fn with_protocol(mut self, protocol: Option<&str>) -> Self {
+        self.protocol = protocol.map(str::to_string);
+        self
+    }
+
+    pub fn with_binary_size(mut self, binary_size: Option<u64>) -> Self {
+        self.binary_size = binary_size;
+        self
+    }
+
+    pub fn with_core_usage(mut self, core_usage: Option<CoreUsage>) -> Self {
+        self.core_usage = core_usage;
+        self
+    }
+
+    /// Headers the server answered a sample request with, these explain
+    /// differences in transferred bytes.
+    pub fn with_headers(mut self, headers: Vec<(String, String)>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Body bytes of the sample response, as compressed when it was.
+    pub fn with_response_size(mut self, response_size: Option<usize>) -> Self {
+        self.response_size = response_size;
+        self
+    }
+
+    /// Requests/sec of each run, the reported metrics being the median one.
+    pub fn with_samples(mut self, samples: Vec<f64>) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    /// Attach the connection ramp this report's metrics were the best of.
+    pub fn with_ramp(mut self, ramp: Option<Ramp>) -> Self {
+        self.ramp = ramp;
+        self
+    }
+
+    /// Attach the `--matrix` grid benchmarked alongside this report.
+    pub fn with_matrix(mut self, matrix: Option<Matrix>) -> Self {
+        self.matrix = matrix;
+        self
+    }
+
+    /// Connection count at which the framework peaked in a sweep, `None`
+    /// without one.
+    pub fn with_optimal_connections(mut self, optimal_connections: Option<usize>) -> Self {
+        self.optimal_connections = optimal_connections;
+        self
+    }
+
+    /// Content encoding the server answered with when compression was
+    /// requested, so compressed and uncompressed rows are not mixed up.
+    pub fn with_encoding(mut self, encoding: Option<String>) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Compute new connections per second over `duration_secs`. Only
+    /// meaningful without keep-alive, where every request opens a connection.
+    pub fn with_connection_rate(mut self, duration_secs: usize) -> Self {
+        self.conn_per_sec = self
+            .metrics
+            .request
+            .total
+            .parse::<f64>()
+            .ok()
+            .map(|total| total / duration_secs.max(1) as f64);
+        self
+    }
+
+    fn name(&self) -> String {
+        match &self.encoding {
+            Some(encoding) => format!("{} ({})", self.framework_name, encoding),
+            None => self.framework_name.clone(),
         }
     }
 
-    pub fn generate_from(reports: &Vec<Report>) -> String {
+    /// The headlined memory figure, the `--mem-stat` one or else the peak.
+    fn memory(&self) -> &str {
+        match self.mem_stat {
+            MemStat::P99 => self.mem_p99.as_deref(),
+            MemStat::P95 => self.mem_p95.as_deref(),
+            MemStat::Peak => None,
+        }
+        .unwrap_or(&self.max_memory)
+    }
+
+    /// Header of `memory` across `reports`, which share their `--mem-stat`.
+    fn memory_header(reports: &[Report]) -> &'static str {
+        reports
+            .iter()
+            .map(|r| r.mem_stat)
+            .find(|stat| *stat != MemStat::Peak)
+            .unwrap_or(MemStat::Peak)
+            .header()
+    }
+
+    /// Header and rows of the comparison table, shared by every format.
+    /// With `grouped`, numbers get thousands separators.
+    fn table(reports: &[Report], grouped: bool) -> (Vec<&'static str>, Vec<Vec<String>>) {
+        let columns = OPTIONAL_COLUMNS
+            .iter()
+            .filter(|(_, cell)| reports.iter().any(|r| cell(r).is_some()))
+            .collect::<Vec<_>>();
+
+        let mut header = REPORT_HEADER.to_vec();
+        *header.last_mut().unwrap() = Self::memory_header(reports);
+        header.extend(columns.iter().map(|(name, _)| *name));
+
+        let rows = reports
+            .iter()
+            .map(|r| {
+                let mut row = vec![
+                    r.name(),
+                    r.metrics.latency.avg.clone(),
+                    r.metrics.latency.std_env.clone(),
+                    r.metrics.latency.min.clone(),
+                    r.metrics.latency.max.clone(),
+                    r.metrics.request.total.clone(),
+                    r.metrics.request.req_per_sec.clone(),
+                    r.metrics.transfer.total.clone(),
+                    r.metrics.transfer.rate.clone(),
+                    r.memory().to_string(),
+                ];
+                row.extend(columns.iter().map(|(_, cell)| cell(r).unwrap_or_default()));
+                if grouped {
+                    // the framework name is left alone, it may well be a number.
+                    row[1..]
+                        .iter_mut()
+                        .for_each(|cell| *cell = format_number(cell));
+                }
+                row
+            })
+            .collect();
+
+        (header, rows)
+    }
+
+    pub fn generate_from(reports: &[Report], grouped: bool) -> String {
         let mut res = String::new();
 
-        res.push_str(REPORT_HEADER);
-        res.push_str(TABLE_SEPARATOR);
+        let (header, rows) = Self::table(reports, grouped);
 
-        for r in reports {
-            let row = format!("|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|",
-                              r.framework_name,
-                              r.metrics.latency.avg,
-                              r.metrics.latency.std_env,
-                              r.metrics.latency.min,
-                              r.metrics.latency.max,
-                              r.metrics.request.total,
-                              r.metrics.request.req_per_sec,
-                              r.metrics.transfer.total,
-                              r.metrics.transfer.rate,
-                              r.max_memory);
-            res.push_str(&row);
+        res.push_str(&format!("| {} |\n", header.join(" | ")));
+        res.push('|');
+        res.push_str(&"---|".repeat(header.len()));
+        res.push('\n');
+
+        for (report, mut row) in reports.iter().zip(rows) {
+            // tags as inline code badges next to the name.
+            for tag in &report.tags {
+                row[0].push_str(&format!(" `{}`", tag));
+            }
+            res.push_str(&format!("|{}|", row.join("|")));
             res.push('\n');
         }
 
@@ -51,16 +484,490 @@ impl Report {
 
         res
     }
+
+    /// The comparison table as plain text, columns padded to align in a
+    /// monospace terminal.
+    pub fn generate_text(reports: &[Report], grouped: bool) -> String {
+        let (header, rows) = Self::table(reports, grouped);
+        let header = header.iter().map(|h| h.to_string()).collect::<Vec<_>>();
+
+        let widths = header
+            .iter()
+            .enumerate()
+            .map(|(i, h)| {
+                rows.iter()
+                    .map(|row| row[i].chars().count())
+                    .chain([h.chars().count()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+
+        let line = |cells: &[String]| {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+        let rule = widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>();
+
+        let mut lines = vec![line(&header), line(&rule)];
+        lines.extend(rows.iter().map(|row| line(row)));
+
+        lines.join("\n")
+    }
+
+    /// The comparison table for `--format html`, sortable by any column,
+    /// followed by charts of Req/Sec and memory.
+    pub fn generate_html(reports: &[Report], grouped: bool) -> String {
+        let (header, rows) = Self::table(reports, grouped);
+        let bars = |value: fn(&Report) -> Option<f64>| {
+            reports
+                .iter()
+                .filter_map(|r| Some((r.name(), value(r)?)))
+                .collect::<Vec<_>>()
+        };
+        let req_per_sec = bars(|r| r.metrics.req_per_sec());
+        let memory = bars(|r| r.memory().trim_end_matches("MB").parse().ok());
+
+        let mut res = html::sortable_table(&header, &rows);
+        if !req_per_sec.is_empty() {
+            res.push('\n');
+            res.push_str(&html::bar_chart("Requests/sec", &req_per_sec, ""));
+        }
+        if !memory.is_empty() {
+            res.push('\n');
+            res.push_str(&html::bar_chart(
+                Self::memory_header(reports),
+                &memory,
+                "MB",
+            ));
+        }
+
+        res
+    }
+
+    /// Categories whose largest sample response is more than `tolerance`
+    /// larger than the smallest, where throughput isn't comparable: serving
+    /// more bytes costs more.
+    pub fn uneven_responses(reports: &[Report], tolerance: f64) -> Vec<String> {
+        let mut categories = Vec::<&str>::new();
+        for r in reports {
+            if !categories.contains(&r.bench_type.as_str()) {
+                categories.push(&r.bench_type);
+            }
+        }
+
+        categories
+            .into_iter()
+            .filter_map(|category| {
+                let sized = reports
+                    .iter()
+                    .filter(|r| r.bench_type == category)
+                    .filter_map(|r| Some((r, r.response_size?)));
+                let smallest = sized.clone().min_by_key(|(_, size)| *size)?;
+                let largest = sized.max_by_key(|(_, size)| *size)?;
+
+                (largest.1 as f64 > smallest.1 as f64 * (1.0 + tolerance)).then(|| {
+                    format!(
+                        "Response sizes differ in {}: {} answers {} bytes, {} {} bytes. The \
+                         comparison is not apples-to-apples.",
+                        category,
+                        smallest.0.name(),
+                        smallest.1,
+                        largest.0.name(),
+                        largest.1
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Every report as a CSV row under `CSV_HEADER`, followed by a row per
+    /// step of its ramp and per cell of its matrix. Values are plain
+    /// numbers in the unit of their column, unknown ones left empty.
+    pub fn generate_csv(reports: &[Report]) -> String {
+        let mut lines = vec![CSV_HEADER.join(",")];
+        let number =
+            |value: Option<f64>| value.map_or(String::new(), |value| format!("{:.2}", value));
+        let mut push = |row: [String; 13]| {
+            lines.push(
+                row.iter()
+                    .map(|field| escape_csv(field))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            )
+        };
+
+        for r in reports {
+            push([
+                r.bench_type.clone(),
+                r.name(),
+                "run".to_string(),
+                r.connections.to_string(),
+                String::new(),
+                if r.metrics.request.req_per_sec == MISSING {
+                    String::new()
+                } else {
+                    r.metrics.request.req_per_sec.clone()
+                },
+                number(megabytes(&r.metrics.transfer.total)),
+                number(megabytes(&r.metrics.transfer.rate)),
+                r.max_memory.trim_end_matches("MB").to_string(),
+                r.core_usage
+                    .map_or(String::new(), |usage| format!("{:.1}", usage.mean)),
+                number(r.metrics.latency.p50.as_deref().and_then(latency_ms)),
+                number(r.metrics.latency.p99.as_deref().and_then(latency_ms)),
+                if r.timings.startup.is_zero() {
+                    String::new()
+                } else {
+                    r.timings.startup.as_millis().to_string()
+                },
+            ]);
+
+            let step = |phase: &str,
+                        connections: usize,
+                        duration: Option<usize>,
+                        req_per_sec: Option<f64>,
+                        memory_mb: Option<f64>| {
+                [
+                    r.bench_type.clone(),
+                    r.name(),
+                    phase.to_string(),
+                    connections.to_string(),
+                    duration.map_or(String::new(), |duration| duration.to_string()),
+                    number(req_per_sec),
+                    String::new(),
+                    String::new(),
+                    memory_mb.map_or(String::new(), |mb| format!("{:.1}", mb)),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                ]
+            };
+            for point in r.ramp.iter().flat_map(|ramp| ramp.points()) {
+                push(step(
+                    "ramp",
+                    point.connections,
+                    None,
+                    Some(point.req_per_sec),
+                    point.memory_mb,
+                ));
+            }
+            for (connections, duration, req_per_sec, memory_mb) in
+                r.matrix.iter().flat_map(|matrix| matrix.cells())
+            {
+                push(step(
+                    "matrix",
+                    connections,
+                    Some(duration),
+                    req_per_sec,
+                    memory_mb,
+                ));
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    /// Median and mean Req/Sec and memory of each language, then the best
+    /// framework of each language in every benchmark category, by Req/Sec.
+    pub fn generate_languages(reports: &[Report], grouped: bool) -> String {
+        let number = |value: Option<f64>, unit: &str| {
+            value.map_or(MISSING.to_string(), |value| {
+                let value = format!("{:.2}", value);
+                let value = if grouped {
+                    format_number(&value)
+                } else {
+                    value
+                };
+                format!("{}{}", value, unit)
+            })
+        };
+        let memory = |r: &Report| r.memory().trim_end_matches("MB").parse::<f64>().ok();
+
+        let mut res = String::new();
+
+        res.push_str("| Language | Frameworks | Req/Sec.Median | Req/Sec.Mean | Memory.Median | Memory.Mean |\n");
+        res.push_str("|---|---|---|---|---|---|\n");
+        for lang in Language::ALL {
+            let of_lang = reports
+                .iter()
+                .filter(|r| r.language == lang)
+                .collect::<Vec<_>>();
+            let req_per_sec = of_lang
+                .iter()
+                .filter_map(|r| r.metrics.req_per_sec())
+                .collect::<Vec<_>>();
+            let memories = of_lang.iter().filter_map(|r| memory(r)).collect::<Vec<_>>();
+
+            res.push_str(&format!(
+                "|{}|{}|{}|{}|{}|{}|\n",
+                lang,
+                of_lang.len(),
+                number(stats::median(&req_per_sec), ""),
+                number(stats::mean(&req_per_sec), ""),
+                number(stats::median(&memories), "MB"),
+                number(stats::mean(&memories), "MB"),
+            ));
+        }
+
+        let mut categories = reports
+            .iter()
+            .map(|r| r.bench_type.as_str())
+            .collect::<Vec<_>>();
+        categories.sort_unstable();
+        categories.dedup();
+
+        res.push('\n');
+        res.push_str("| Category | Best Rust | Best Go |\n");
+        res.push_str("|---|---|---|\n");
+        for category in categories {
+            let best = |lang: Language| {
+                reports
+                    .iter()
+                    .filter(|r| r.bench_type == category && r.language == lang)
+                    .filter_map(|r| Some((r, r.metrics.req_per_sec()?)))
+                    .max_by(|a, b| a.1.total_cmp(&b.1))
+                    .map_or(MISSING.to_string(), |(r, rps)| {
+                        format!("{} ({})", r.name(), number(Some(rps), ""))
+                    })
+            };
+            res.push_str(&format!(
+                "|{}|{}|{}|\n",
+                category,
+                best(Language::Rust),
+                best(Language::Go)
+            ));
+        }
+
+        res.pop(); // drop last '\n'
+
+        res
+    }
 }
 
-#[derive(PartialEq, Debug)]
+/// Insert thousands separators into a plain number, e.g. `1234567.89` to
+/// `1,234,567.89`. Anything else, e.g. `0.50ms`, is returned as is.
+fn format_number(value: &str) -> String {
+    let (int, frac) = value
+        .split_once('.')
+        .map_or((value, None), |(i, f)| (i, Some(f)));
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !is_digits(int) || !frac.is_none_or(is_digits) {
+        return value.to_string();
+    }
+
+    let mut res = String::new();
+    for (i, digit) in int.chars().enumerate() {
+        if i > 0 && (int.len() - i) % 3 == 0 {
+            res.push(',');
+        }
+        res.push(digit);
+    }
+    if let Some(frac) = frac {
+        res.push('.');
+        res.push_str(frac);
+    }
+
+    res
+}
+
+/// Quote a field containing a separator, quote or line break, doubling its
+/// quotes.
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Standard deviation over mean of `samples`, `None` for fewer than two
+/// samples.
+pub fn coefficient_of_variation(samples: &[f64]) -> Option<f64> {
+    if samples.len() < 2 {
+        return None;
+    }
+
+    let len = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / len;
+    let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (len - 1.0);
+
+    Some(variance.sqrt() / mean)
+}
+
+/// Whether the last two of `samples` are within `tolerance` (e.g. `0.03`
+/// for 3%) of each other, relative to the earlier one.
+pub fn converged(samples: &[f64], tolerance: f64) -> bool {
+    match samples {
+        [.., previous, last] => (last - previous).abs() <= previous.abs() * tolerance,
+        _ => false,
+    }
+}
+
+#[derive(PartialEq, Debug, Serialize)]
 pub struct Metrics {
     latency: Latency,
     request: Request,
     transfer: Transfer,
+    /// Failed requests, `None` when the tool doesn't report them.
+    errors: Option<u64>,
+    /// Requests of `errors` that timed out, `None` when the tool doesn't
+    /// tell them apart.
+    timeouts: Option<u64>,
+    /// TCP connections the tool opened, `None` when it doesn't report them.
+    connections_opened: Option<u64>,
+    /// Time to first byte, `None` when the tool doesn't tell it apart
+    /// from the full response.
+    ttfb: Option<Ttfb>,
+    /// Latencies in milliseconds, `None` when missing or in an unknown unit.
+    lat_min: Option<f64>,
+    lat_avg: Option<f64>,
+    lat_max: Option<f64>,
+}
+
+impl Metrics {
+    pub fn req_per_sec(&self) -> Option<f64> {
+        self.request.req_per_sec.parse().ok()
+    }
+
+    /// Successful requests, when the tool reports them.
+    pub fn total(&self) -> Option<u64> {
+        self.request.total.parse().ok()
+    }
+
+    pub fn timeouts(&self) -> Option<u64> {
+        self.timeouts
+    }
+
+    /// Failed over attempted requests, when both are known.
+    pub fn error_rate(&self) -> Option<f64> {
+        let errors = self.errors? as f64;
+        let total = self.request.total.parse::<f64>().ok()? + errors;
+        Some(if total > 0.0 { errors / total } else { 0.0 })
+    }
+
+    /// The percentiles from p99 up the tool reported, e.g.
+    /// `[("p99", "4.02ms"), ("p99.9", "12.50ms")]`.
+    pub fn tail_latency(&self) -> Vec<(&str, &str)> {
+        [
+            ("p99", &self.latency.p99),
+            ("p99.9", &self.latency.p999),
+            ("p99.99", &self.latency.p9999),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value.as_deref()?)))
+        .collect()
+    }
+
+    /// Time to first byte statistics the tool reported, e.g.
+    /// `[("avg", "0.31ms"), ("p99", "1.02ms")]`.
+    pub fn ttfb(&self) -> Vec<(&str, &str)> {
+        let Some(ttfb) = &self.ttfb else {
+            return Vec::new();
+        };
+
+        [("avg", &ttfb.avg), ("p50", &ttfb.p50), ("p99", &ttfb.p99)]
+            .into_iter()
+            .filter_map(|(name, value)| Some((name, value.as_deref()?)))
+            .collect()
+    }
+
+    /// Min, avg and max latency in milliseconds, when all are known.
+    pub fn latency_summary(&self) -> Option<(f64, f64, f64)> {
+        Some((self.lat_min?, self.lat_avg?, self.lat_max?))
+    }
+
+    /// Requests served per connection opened, when both are known. Close
+    /// to 1 means the server made the client reconnect for every request.
+    pub fn reuse_ratio(&self) -> Option<f64> {
+        let opened = self.connections_opened.filter(|opened| *opened > 0)? as f64;
+        Some(self.request.total.parse::<f64>().ok()? / opened)
+    }
+
+    /// Build from named values, as captured by a custom parser. Only `rps`
+    /// is required, anything else the tool doesn't report is shown as `-`.
+    pub fn from_captures(get: impl Fn(&str) -> Option<String>) -> Result<Self, MetricsError> {
+        let or_missing = |name: &str| get(name).unwrap_or_else(|| MISSING.to_string());
+        let latency = Latency {
+            avg: or_missing("avg"),
+            std_env: or_missing("stdev"),
+            min: or_missing("min"),
+            max: or_missing("max"),
+            p50: get("p50"),
+            p99: get("p99"),
+            p999: get("p999"),
+            p9999: get("p9999"),
+        };
+        let ttfb = Ttfb {
+            avg: get("ttfb_avg"),
+            p50: get("ttfb_p50"),
+            p99: get("ttfb_p99"),
+        };
+
+        Ok(Self {
+            lat_min: latency_ms(&latency.min),
+            lat_avg: latency_ms(&latency.avg),
+            lat_max: latency_ms(&latency.max),
+            latency,
+            request: Request {
+                total: or_missing("total"),
+                req_per_sec: get("rps").ok_or(MetricsError::ParseError)?,
+            },
+            transfer: Transfer {
+                total: or_missing("transfer"),
+                rate: or_missing("transfer_rate"),
+            },
+            errors: get("errors").and_then(|errors| errors.parse().ok()),
+            timeouts: get("timeouts").and_then(|timeouts| timeouts.parse().ok()),
+            connections_opened: get("opened").and_then(|opened| opened.parse().ok()),
+            ttfb: (ttfb != Ttfb::default()).then_some(ttfb),
+        })
+    }
+}
+
+/// Milliseconds of a latency as tools print it, e.g. `41.93ms`, `1.2s` or
+/// `850us`.
+fn latency_ms(value: &str) -> Option<f64> {
+    let split = value.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (number, unit) = value.split_at(split);
+    let number = number.parse::<f64>().ok()?;
+
+    match unit.trim() {
+        "s" => Some(number * 1000.0),
+        "ms" => Some(number),
+        "us" | "µs" => Some(number / 1000.0),
+        "ns" => Some(number / 1_000_000.0),
+        _ => None,
+    }
+}
+
+/// Megabytes of a size or rate as tools print it, e.g. `3.65GB` or
+/// `124.76MB/Sec`.
+fn megabytes(value: &str) -> Option<f64> {
+    let value = value.trim_end_matches("/Sec");
+    let split = value.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (number, unit) = value.split_at(split);
+    let number = number.parse::<f64>().ok()?;
+
+    match unit.trim() {
+        "B" => Some(number / (1024.0 * 1024.0)),
+        "KB" => Some(number / 1024.0),
+        "MB" => Some(number),
+        "GB" => Some(number * 1024.0),
+        "TB" => Some(number * 1024.0 * 1024.0),
+        _ => None,
+    }
 }
 
-// parse std output from rewrk result
+// parse std output from rewrk result, anchored on the section headers so
+// banners or warnings printed before the stats are skipped.
 // something like this:
 //      Beginning round 1...
 //      Benchmarking 500 connections @ http://127.0.0.1:3000 for 30 second(s)
@@ -71,30 +978,62 @@ pub struct Metrics {
 //          Total: 30178057 Req/Sec: 1006342.33
 //        Transfer:
 //          Total: 3.65 GB Transfer Rate: 124.76 MB/Sec
+//
+//      691 Errors: error shutting down connection: Socket is not connected (os error 57)
 impl FromStr for Metrics {
     type Err = MetricsError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines = s.trim().lines().collect::<Vec<&str>>();
+        let lines = s.lines().map(str::trim).collect::<Vec<&str>>();
 
-        if let [_, _, _, _, latency, _, request, _, transfer, ..] = lines[..] {
-            Ok(Self {
-                latency: latency.parse()?,
-                request: request.parse()?,
-                transfer: transfer.parse()?,
-            })
-        } else {
-            Err(MetricsError::ParseError)
-        }
+        // the line `offset` lines below `header`.
+        let section = |header: &str, offset: usize| {
+            lines
+                .iter()
+                .position(|line| *line == header)
+                .and_then(|index| lines.get(index + offset))
+                .ok_or(MetricsError::ParseError)
+        };
+
+        // "Latencies:" is followed by the column names, then the values.
+        let latency = section("Latencies:", 2)?.parse::<Latency>()?;
+
+        Ok(Self {
+            lat_min: latency_ms(&latency.min),
+            lat_avg: latency_ms(&latency.avg),
+            lat_max: latency_ms(&latency.max),
+            latency,
+            request: section("Requests:", 1)?.parse()?,
+            transfer: section("Transfer:", 1)?.parse()?,
+            // one "<count> Errors: <message>" line per kind of error.
+            errors: Some(
+                lines
+                    .iter()
+                    .filter_map(|line| line.split_once(" Errors: "))
+                    .filter_map(|(count, _)| count.parse::<u64>().ok())
+                    .sum(),
+            ),
+            // rewrk doesn't tell.
+            timeouts: None,
+            connections_opened: None,
+            ttfb: None,
+        })
     }
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Serialize)]
 struct Latency {
     avg: String,
     std_env: String,
     min: String,
     max: String,
+    /// Median, `None` when the tool doesn't report it.
+    p50: Option<String>,
+    p99: Option<String>,
+    /// 99.9th and 99.99th percentiles, `None` when the tool doesn't report
+    /// them.
+    p999: Option<String>,
+    p9999: Option<String>,
 }
 
 // parse for
@@ -103,8 +1042,7 @@ impl FromStr for Latency {
     type Err = MetricsError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
-        let parts = value.trim().split_whitespace()
-            .collect::<Vec<&str>>();
+        let parts = value.split_whitespace().collect::<Vec<&str>>();
 
         if let [avg, std_env, min, max, ..] = parts[..] {
             Ok(Self {
@@ -112,6 +1050,10 @@ impl FromStr for Latency {
                 std_env: std_env.to_string(),
                 min: min.to_string(),
                 max: max.to_string(),
+                p99: None,
+                p50: None,
+                p999: None,
+                p9999: None,
             })
         } else {
             Err(MetricsError::ParseError)
@@ -119,7 +1061,16 @@ impl FromStr for Latency {
     }
 }
 
-#[derive(PartialEq, Debug)]
+/// Time from sending a request to the first byte of its response, which
+/// is what a client waits for before it sees anything of a streamed body.
+#[derive(PartialEq, Debug, Default, Serialize)]
+struct Ttfb {
+    avg: Option<String>,
+    p50: Option<String>,
+    p99: Option<String>,
+}
+
+#[derive(PartialEq, Debug, Serialize)]
 struct Request {
     total: String,
     req_per_sec: String,
@@ -131,8 +1082,7 @@ impl FromStr for Request {
     type Err = MetricsError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
-        let parts = value.trim().split_whitespace()
-            .collect::<Vec<&str>>();
+        let parts = value.split_whitespace().collect::<Vec<&str>>();
 
         // ["Total:", total, "Req/Sec:", rps]
         if let [_, total, _, req_per_sec, ..] = parts[..] {
@@ -146,7 +1096,7 @@ impl FromStr for Request {
     }
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Serialize)]
 struct Transfer {
     total: String,
     rate: String,
@@ -158,8 +1108,7 @@ impl FromStr for Transfer {
     type Err = MetricsError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
-        let parts = value.trim().split_whitespace()
-            .collect::<Vec<&str>>();
+        let parts = value.split_whitespace().collect::<Vec<&str>>();
 
         // ["Total:", total, total_unit, "Transfer", "Rate:", rate, rate_unit]
         if let [_, total, total_unit, _, _, rate, rate_unit, ..] = parts[..] {
@@ -176,10 +1125,40 @@ impl FromStr for Transfer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ramp::RampPoint;
 
     mod report {
         use super::*;
 
+        #[test]
+        fn generate_html() {
+            let given = vec![Report::new(
+                "axum",
+                12.4,
+                r#"
+                     Beginning round 1...
+                     Benchmarking 500 connections @ http://127.0.0.1:3000 for 30 second(s)
+                       Latencies:
+                         Avg      Stdev    Min      Max
+                         0.72ms   0.36ms   0.03ms   17.55ms
+                       Requests:
+                         Total: 20765149 Req/Sec: 692354.35
+                       Transfer:
+                         Total: 2.51 GB Transfer Rate: 85.84 MB/Sec
+                "#
+                .parse()
+                .expect("parse metric fail"),
+            )];
+
+            let actual = Report::generate_html(&given, true);
+
+            assert!(actual.starts_with("<table class=\"sortable\">"));
+            assert!(actual.contains("<td>axum</td><td>0.72ms</td>"));
+            assert!(actual.contains(">Requests/sec</text>"));
+            assert!(actual.contains(">692354.35</text>"));
+            assert!(actual.contains(">12.40MB</text>"));
+        }
+
         #[test]
         fn generate() {
             let given = vec![
@@ -204,20 +1183,446 @@ mod tests {
                          Total: 20765149 Req/Sec: 692354.35
                        Transfer:
                          Total: 2.51 GB Transfer Rate: 85.84 MB/Sec
-                "#.parse().expect("parse metric fail")),
+                "#
+                    .parse()
+                    .expect("parse metric fail"),
+                ),
             ];
 
-            let actual = Report::generate_from(&given);
+            let actual = Report::generate_from(&given, true);
 
             let expect = r#"
 | Framework Name | Latency.Avg | Latency.Stdev | Latency.Min | Latency.Max | Request.Total | Request.Req/Sec | Transfer.Total | Transfer.Rate | Max. Memory Usage |
 |---|---|---|---|---|---|---|---|---|---|
-|actix-web|0.50ms|1.22ms|0.02ms|41.93ms|30178057|1006342.33|3.65GB|124.76MB/Sec|13.7MB|
-|axum|0.72ms|0.36ms|0.03ms|17.55ms|20765149|692354.35|2.51GB|85.84MB/Sec|12.4MB|
+|actix-web|0.50ms|1.22ms|0.02ms|41.93ms|30,178,057|1,006,342.33|3.65GB|124.76MB/Sec|13.7MB|
+|axum|0.72ms|0.36ms|0.03ms|17.55ms|20,765,149|692,354.35|2.51GB|85.84MB/Sec|12.4MB|
+"#.trim();
+
+            assert_eq!(actual, expect);
+        }
+
+        #[test]
+        fn generate_text() {
+            let given = vec![
+                Report::new(
+                    "actix-web",
+                    13.7,
+                    r#"
+                     Beginning round 1...
+                     Benchmarking 500 connections @ http://127.0.0.1:3000 for 30 second(s)
+                       Latencies:
+                         Avg      Stdev    Min      Max
+                         0.50ms   1.22ms   0.02ms   41.93ms
+                       Requests:
+                         Total: 30178057 Req/Sec: 1006342.33
+                       Transfer:
+                         Total: 3.65 GB Transfer Rate: 124.76 MB/Sec
+                "#
+                    .parse()
+                    .expect("parse metric fail"),
+                ),
+                Report::new(
+                    "axum",
+                    12.4,
+                    r#"
+                     Beginning round 1...
+                     Benchmarking 500 connections @ http://127.0.0.1:3000 for 30 second(s)
+                       Latencies:
+                         Avg      Stdev    Min      Max
+                         0.72ms   0.36ms   0.03ms   117.55ms
+                       Requests:
+                         Total: 20765149 Req/Sec: 692354.35
+                       Transfer:
+                         Total: 2.51 GB Transfer Rate: 85.84 MB/Sec
+                "#
+                    .parse()
+                    .expect("parse metric fail"),
+                ),
+            ];
+
+            let actual = Report::generate_text(&given, false);
+
+            let expect = r#"
+Framework Name  Latency.Avg  Latency.Stdev  Latency.Min  Latency.Max  Request.Total  Request.Req/Sec  Transfer.Total  Transfer.Rate  Max. Memory Usage
+--------------  -----------  -------------  -----------  -----------  -------------  ---------------  --------------  -------------  -----------------
+actix-web       0.50ms       1.22ms         0.02ms       41.93ms      30178057       1006342.33       3.65GB          124.76MB/Sec   13.7MB
+axum            0.72ms       0.36ms         0.03ms       117.55ms     20765149       692354.35        2.51GB          85.84MB/Sec    12.4MB
+"#.trim();
+
+            assert_eq!(actual, expect);
+        }
+
+        #[test]
+        fn generate_with_encoding() {
+            let metrics = r#"
+                     Beginning round 1...
+                     Benchmarking 500 connections @ http://127.0.0.1:3000 for 30 second(s)
+                       Latencies:
+                         Avg      Stdev    Min      Max
+                         0.50ms   1.22ms   0.02ms   41.93ms
+                       Requests:
+                         Total: 30178057 Req/Sec: 1006342.33
+                       Transfer:
+                         Total: 3.65 GB Transfer Rate: 124.76 MB/Sec
+                "#;
+            let given = vec![
+                Report::new(
+                    "actix-web",
+                    13.7,
+                    metrics.parse().expect("parse metric fail"),
+                )
+                .with_encoding(Some("gzip".to_string())),
+                Report::new("axum", 12.4, metrics.parse().expect("parse metric fail"))
+                    .with_encoding(Some("identity".to_string())),
+            ];
+
+            let actual = Report::generate_from(&given, false);
+
+            assert!(actual.contains("\n|actix-web (gzip)|"));
+            assert!(actual.contains("\n|axum (identity)|"));
+        }
+
+        #[test]
+        fn generate_with_tags() {
+            let metrics = r#"
+                     Beginning round 1...
+                     Benchmarking 500 connections @ http://127.0.0.1:3000 for 30 second(s)
+                       Latencies:
+                         Avg      Stdev    Min      Max
+                         0.50ms   1.22ms   0.02ms   41.93ms
+                       Requests:
+                         Total: 30178057 Req/Sec: 1006342.33
+                       Transfer:
+                         Total: 3.65 GB Transfer Rate: 124.76 MB/Sec
+                "#;
+            let given = vec![
+                Report::new(
+                    "actix-web",
+                    13.7,
+                    metrics.parse().expect("parse metric fail"),
+                )
+                .with_tags(vec!["full-stack".to_string(), "actix-rt".to_string()]),
+                Report::new("axum", 12.4, metrics.parse().expect("parse metric fail")),
+            ];
+
+            let actual = Report::generate_from(&given, false);
+
+            assert!(actual.contains("\n|actix-web `full-stack` `actix-rt`|"));
+            assert!(actual.contains("\n|axum|"));
+        }
+
+        #[test]
+        fn generate_with_idle_memory() {
+            let given = vec![Report::new(
+                "actix-web",
+                13.7,
+                r#"
+                     Beginning round 1...
+                     Benchmarking 500 connections @ http://127.0.0.1:3000 for 30 second(s)
+                       Latencies:
+                         Avg      Stdev    Min      Max
+                         0.50ms   1.22ms   0.02ms   41.93ms
+                       Requests:
+                         Total: 300000 Req/Sec: 10000.00
+                       Transfer:
+                         Total: 3.65 GB Transfer Rate: 124.76 MB/Sec
+                "#
+                .parse()
+                .expect("parse metric fail"),
+            )
+            .with_idle_memory(Some(4.2), 13.7)];
+
+            let actual = Report::generate_from(&given, false);
+
+            assert!(actual.contains("| Memory.Idle | Memory.Load Delta |"));
+            assert!(actual.contains("|13.7MB|4.2MB|+9.5MB|"));
+        }
+
+        #[test]
+        fn generate_with_mem_stat() {
+            let given = vec![Report::new(
+                "actix-web",
+                13.7,
+                r#"
+                     Beginning round 1...
+                     Benchmarking 500 connections @ http://127.0.0.1:3000 for 30 second(s)
+                       Latencies:
+                         Avg      Stdev    Min      Max
+                         0.50ms   1.22ms   0.02ms   41.93ms
+                       Requests:
+                         Total: 300000 Req/Sec: 10000.00
+                       Transfer:
+                         Total: 3.65 GB Transfer Rate: 124.76 MB/Sec
+                "#
+                .parse()
+                .expect("parse metric fail"),
+            )
+            .with_mem_stat(Some((MemStat::P99, 11.2)))];
+
+            let actual = Report::generate_from(&given, false);
+
+            assert!(actual.contains("| Memory Usage (p99) | Memory.Peak |"));
+            assert!(actual.contains("|11.2MB|13.7MB|"));
+        }
+
+        #[test]
+        fn generate_with_connection_rate() {
+            let given = vec![Report::new(
+                "actix-web",
+                13.7,
+                r#"
+                     Beginning round 1...
+                     Benchmarking 500 connections @ http://127.0.0.1:3000 for 30 second(s)
+                       Latencies:
+                         Avg      Stdev    Min      Max
+                         0.50ms   1.22ms   0.02ms   41.93ms
+                       Requests:
+                         Total: 300000 Req/Sec: 10000.00
+                       Transfer:
+                         Total: 3.65 GB Transfer Rate: 124.76 MB/Sec
+                "#
+                .parse()
+                .expect("parse metric fail"),
+            )
+            .with_connection_rate(30)];
+
+            let actual = Report::generate_from(&given, false);
+
+            let expect = r#"
+| Framework Name | Latency.Avg | Latency.Stdev | Latency.Min | Latency.Max | Request.Total | Request.Req/Sec | Transfer.Total | Transfer.Rate | Max. Memory Usage | Conn/Sec |
+|---|---|---|---|---|---|---|---|---|---|---|
+|actix-web|0.50ms|1.22ms|0.02ms|41.93ms|300000|10000.00|3.65GB|124.76MB/Sec|13.7MB|10000.00|
+"#.trim();
+
+            assert_eq!(actual, expect);
+        }
+
+        #[test]
+        fn generate_csv() {
+            let given = vec![Report::new(
+                "actix-web",
+                13.7,
+                r#"
+                     Beginning round 1...
+                     Benchmarking 500 connections @ http://127.0.0.1:3000 for 30 second(s)
+                       Latencies:
+                         Avg      Stdev    Min      Max
+                         0.50ms   1.22ms   0.02ms   41.93ms
+                       Requests:
+                         Total: 30178057 Req/Sec: 1006342.33
+                       Transfer:
+                         Total: 3.65 GB Transfer Rate: 124.76 MB/Sec
+                "#
+                .parse()
+                .expect("parse metric fail"),
+            )
+            .with_bench_type("hello-world")
+            .with_connections(500)
+            .with_ramp(Some({
+                let mut ramp = Ramp::new(0.05);
+                ramp.push(RampPoint {
+                    connections: 250,
+                    req_per_sec: 900000.0,
+                    memory_mb: Some(12.5),
+                });
+                ramp.push(RampPoint {
+                    connections: 500,
+                    req_per_sec: 1006342.33,
+                    memory_mb: None,
+                });
+                ramp
+            }))
+            .with_matrix(Some(Matrix::sweep(&[500], &[10, 30], |_, duration| {
+                (Some(duration as f64 * 1000.0), None)
+            })))
+            .with_timings(Timings {
+                startup: std::time::Duration::from_millis(1200),
+                ..Timings::default()
+            })];
+
+            let actual = Report::generate_csv(&given);
+
+            let expect = r#"
+bench_type,framework,phase,connections,duration_secs,req_per_sec,transfer_total_mb,transfer_rate_mb_per_sec,max_memory_mb,cpu_mean_percent,latency_p50_ms,latency_p99_ms,startup_ms
+hello-world,actix-web,run,500,,1006342.33,3737.60,124.76,13.7,,,,1200
+hello-world,actix-web,ramp,250,,900000.00,,,12.5,,,,
+hello-world,actix-web,ramp,500,,1006342.33,,,,,,,
+hello-world,actix-web,matrix,500,10,10000.00,,,,,,,
+hello-world,actix-web,matrix,500,30,30000.00,,,,,,,
 "#.trim();
 
             assert_eq!(actual, expect);
         }
+
+        #[test]
+        fn generate_languages() {
+            let metrics = |req_per_sec: &str| {
+                format!(
+                    "Latencies:\n  Avg Stdev Min Max\n  0.50ms 1.22ms 0.02ms 41.93ms\n\
+                     Requests:\n  Total: 100 Req/Sec: {}\n\
+                     Transfer:\n  Total: 3.65 GB Transfer Rate: 124.76 MB/Sec\n",
+                    req_per_sec
+                )
+                .parse::<Metrics>()
+                .expect("parse metric fail")
+            };
+            let given = vec![
+                Report::new("axum", 12.0, metrics("300.00")).with_bench_type("hello-world"),
+                Report::new("warp", 14.0, metrics("100.00")).with_bench_type("hello-world"),
+                Report::new("go_nethttp", 20.0, metrics("200.00"))
+                    .with_bench_type("hello-world")
+                    .with_language(Language::Go),
+            ];
+
+            let actual = Report::generate_languages(&given, false);
+
+            let expect = r#"
+| Language | Frameworks | Req/Sec.Median | Req/Sec.Mean | Memory.Median | Memory.Mean |
+|---|---|---|---|---|---|
+|Rust|2|200.00|200.00|13.00MB|13.00MB|
+|Go|1|200.00|200.00|20.00MB|20.00MB|
+
+| Category | Best Rust | Best Go |
+|---|---|---|
+|hello-world|axum (300.00)|go_nethttp (200.00)|
+"#
+            .trim();
+
+            assert_eq!(actual, expect);
+        }
+    }
+
+    mod uneven_responses {
+        use super::*;
+
+        fn sized(name: &str, bench_type: &str, size: Option<usize>) -> Report {
+            let metrics = "Latencies:\n  Avg Stdev Min Max\n  0.50ms 1.22ms 0.02ms 41.93ms\n\
+                           Requests:\n  Total: 100 Req/Sec: 100.00\n\
+                           Transfer:\n  Total: 3.65 GB Transfer Rate: 124.76 MB/Sec\n";
+            Report::new(name, 13.7, metrics.parse().expect("parse metric fail"))
+                .with_bench_type(bench_type)
+                .with_response_size(size)
+        }
+
+        #[test]
+        fn ok() {
+            let given = vec![
+                sized("actix-web", "hello-world", Some(13)),
+                sized("axum", "hello-world", Some(40)),
+                sized("warp", "hello-world", None),
+                sized("actix-web", "json", Some(27)),
+                sized("axum", "json", Some(30)),
+            ];
+
+            assert_eq!(
+                Report::uneven_responses(&given, 0.5),
+                ["Response sizes differ in hello-world: actix-web answers 13 bytes, axum 40 bytes. \
+                  The comparison is not apples-to-apples."]
+            );
+            assert!(Report::uneven_responses(&given, 3.0).is_empty());
+        }
+    }
+
+    mod megabytes {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            assert_eq!(megabytes("3.65GB"), Some(3737.6));
+            assert_eq!(megabytes("124.76MB/Sec"), Some(124.76));
+            assert_eq!(megabytes("512KB/Sec"), Some(0.5));
+            assert_eq!(megabytes(MISSING), None);
+        }
+    }
+
+    mod latency_ms {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            assert_eq!(latency_ms("41.93ms"), Some(41.93));
+            assert_eq!(latency_ms("1.5s"), Some(1500.0));
+            assert_eq!(latency_ms("850us"), Some(0.85));
+            assert_eq!(latency_ms(MISSING), None);
+            assert_eq!(latency_ms("12"), None);
+        }
+
+        #[test]
+        fn summary() {
+            let given = r#"
+  Latencies:
+    Avg      Stdev    Min      Max
+    0.50ms   1.22ms   20us     1.25s
+  Requests:
+    Total: 30178057 Req/Sec: 1006342.33
+  Transfer:
+    Total: 3.65 GB Transfer Rate: 124.76 MB/Sec
+            "#;
+
+            let actual = given.parse::<Metrics>().unwrap().latency_summary();
+
+            assert_eq!(actual, Some((0.02, 0.5, 1250.0)));
+        }
+    }
+
+    mod format_number {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            assert_eq!(format_number("1234567"), "1,234,567");
+            assert_eq!(format_number("692354.35"), "692,354.35");
+            assert_eq!(format_number("100"), "100");
+        }
+
+        #[test]
+        fn not_a_number() {
+            assert_eq!(format_number("0.50ms"), "0.50ms");
+            assert_eq!(format_number("-"), "-");
+            assert_eq!(format_number("185000.00@32"), "185000.00@32");
+        }
+    }
+
+    mod escape_csv {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            assert_eq!(escape_csv("axum"), "axum");
+            assert_eq!(escape_csv("axum (gzip, br)"), "\"axum (gzip, br)\"");
+            assert_eq!(escape_csv("say \"hi\""), "\"say \"\"hi\"\"\"");
+        }
+    }
+
+    mod coefficient_of_variation {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let actual = coefficient_of_variation(&[90.0, 100.0, 110.0]).unwrap();
+
+            assert!((actual - 0.1).abs() < 1e-9);
+        }
+
+        #[test]
+        fn single_sample() {
+            assert_eq!(coefficient_of_variation(&[100.0]), None);
+        }
+    }
+
+    mod converged {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            assert!(converged(&[80.0, 100.0, 102.0], 0.03));
+            assert!(!converged(&[100.0, 80.0, 102.0], 0.03));
+        }
+
+        #[test]
+        fn single_sample() {
+            assert!(!converged(&[100.0], 0.03));
+        }
     }
 
     mod metrics {
@@ -240,23 +1645,138 @@ Benchmarking 500 connections @ http://127.0.0.1:3000 for 30 second(s)
             "#;
             let actual = given.parse::<Metrics>();
 
-            let expect = Ok(
-                Metrics {
-                    latency: Latency {
-                        avg: "0.50ms".to_string(),
-                        std_env: "1.22ms".to_string(),
-                        min: "0.02ms".to_string(),
-                        max: "41.93ms".to_string(),
-                    },
-                    request: Request {
-                        total: "30178057".to_string(),
-                        req_per_sec: "1006342.33".to_string(),
-                    },
-                    transfer: Transfer {
-                        total: "3.65GB".to_string(),
-                        rate: "124.76MB/Sec".to_string(),
-                    },
-                });
+            let expect = Ok(Metrics {
+                latency: Latency {
+                    avg: "3.56ms".to_string(),
+                    std_env: "0.97ms".to_string(),
+                    min: "0.11ms".to_string(),
+                    max: "114.54ms".to_string(),
+                    p99: None,
+                    p50: None,
+                    p999: None,
+                    p9999: None,
+                },
+                request: Request {
+                    total: "4206350".to_string(),
+                    req_per_sec: "140218.97".to_string(),
+                },
+                transfer: Transfer {
+                    total: "521.41MB".to_string(),
+                    rate: "17.38MB/Sec".to_string(),
+                },
+                errors: Some(691),
+                timeouts: None,
+                connections_opened: None,
+                ttfb: None,
+                lat_min: Some(0.11),
+                lat_avg: Some(3.56),
+                lat_max: Some(114.54),
+            });
+
+            assert_eq!(actual, expect);
+        }
+
+        #[test]
+        fn error_rate() {
+            let given = Metrics::from_captures(|name| match name {
+                "rps" => Some("100.00".to_string()),
+                "total" => Some("900".to_string()),
+                "errors" => Some("100".to_string()),
+                _ => None,
+            })
+            .unwrap();
+
+            assert_eq!(given.error_rate(), Some(0.1));
+        }
+
+        #[test]
+        fn tail_latency() {
+            let given = Metrics::from_captures(|name| match name {
+                "rps" => Some("100.00".to_string()),
+                "p99" => Some("4.02ms".to_string()),
+                "p9999" => Some("31.70ms".to_string()),
+                _ => None,
+            })
+            .unwrap();
+
+            assert_eq!(
+                given.tail_latency(),
+                [("p99", "4.02ms"), ("p99.99", "31.70ms")]
+            );
+        }
+
+        #[test]
+        fn ttfb() {
+            let given = Metrics::from_captures(|name| match name {
+                "rps" => Some("100.00".to_string()),
+                "ttfb_p50" => Some("0.20ms".to_string()),
+                "ttfb_p99" => Some("1.10ms".to_string()),
+                _ => None,
+            })
+            .unwrap();
+
+            assert_eq!(given.ttfb(), [("p50", "0.20ms"), ("p99", "1.10ms")]);
+
+            let given =
+                Metrics::from_captures(|name| (name == "rps").then(|| "100.00".to_string()))
+                    .unwrap();
+
+            assert_eq!(given.ttfb, None);
+        }
+
+        #[test]
+        fn reuse_ratio() {
+            let given = |opened: &str| {
+                let opened = opened.to_string();
+                Metrics::from_captures(move |name| match name {
+                    "rps" => Some("100.00".to_string()),
+                    "total" => Some("1000".to_string()),
+                    "opened" => Some(opened.clone()),
+                    _ => None,
+                })
+                .unwrap()
+            };
+
+            assert_eq!(given("4").reuse_ratio(), Some(250.0));
+            assert_eq!(given("0").reuse_ratio(), None);
+        }
+
+        #[test]
+        fn banner() {
+            let given = r#"
+rewrk v0.3.2 - HTTP benchmark tool
+warning: `-h` is deprecated, use `--host` instead
+Beginning round 1...
+Benchmarking 500 connections @ http://127.0.0.1:3000 for 30 second(s)
+  Latencies:
+    Avg      Stdev    Min      Max
+    3.56ms   0.97ms   0.11ms   114.54ms
+  Requests:
+    Total: 4206350 Req/Sec: 140218.97
+  Transfer:
+    Total: 521.41 MB Transfer Rate: 17.38 MB/Sec
+            "#;
+            let actual = given.parse::<Metrics>().map(|metrics| metrics.request);
+
+            let expect = Ok(Request {
+                total: "4206350".to_string(),
+                req_per_sec: "140218.97".to_string(),
+            });
+
+            assert_eq!(actual, expect);
+        }
+
+        #[test]
+        fn err() {
+            let given = r#"
+Beginning round 1...
+Benchmarking 500 connections @ http://127.0.0.1:3000 for 30 second(s)
+  Latencies:
+    Avg      Stdev    Min      Max
+    3.56ms   0.97ms   0.11ms   114.54ms
+            "#;
+            let actual = given.parse::<Metrics>();
+            let expect = Err(MetricsError::ParseError);
 
             assert_eq!(actual, expect);
         }
@@ -275,6 +1795,10 @@ Benchmarking 500 connections @ http://127.0.0.1:3000 for 30 second(s)
                 std_env: "1.22ms".to_string(),
                 min: "0.02ms".to_string(),
                 max: "41.93ms".to_string(),
+                p99: None,
+                p50: None,
+                p999: None,
+                p9999: None,
             });
 
             assert_eq!(actual, expect);
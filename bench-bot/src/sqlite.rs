@@ -0,0 +1,252 @@
+use crate::report::Report;
+use serde_json::Value;
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Schema changes in order, `PRAGMA user_version` of a database counts the
+/// ones it has had. Only ever append.
+const MIGRATIONS: [&str; 2] = [
+    "
+CREATE TABLE runs (
+    id INTEGER PRIMARY KEY,
+    started_at INTEGER NOT NULL,
+    git_hash TEXT,
+    command TEXT NOT NULL
+);
+CREATE TABLE frameworks (
+    id INTEGER PRIMARY KEY,
+    bench_type TEXT NOT NULL,
+    name TEXT NOT NULL,
+    language TEXT NOT NULL,
+    UNIQUE (bench_type, name)
+);
+CREATE TABLE metrics (
+    run_id INTEGER NOT NULL REFERENCES runs (id),
+    framework_id INTEGER NOT NULL REFERENCES frameworks (id),
+    measured_at INTEGER NOT NULL,
+    connections INTEGER NOT NULL,
+    req_per_sec REAL,
+    latency_avg_ms REAL,
+    latency_max_ms REAL,
+    max_memory_mb REAL,
+    errors INTEGER,
+    PRIMARY KEY (run_id, framework_id)
+);
+",
+    "
+ALTER TABLE runs ADD COLUMN label TEXT;
+",
+];
+
+/// Check that the `sqlite3` shell `--sqlite` runs is installed.
+pub fn check() -> Result<(), String> {
+    Command::new("sqlite3")
+        .arg("-version")
+        .stdout(Stdio::null())
+        .status()
+        .map(drop)
+        .map_err(|_| "--sqlite needs sqlite3, which was not found".to_string())
+}
+
+/// `--sqlite` database, with the run being recorded.
+pub struct Database {
+    path: PathBuf,
+    run_id: i64,
+}
+
+impl Database {
+    /// Create or migrate the database at `path` and record this run in it.
+    pub fn open(
+        path: &Path,
+        git_hash: Option<&str>,
+        label: Option<&str>,
+        command: &str,
+    ) -> Result<Self, String> {
+        let version = execute(path, "PRAGMA user_version;")?;
+        let version = version
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| format!("unexpected schema version {:?}", version.trim()))?;
+        if version > MIGRATIONS.len() {
+            return Err(format!(
+                "{:?} has schema version {}, newer than the {} of this bench-bot",
+                path,
+                version,
+                MIGRATIONS.len()
+            ));
+        }
+
+        let run = format!(
+            "{}INSERT INTO runs (started_at, git_hash, label, command) VALUES ({}, {}, {}, {});\n\
+             SELECT last_insert_rowid();",
+            migration_script(version),
+            now(),
+            git_hash.map_or("NULL".to_string(), text),
+            label.map_or("NULL".to_string(), text),
+            text(command)
+        );
+        let run_id = execute(path, &run)?;
+        let run_id = run_id
+            .trim()
+            .parse()
+            .map_err(|_| format!("unexpected run id {:?}", run_id.trim()))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            run_id,
+        })
+    }
+
+    pub fn insert(&self, report: &Report) -> Result<(), String> {
+        let report = serde_json::to_value(report).map_err(|e| e.to_string())?;
+        let sql = insert_sql(self.run_id, &report, now())
+            .ok_or_else(|| "the report has no category or framework name".to_string())?;
+
+        execute(&self.path, &sql).map(drop)
+    }
+}
+
+/// Migrations after the first `from`, in a single transaction.
+fn migration_script(from: usize) -> String {
+    if from == MIGRATIONS.len() {
+        return String::new();
+    }
+
+    let mut script = String::from("BEGIN;\n");
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(from) {
+        script.push_str(migration.trim());
+        script.push_str(&format!("\nPRAGMA user_version = {};\n", version + 1));
+    }
+    script.push_str("COMMIT;\n");
+
+    script
+}
+
+/// Statements recording `report`, as serialized into the JSON output, for
+/// the run `run_id`.
+fn insert_sql(run_id: i64, report: &Value, measured_at: u64) -> Option<String> {
+    let bench_type = text(report["bench_type"].as_str()?);
+    let name = text(report["framework_name"].as_str()?);
+    let number = |value: &Value| {
+        let number = match value {
+            Value::String(value) => value.trim_end_matches("MB").parse().ok(),
+            value => value.as_f64(),
+        };
+        number.map_or("NULL".to_string(), |number: f64| number.to_string())
+    };
+    let metrics = &report["metrics"];
+
+    Some(format!(
+        "INSERT OR IGNORE INTO frameworks (bench_type, name, language) VALUES ({}, {}, {});\n\
+         INSERT OR REPLACE INTO metrics (run_id, framework_id, measured_at, connections, \
+         req_per_sec, latency_avg_ms, latency_max_ms, max_memory_mb, errors)\n\
+         SELECT {}, id, {}, {}, {}, {}, {}, {}, {} FROM frameworks WHERE bench_type = {} AND name = {};",
+        bench_type,
+        name,
+        text(report["language"].as_str().unwrap_or_default()),
+        run_id,
+        measured_at,
+        report["connections"].as_u64().unwrap_or_default(),
+        number(&metrics["request"]["req_per_sec"]),
+        number(&metrics["lat_avg"]),
+        number(&metrics["lat_max"]),
+        number(&report["max_memory"]),
+        number(&metrics["errors"]),
+        bench_type,
+        name
+    ))
+}
+
+/// SQL string literal.
+fn text(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+/// Run `sql` against the database at `path`, stopping at the first error.
+fn execute(path: &Path, sql: &str) -> Result<String, String> {
+    let mut sqlite = Command::new("sqlite3")
+        .arg("-batch")
+        .arg("-bail")
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not run sqlite3: {}", e))?;
+    sqlite
+        .stdin
+        .take()
+        .expect("piped stdin")
+        .write_all(sql.as_bytes())
+        .map_err(|e| format!("could not write to sqlite3: {}", e))?;
+
+    let output = sqlite
+        .wait_with_output()
+        .map_err(|e| format!("sqlite3 failed: {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod migration_script {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let actual = migration_script(0);
+
+            assert!(actual.starts_with("BEGIN;\nCREATE TABLE runs ("));
+            assert!(actual.ends_with(
+                "ALTER TABLE runs ADD COLUMN label TEXT;\nPRAGMA user_version = 2;\nCOMMIT;\n"
+            ));
+            assert!(migration_script(1).starts_with("BEGIN;\nALTER TABLE runs"));
+            assert_eq!(migration_script(MIGRATIONS.len()), "");
+        }
+    }
+
+    mod insert_sql {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = serde_json::json!({
+                "bench_type": "hello-world",
+                "framework_name": "o'axum",
+                "language": "Rust",
+                "connections": 500,
+                "max_memory": "12.4MB",
+                "metrics": {
+                    "request": { "req_per_sec": "1006342.33" },
+                    "lat_avg": 0.5,
+                    "lat_max": 41.93,
+                    "errors": null,
+                },
+            });
+
+            let actual = insert_sql(3, &given, 1659357000).unwrap();
+
+            let expect = "\
+INSERT OR IGNORE INTO frameworks (bench_type, name, language) VALUES ('hello-world', 'o''axum', 'Rust');
+INSERT OR REPLACE INTO metrics (run_id, framework_id, measured_at, connections, req_per_sec, latency_avg_ms, latency_max_ms, max_memory_mb, errors)
+SELECT 3, id, 1659357000, 500, 1006342.33, 0.5, 41.93, 12.4, NULL FROM frameworks WHERE bench_type = 'hello-world' AND name = 'o''axum';";
+
+            assert_eq!(actual, expect);
+        }
+    }
+}
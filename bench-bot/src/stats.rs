@@ -0,0 +1,58 @@
+/// Nearest-rank percentile of `sorted`, ascending, `p` in `0.0..=1.0`.
+pub fn percentile<T: Copy>(sorted: &[T], p: f64) -> Option<T> {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.clamp(1, sorted.len().max(1)) - 1).copied()
+}
+
+/// Median of `values`, the mean of the two middle ones for an even count.
+pub fn median(values: &[f64]) -> Option<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    let mid = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((sorted[mid - 1] + sorted[mid]) / 2.0),
+        _ => Some(sorted[mid]),
+    }
+}
+
+pub fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod percentile {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = (1..=100).collect::<Vec<u64>>();
+
+            assert_eq!(percentile(&given, 0.5), Some(50));
+            assert_eq!(percentile(&given, 0.99), Some(99));
+            assert_eq!(percentile(&given, 0.0), Some(1));
+            assert_eq!(percentile(&[7], 0.99), Some(7));
+            assert_eq!(percentile::<u64>(&[], 0.5), None);
+        }
+    }
+
+    mod median {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            assert_eq!(median(&[3.0, 1.0, 2.0]), Some(2.0));
+            assert_eq!(median(&[4.0, 1.0, 3.0, 2.0]), Some(2.5));
+            assert_eq!(median(&[]), None);
+            assert_eq!(mean(&[1.0, 2.0, 6.0]), Some(3.0));
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use crate::report::Report;
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+/// Where `--stream` sends every report the moment it is measured, one JSON
+/// object per line.
+pub enum Sink {
+    Stdout,
+    #[cfg(unix)]
+    Socket(std::os::unix::net::UnixStream),
+}
+
+impl Sink {
+    /// Stdout, or the Unix socket a dashboard listens on.
+    pub fn open(socket: Option<&Path>) -> io::Result<Self> {
+        match socket {
+            None => Ok(Sink::Stdout),
+            #[cfg(unix)]
+            Some(path) => std::os::unix::net::UnixStream::connect(path).map(Sink::Socket),
+            #[cfg(not(unix))]
+            Some(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "--stream-socket needs Unix sockets",
+            )),
+        }
+    }
+
+    pub fn emit(&mut self, report: &Report) -> io::Result<()> {
+        let line = line(report);
+
+        match self {
+            Sink::Stdout => {
+                let mut stdout = io::stdout().lock();
+                stdout.write_all(line.as_bytes())?;
+                stdout.flush()
+            }
+            #[cfg(unix)]
+            Sink::Socket(socket) => socket.write_all(line.as_bytes()),
+        }
+    }
+}
+
+fn line(report: &Report) -> String {
+    let mut line = serde_json::to_string(report).expect("serialize report");
+    line.push('\n');
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod line {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let metrics = r#"
+  Latencies:
+    Avg      Stdev    Min      Max
+    0.50ms   1.22ms   0.02ms   41.93ms
+  Requests:
+    Total: 30178057 Req/Sec: 1006342.33
+  Transfer:
+    Total: 3.65 GB Transfer Rate: 124.76 MB/Sec
+            "#;
+            let given = Report::new("axum", 12.4, metrics.parse().unwrap());
+
+            let actual = line(&given);
+
+            assert!(actual.ends_with('\n'));
+            assert_eq!(actual.matches('\n').count(), 1);
+            let value = serde_json::from_str::<serde_json::Value>(&actual).unwrap();
+            assert_eq!(value["framework_name"], "axum");
+        }
+    }
+}
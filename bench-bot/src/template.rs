@@ -0,0 +1,169 @@
+/// Layout of the markdown reports unless `--template` is given.
+pub const DEFAULT: &str = include_str!("template.md");
+
+/// Placeholders a template may use, each written `{{name}}`.
+pub const PLACEHOLDERS: [&str; 7] = [
+    "generated_by",
+    "hardware",
+    "command",
+    "methodology",
+    "comparison_table",
+    "websocket",
+    "per_framework",
+];
+
+/// Check that `template` only uses known placeholders.
+pub fn check(template: &str) -> Result<(), String> {
+    let unknown = template
+        .split_inclusive('\n')
+        .flat_map(pieces)
+        .filter_map(|piece| match piece {
+            Piece::Placeholder(name) if !PLACEHOLDERS.contains(&name) => Some(name),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "unknown placeholder(s) {:?}, known ones are {:?}",
+            unknown, PLACEHOLDERS
+        ))
+    }
+}
+
+/// Replace every `{{name}}` of `template` by its value, in a single pass
+/// so that values are never substituted into. A line holding only
+/// placeholders that are all empty is dropped along with the blank line
+/// after it, so that optional sections leave no gap.
+pub fn render(template: &str, values: &[(&str, &str)]) -> String {
+    let mut res = String::new();
+    let mut lines = template.split_inclusive('\n').peekable();
+
+    while let Some(line) = lines.next() {
+        let pieces = pieces(line);
+        let mut rendered = String::new();
+        for piece in &pieces {
+            match piece {
+                Piece::Text(text) => rendered.push_str(text),
+                Piece::Placeholder(name) => match values.iter().find(|(key, _)| key == name) {
+                    Some((_, value)) => rendered.push_str(value.trim()),
+                    None => rendered.push_str(&format!("{{{{{}}}}}", name)),
+                },
+            }
+        }
+
+        let only_placeholders = pieces.iter().all(|piece| match piece {
+            Piece::Text(text) => text.trim().is_empty(),
+            Piece::Placeholder(_) => true,
+        });
+        if only_placeholders && pieces.len() > 1 && rendered.trim().is_empty() {
+            if lines.peek().is_some_and(|next| next.trim().is_empty()) {
+                lines.next();
+            }
+            continue;
+        }
+        res.push_str(&rendered);
+    }
+
+    let mut res = res.trim().to_string();
+    res.push('\n');
+    res
+}
+
+/// Part of a template line, the name of a placeholder trimmed so that
+/// `{{ name }}` and `{{name}}` are the same.
+#[derive(Debug, PartialEq)]
+enum Piece<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+// an unclosed `{{` is text.
+fn pieces(line: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let mut rest = line;
+    while let Some((text, after)) = rest.split_once("{{") {
+        let Some((name, after)) = after.split_once("}}") else {
+            break;
+        };
+        pieces.push(Piece::Text(text));
+        pieces.push(Piece::Placeholder(name.trim()));
+        rest = after;
+    }
+    pieces.push(Piece::Text(rest));
+
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod check {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            assert!(check(DEFAULT).is_ok());
+            assert!(check("# Results\n{{comparison_table}}").is_ok());
+        }
+
+        #[test]
+        fn unknown() {
+            let actual = check("{{hardware}} {{logo}}");
+
+            assert!(actual.unwrap_err().contains("\"logo\""));
+        }
+    }
+
+    mod render {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = "# {{comparison_table}}\n\n{{websocket}}\n\n{{per_framework}}\n";
+
+            let actual = render(
+                given,
+                &[
+                    ("comparison_table", "table"),
+                    ("websocket", ""),
+                    ("per_framework", "## axum\n"),
+                ],
+            );
+
+            assert_eq!(actual, "# table\n\n## axum\n");
+        }
+
+        #[test]
+        fn spaced() {
+            let actual = render("{{ hardware }}\n", &[("hardware", "8 cores")]);
+
+            assert_eq!(actual, "8 cores\n");
+        }
+
+        #[test]
+        fn keeps_blank_lines() {
+            let given = "```\na\n\n\n\nb\n```\n{{websocket}}\n";
+
+            assert_eq!(
+                render(given, &[("websocket", "")]),
+                "```\na\n\n\n\nb\n```\n"
+            );
+        }
+
+        #[test]
+        fn single_pass() {
+            let given = "{{command}} {{hardware}}\n";
+
+            let actual = render(
+                given,
+                &[("command", "echo {{hardware}}"), ("hardware", "8 cores")],
+            );
+
+            assert_eq!(actual, "echo {{hardware}} 8 cores\n");
+        }
+    }
+}
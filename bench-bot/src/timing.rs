@@ -0,0 +1,55 @@
+use serde::Serialize;
+use std::time::Duration;
+
+/// Where the time of a framework went, phase by phase.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Timings {
+    /// `None` when the member was not built in this run.
+    pub build: Option<Duration>,
+    /// From spawning the server to sending load.
+    pub startup: Duration,
+    /// Every run, ramp step and WebSocket phase.
+    pub benchmark: Duration,
+    /// Before spawning the server, `None` when the member didn't cool
+    /// down, after an unmeasured member or with `--skip-cooldown-first`.
+    pub cooldown: Option<Duration>,
+}
+
+impl Timings {
+    pub fn render(&self) -> String {
+        let secs = |duration: Duration| format!("{:.1}s", duration.as_secs_f64());
+
+        let mut phases = Vec::new();
+        if let Some(build) = self.build {
+            phases.push(format!("build {}", secs(build)));
+        }
+        if let Some(cooldown) = self.cooldown {
+            phases.push(format!("cooldown {}", secs(cooldown)));
+        }
+        phases.push(format!("startup {}", secs(self.startup)));
+        phases.push(format!("benchmark {}", secs(self.benchmark)));
+
+        phases.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod timings {
+        use super::*;
+
+        #[test]
+        fn render() {
+            let given = Timings {
+                build: Some(Duration::from_millis(12_340)),
+                startup: Duration::from_secs(1),
+                benchmark: Duration::from_millis(30_050),
+                cooldown: None,
+            };
+
+            assert_eq!(given.render(), "build 12.3s, startup 1.0s, benchmark 30.1s");
+        }
+    }
+}
@@ -0,0 +1,324 @@
+use crate::report::{Metrics, MetricsError};
+use regex::Regex;
+
+/// Load generator used for every benchmark.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Tool {
+    Rewrk,
+    /// bench-bot's own HTTP/1.1 load generator, one thread per connection.
+    /// Slower than rewrk, but needs nothing installed.
+    Builtin,
+    /// Any tool, run through `--tool-command` and parsed with `--parse-regex`.
+    Custom,
+}
+
+/// Capture groups a custom parser must provide.
+const REQUIRED_CAPTURES: [&str; 1] = ["rps"];
+
+/// Capture groups a custom parser may provide, mapped onto `Metrics`.
+const KNOWN_CAPTURES: [&str; 18] = [
+    "rps",
+    "total",
+    "avg",
+    "stdev",
+    "min",
+    "max",
+    "p50",
+    "p99",
+    "p999",
+    "p9999",
+    "transfer",
+    "transfer_rate",
+    "errors",
+    "timeouts",
+    "opened",
+    "ttfb_avg",
+    "ttfb_p50",
+    "ttfb_p99",
+];
+
+/// Parses the output of a custom tool from the named capture groups of a
+/// user supplied regex.
+#[derive(Debug, Clone)]
+pub struct CustomParser {
+    regex: Regex,
+}
+
+impl CustomParser {
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        let regex = Regex::new(pattern).map_err(|e| format!("invalid --parse-regex: {}", e))?;
+        let names = regex.capture_names().flatten().collect::<Vec<_>>();
+
+        let missing = REQUIRED_CAPTURES
+            .iter()
+            .filter(|name| !names.contains(name))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(format!(
+                "--parse-regex is missing the required capture group(s) {:?}",
+                missing
+            ));
+        }
+
+        let unknown = names
+            .iter()
+            .filter(|name| !KNOWN_CAPTURES.contains(name))
+            .collect::<Vec<_>>();
+        if !unknown.is_empty() {
+            log::warn!(
+                "--parse-regex capture group(s) {:?} are ignored, known groups are {:?}",
+                unknown,
+                KNOWN_CAPTURES
+            );
+        }
+
+        Ok(Self { regex })
+    }
+
+    pub fn parse(&self, output: &str) -> Result<Metrics, MetricsError> {
+        let captures = self
+            .regex
+            .captures(output)
+            .ok_or(MetricsError::ParseError)?;
+
+        Metrics::from_captures(|name| captures.name(name).map(|m| m.as_str().to_string()))
+    }
+}
+
+/// Split `--tool-command` into program and arguments, replacing every
+/// `{name}` placeholder by its value. The template was checked with
+/// `split_words` beforehand.
+pub fn custom_command(template: &str, placeholders: &[(&str, &str)]) -> Vec<String> {
+    split_words(template)
+        .expect("--tool-command is checked at startup")
+        .into_iter()
+        .map(|part| {
+            placeholders
+                .iter()
+                .fold(part.to_string(), |part, (name, value)| {
+                    part.replace(&format!("{{{}}}", name), value)
+                })
+        })
+        .collect()
+}
+
+/// Split `command` into words as a shell would, on whitespace outside of
+/// quotes. Single quotes keep everything literally, double quotes and a
+/// bare backslash escape the next character, no expansion happens.
+pub fn split_words(command: &str) -> Result<Vec<String>, String> {
+    const UNCLOSED_DOUBLE: &str = "--tool-command has an unclosed \"";
+
+    let mut words = Vec::new();
+    // the current word, `None` between words, so that `''` is one.
+    let mut word: Option<String> = None;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\\' => {
+                let escaped = chars.next().ok_or("--tool-command ends with a backslash")?;
+                word.get_or_insert_with(String::new).push(escaped);
+            }
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err("--tool-command has an unclosed '".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            // only these are escaped inside double quotes.
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some(c) => word.extend(['\\', c]),
+                            None => return Err(UNCLOSED_DOUBLE.to_string()),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(UNCLOSED_DOUBLE.to_string()),
+                    }
+                }
+            }
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+
+    Ok(words)
+}
+
+/// Flags generated arguments may repeat, e.g. one `-H` per header.
+const REPEATABLE: [&str; 2] = ["-H", "--header"];
+
+/// Flags of `extra`, the `--tool-arg`s, the generated `command` already
+/// sets, which the tool would see twice.
+pub fn conflicts(command: &[String], extra: &[String]) -> Vec<String> {
+    let flag = |arg: &String| {
+        let flag = arg.split('=').next().unwrap_or_default();
+        (flag.starts_with('-') && !REPEATABLE.contains(&flag)).then(|| flag.to_string())
+    };
+    let generated = command.iter().filter_map(flag).collect::<Vec<_>>();
+
+    extra
+        .iter()
+        .filter_map(flag)
+        .filter(|flag| generated.contains(flag))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod custom_parser {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let given = CustomParser::new(
+                r"Requests/sec:\s+(?P<rps>[\d.]+)[\s\S]*Transfer/sec:\s+(?P<transfer_rate>\S+)",
+            )
+            .unwrap();
+
+            let actual = given.parse("Requests/sec: 407349.68\nTransfer/sec:     49.72MB\n");
+
+            let expect = Metrics::from_captures(|name| match name {
+                "rps" => Some("407349.68".to_string()),
+                "transfer_rate" => Some("49.72MB".to_string()),
+                _ => None,
+            });
+
+            assert_eq!(actual, expect);
+        }
+
+        #[test]
+        fn missing_capture() {
+            let actual = CustomParser::new(r"Latency\s+(?P<avg>\S+)");
+
+            assert!(actual.unwrap_err().contains("\"rps\""));
+        }
+
+        #[test]
+        fn invalid_regex() {
+            assert!(CustomParser::new(r"(?P<rps>").is_err());
+        }
+
+        #[test]
+        fn no_match() {
+            let given = CustomParser::new(r"Requests/sec:\s+(?P<rps>[\d.]+)").unwrap();
+
+            assert_eq!(
+                given.parse("connection refused"),
+                Err(MetricsError::ParseError)
+            );
+        }
+    }
+
+    mod custom_command {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let actual = custom_command(
+                "wrk -t{threads} -c {connections} -d {duration} {url}",
+                &[
+                    ("url", "http://127.0.0.1:3000"),
+                    ("connections", "500"),
+                    ("duration", "30s"),
+                    ("threads", "7"),
+                ],
+            );
+
+            assert_eq!(
+                actual,
+                [
+                    "wrk",
+                    "-t7",
+                    "-c",
+                    "500",
+                    "-d",
+                    "30s",
+                    "http://127.0.0.1:3000"
+                ]
+            );
+        }
+
+        #[test]
+        fn quoted() {
+            let actual = custom_command(
+                "oha -H 'X-Run: {url}' {url}",
+                &[("url", "http://127.0.0.1:3000")],
+            );
+
+            assert_eq!(
+                actual,
+                [
+                    "oha",
+                    "-H",
+                    "X-Run: http://127.0.0.1:3000",
+                    "http://127.0.0.1:3000"
+                ]
+            );
+        }
+    }
+
+    mod split_words {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            assert_eq!(
+                split_words("wrk  -c {connections}\t{url}").unwrap(),
+                ["wrk", "-c", "{connections}", "{url}"]
+            );
+        }
+
+        #[test]
+        fn quoted() {
+            assert_eq!(
+                split_words(r#"oha -H 'Accept: */*' -m "PO\"ST" --body=a\ b ''"#).unwrap(),
+                ["oha", "-H", "Accept: */*", "-m", "PO\"ST", "--body=a b", ""]
+            );
+            assert_eq!(
+                split_words(r#"echo "a\nb" 'it'"s""#).unwrap(),
+                ["echo", "a\\nb", "its"]
+            );
+        }
+
+        #[test]
+        fn unclosed() {
+            assert!(split_words("wrk 'http://x").is_err());
+            assert!(split_words("wrk \"http://x").is_err());
+            assert!(split_words("wrk \\").is_err());
+        }
+    }
+
+    mod conflicts {
+        use super::*;
+
+        #[test]
+        fn ok() {
+            let command = [
+                "rewrk",
+                "-c",
+                "500",
+                "-d",
+                "30s",
+                "-H",
+                "Connection: close",
+                "--http2",
+            ]
+            .map(String::from);
+            let extra = ["--pct", "-H", "X-Trace: 1", "-c=100", "--http2"].map(String::from);
+
+            assert_eq!(conflicts(&command, &extra), ["-c", "--http2"]);
+        }
+    }
+}
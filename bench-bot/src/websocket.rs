@@ -0,0 +1,326 @@
+use crate::stats;
+use serde::Serialize;
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::TcpStream,
+    thread,
+    time::{Duration, Instant},
+};
+
+const WS_HEADER: &str = "| Framework Name | Connections | Messages | Msg/Sec | Latency.Avg | Latency.P50 | Latency.P99 | Latency.Max |";
+const WS_SEPARATOR: &str = "\n|---|---|---|---|---|---|---|---|\n";
+
+/// Payload echoed back by the server for every round trip.
+const MESSAGE: &[u8] = b"Hello, World!";
+
+/// Largest frame payload read, well above any echo, so that a corrupt or
+/// hostile length doesn't allocate gigabytes.
+const MAX_FRAME_LEN: u64 = 16 * 1024 * 1024;
+
+/// Echo round trips measured over persistent WebSocket connections.
+#[derive(Debug, Clone, Serialize)]
+pub struct WsStats {
+    pub connections: usize,
+    pub duration: Duration,
+    /// Round trip latency of every echoed message.
+    latencies: Vec<Duration>,
+}
+
+impl WsStats {
+    fn new(connections: usize, duration: Duration, mut latencies: Vec<Duration>) -> Self {
+        latencies.sort();
+        Self {
+            connections,
+            duration,
+            latencies,
+        }
+    }
+
+    pub fn messages(&self) -> usize {
+        self.latencies.len()
+    }
+
+    /// `p` in `0.0..=1.0`, nearest-rank.
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        stats::percentile(&self.latencies, p)
+    }
+
+    pub fn avg(&self) -> Option<Duration> {
+        let total = self.latencies.iter().sum::<Duration>();
+        u32::try_from(self.latencies.len())
+            .ok()
+            .filter(|len| *len > 0)
+            .map(|len| total / len)
+    }
+
+    pub fn generate_from(stats: &[(String, WsStats)]) -> String {
+        let mut res = String::new();
+
+        res.push_str(WS_HEADER);
+        res.push_str(WS_SEPARATOR);
+
+        let ms = |latency: Option<Duration>| {
+            latency.map_or("-".to_string(), |latency| {
+                format!("{:.2}ms", latency.as_secs_f64() * 1000.0)
+            })
+        };
+
+        for (name, s) in stats {
+            let row = format!(
+                "|{}|{}|{}|{:.2}|{}|{}|{}|{}|",
+                name,
+                s.connections,
+                s.messages(),
+                s.messages() as f64 / s.duration.as_secs_f64(),
+                ms(s.avg()),
+                ms(s.percentile(0.5)),
+                ms(s.percentile(0.99)),
+                ms(s.latencies.last().copied())
+            );
+            res.push_str(&row);
+            res.push('\n');
+        }
+
+        res.pop(); // drop last '\n'
+
+        res
+    }
+}
+
+/// Open `connections` WebSocket connections to `url` (`ws://host:port/path`)
+/// and echo messages over each of them for `duration`.
+pub fn bench(url: &str, connections: usize, duration: Duration) -> io::Result<WsStats> {
+    // fail fast, before spawning every connection, if there is no endpoint.
+    drop(connect(url)?);
+
+    let deadline = Instant::now() + duration;
+
+    let latencies = thread::scope(|scope| {
+        let workers = (0..connections)
+            .map(|_| scope.spawn(|| echo_until(url, deadline)))
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("websocket worker panicked"))
+            .collect::<io::Result<Vec<_>>>()
+    })?;
+
+    Ok(WsStats::new(connections, duration, latencies.concat()))
+}
+
+fn echo_until(url: &str, deadline: Instant) -> io::Result<Vec<Duration>> {
+    let mut stream = connect(url)?;
+    let mut latencies = Vec::new();
+
+    while Instant::now() < deadline {
+        let start = Instant::now();
+        write_frame(&mut stream, MESSAGE)?;
+        read_frame(&mut stream)?;
+        latencies.push(start.elapsed());
+    }
+
+    Ok(latencies)
+}
+
+fn connect(url: &str) -> io::Result<BufReader<TcpStream>> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let path = if path.is_empty() { "/" } else { path };
+
+    let stream = TcpStream::connect(authority)?;
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut stream = BufReader::new(stream);
+    // the key is only there to prove the server speaks websocket, a fixed
+    // one is fine for benchmarking.
+    write!(
+        stream.get_mut(),
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n",
+        path,
+        authority
+    )?;
+
+    let mut status = String::new();
+    stream.read_line(&mut status)?;
+    if status.split_whitespace().nth(1) != Some("101") {
+        return Err(io::Error::other(format!(
+            "websocket upgrade refused: {}",
+            status.trim()
+        )));
+    }
+
+    // skip the remaining response headers.
+    let mut line = String::new();
+    while stream.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+
+    Ok(stream)
+}
+
+/// Write a masked text frame, as clients must.
+fn write_frame(stream: &mut BufReader<impl Read + Write>, payload: &[u8]) -> io::Result<()> {
+    let mask = [0x12, 0x34, 0x56, 0x78];
+    let mut frame = vec![0x81]; // FIN + text
+
+    match payload.len() {
+        len @ 0..=125 => frame.push(0x80 | len as u8),
+        len @ 126..=0xFFFF => {
+            frame.push(0x80 | 126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(0x80 | 127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+
+    frame.extend_from_slice(&mask);
+    frame.extend(payload.iter().zip(mask.iter().cycle()).map(|(b, m)| b ^ m));
+
+    stream.get_mut().write_all(&frame)
+}
+
+/// Read the next data frame, skipping control frames other than close.
+fn read_frame(stream: &mut impl Read) -> io::Result<Vec<u8>> {
+    loop {
+        let mut head = [0; 2];
+        stream.read_exact(&mut head)?;
+
+        let len = match head[1] & 0x7F {
+            126 => {
+                let mut len = [0; 2];
+                stream.read_exact(&mut len)?;
+                u64::from(u16::from_be_bytes(len))
+            }
+            127 => {
+                let mut len = [0; 8];
+                stream.read_exact(&mut len)?;
+                u64::from_be_bytes(len)
+            }
+            len => u64::from(len),
+        };
+
+        if len > MAX_FRAME_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "websocket frame of {} bytes, over the {} bytes limit",
+                    len, MAX_FRAME_LEN
+                ),
+            ));
+        }
+
+        let mut mask = [0; 4];
+        if head[1] & 0x80 != 0 {
+            stream.read_exact(&mut mask)?;
+        }
+
+        let mut payload = vec![0; len as usize];
+        stream.read_exact(&mut payload)?;
+        payload
+            .iter_mut()
+            .zip(mask.iter().cycle())
+            .for_each(|(b, m)| *b ^= m);
+
+        match head[0] & 0x0F {
+            0x1 | 0x2 => return Ok(payload),
+            0x8 => {
+                return Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "websocket closed",
+                ))
+            }
+            _ => continue, // ping/pong
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    mod frame {
+        use super::*;
+
+        #[test]
+        fn roundtrip() {
+            let mut written = BufReader::new(Cursor::new(Vec::new()));
+            write_frame(&mut written, MESSAGE).unwrap();
+
+            let written = written.into_inner().into_inner();
+            // masked client frame
+            assert_eq!(written[0], 0x81);
+            assert_eq!(written[1], 0x80 | MESSAGE.len() as u8);
+
+            let actual = read_frame(&mut Cursor::new(written)).unwrap();
+
+            assert_eq!(actual, MESSAGE);
+        }
+
+        #[test]
+        fn skips_ping() {
+            let given = [0x89, 0x00, 0x81, 0x02, b'o', b'k'];
+            let actual = read_frame(&mut Cursor::new(given)).unwrap();
+
+            assert_eq!(actual, b"ok");
+        }
+
+        #[test]
+        fn too_long() {
+            let mut given = vec![0x82, 0x7F];
+            given.extend_from_slice(&u64::MAX.to_be_bytes());
+            let actual = read_frame(&mut Cursor::new(given)).unwrap_err();
+
+            assert_eq!(actual.kind(), io::ErrorKind::InvalidData);
+        }
+
+        #[test]
+        fn close() {
+            let given = [0x88, 0x00];
+            let actual = read_frame(&mut Cursor::new(given));
+
+            assert!(actual.is_err());
+        }
+    }
+
+    mod ws_stats {
+        use super::*;
+
+        #[test]
+        fn percentile() {
+            let given = WsStats::new(
+                1,
+                Duration::from_secs(1),
+                (1..=100).rev().map(Duration::from_millis).collect(),
+            );
+
+            assert_eq!(given.messages(), 100);
+            assert_eq!(given.percentile(0.5), Some(Duration::from_millis(50)));
+            assert_eq!(given.percentile(0.99), Some(Duration::from_millis(99)));
+            assert_eq!(given.avg(), Some(Duration::from_micros(50_500)));
+        }
+
+        #[test]
+        fn generate() {
+            let given = vec![(
+                "axum".to_string(),
+                WsStats::new(2, Duration::from_secs(2), vec![Duration::from_millis(1); 4]),
+            )];
+
+            let actual = WsStats::generate_from(&given);
+
+            let expect = r#"
+| Framework Name | Connections | Messages | Msg/Sec | Latency.Avg | Latency.P50 | Latency.P99 | Latency.Max |
+|---|---|---|---|---|---|---|---|
+|axum|2|4|2.00|1.00ms|1.00ms|1.00ms|1.00ms|
+"#.trim();
+
+            assert_eq!(actual, expect);
+        }
+    }
+}
@@ -3,7 +3,7 @@ use actix_web::{web, App, HttpServer};
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> std::io::Result<()> {
     HttpServer::new(|| App::new().service(web::resource("/").to(index)))
-        .bind("127.0.0.1:3000")?
+        .bind(("127.0.0.1", port()))?
         .run()
         .await
 }
@@ -11,3 +11,10 @@ async fn main() -> std::io::Result<()> {
 async fn index() -> &'static str {
     "Hello, World!"
 }
+
+fn port() -> u16 {
+    std::env::var("BENCH_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(3000)
+}
@@ -1,7 +1,14 @@
 use astra::{Body, Response, Server};
 
 fn main() {
-    Server::bind("127.0.0.1:3000")
+    Server::bind(("127.0.0.1", port()))
         .serve(|_req| Response::new(Body::new("Hello, World!")))
         .unwrap()
 }
+
+fn port() -> u16 {
+    std::env::var("BENCH_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(3000)
+}
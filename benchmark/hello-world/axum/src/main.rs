@@ -6,7 +6,7 @@ async fn main() {
     let app = Router::new().route("/", get(handler));
 
     // run it
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:3000")
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port()))
         .await
         .unwrap();
     axum::serve(listener, app).await.unwrap();
@@ -14,4 +14,11 @@ async fn main() {
 
 async fn handler() -> &'static str {
     "Hello, World!"
-}
\ No newline at end of file
+}
+
+fn port() -> u16 {
+    std::env::var("BENCH_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(3000)
+}
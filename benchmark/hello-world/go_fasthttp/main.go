@@ -2,6 +2,8 @@ package main
 
 import (
 	"fmt"
+	"os"
+	"runtime/debug"
 
 	"github.com/valyala/fasthttp"
 )
@@ -10,11 +12,36 @@ func helloHandler(ctx *fasthttp.RequestCtx) {
 	ctx.WriteString("Hello, World!")
 }
 
+// gcHandler collects garbage and returns it to the OS, for bench-bot's
+// --gc-endpoint.
+func gcHandler(ctx *fasthttp.RequestCtx) {
+	debug.FreeOSMemory()
+}
+
+// router serves /debug/gc apart from the benchmarked handler, as
+// http.HandleFunc does for the net/http member.
+func router(ctx *fasthttp.RequestCtx) {
+	switch string(ctx.Path()) {
+	case "/debug/gc":
+		gcHandler(ctx)
+	default:
+		helloHandler(ctx)
+	}
+}
+
 func main() {
-	fmt.Println("Starting server on :8080")
+	addr := ":" + port()
+	fmt.Println("Starting server on " + addr)
 
 	// Using fasthttp server
-	if err := fasthttp.ListenAndServe(":3000", helloHandler); err != nil {
+	if err := fasthttp.ListenAndServe(addr, router); err != nil {
 		fmt.Println("Server failed:", err)
 	}
 }
+
+func port() string {
+	if port := os.Getenv("BENCH_PORT"); port != "" {
+		return port
+	}
+	return "3000"
+}
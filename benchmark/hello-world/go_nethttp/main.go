@@ -3,16 +3,33 @@ package main
 import (
 	"fmt"
 	"net/http"
+	"os"
+	"runtime/debug"
 )
 
 func helloHandler(w http.ResponseWriter, r *http.Request) {
 	fmt.Fprintln(w, "Hello, World!")
 }
 
+// gcHandler collects garbage and returns it to the OS, for bench-bot's
+// --gc-endpoint.
+func gcHandler(w http.ResponseWriter, r *http.Request) {
+	debug.FreeOSMemory()
+}
+
 func main() {
 	http.HandleFunc("/", helloHandler)
-	fmt.Println("Starting server on :8080")
-	if err := http.ListenAndServe(":3000", nil); err != nil {
+	http.HandleFunc("/debug/gc", gcHandler)
+	addr := ":" + port()
+	fmt.Println("Starting server on " + addr)
+	if err := http.ListenAndServe(addr, nil); err != nil {
 		fmt.Println("Server failed:", err)
 	}
 }
+
+func port() string {
+	if port := os.Getenv("BENCH_PORT"); port != "" {
+		return port
+	}
+	return "3000"
+}
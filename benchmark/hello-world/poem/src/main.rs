@@ -10,8 +10,15 @@ fn hello() -> String {
 #[tokio::main]
 async fn main() -> Result<(), std::io::Error> {
     let app = Route::new().at("/", get(hello));
-    Server::new(TcpListener::bind("127.0.0.1:3000"))
+    Server::new(TcpListener::bind(format!("127.0.0.1:{}", port())))
         .name("hello-world")
         .run(app)
         .await
 }
+
+fn port() -> u16 {
+    std::env::var("BENCH_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(3000)
+}
@@ -7,5 +7,14 @@ fn hello() -> &'static str {
 
 #[launch]
 fn rocket() -> _ {
-    rocket::build().mount("/", routes![hello])
+    let figment = rocket::Config::figment().merge(("port", port()));
+
+    rocket::custom(figment).mount("/", routes![hello])
+}
+
+fn port() -> u16 {
+    std::env::var("BENCH_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(3000)
 }